@@ -0,0 +1,95 @@
+// Target program of `testing::TargetFixture`, compiled at runtime.
+// It prints addresses of variables with known values, then answers
+// `get <name>` requests on stdin with current values until stdin is closed.
+
+use std::io::{self, BufRead, Write};
+use std::ptr::addr_of;
+use std::thread;
+
+#[repr(C)]
+pub struct HeapObject {
+  padding: [u64; 2],
+  value: u32,
+}
+
+#[no_mangle]
+pub static mut TRICKSTER_INTEGER: u32 = 1337;
+#[no_mangle]
+pub static mut TRICKSTER_FLOAT: f32 = 13.37;
+#[no_mangle]
+pub static mut TRICKSTER_STRING: [u8; 16] = *b"trickster\0\0\0\0\0\0\0";
+#[no_mangle]
+pub static mut TRICKSTER_SIGNATURE: [u8; 8] = [0xDE, 0xAD, 0xBE, 0xEF, 0x13, 0x37, 0xC0, 0xDE];
+#[no_mangle]
+pub static mut TRICKSTER_POINTER: *mut HeapObject = std::ptr::null_mut();
+
+fn main() {
+  let object = Box::into_raw(Box::new(HeapObject {
+    padding: [0; 2],
+    value: 4242,
+  }));
+  unsafe {
+    TRICKSTER_POINTER = object;
+  }
+
+  for _ in 0..2 {
+    thread::spawn(|| loop {
+      thread::park();
+    });
+  }
+
+  let stdout = io::stdout();
+  let mut stdout = stdout.lock();
+  unsafe {
+    writeln!(
+      stdout,
+      "integer={:#x}",
+      addr_of!(TRICKSTER_INTEGER) as usize
+    )
+    .unwrap();
+    writeln!(stdout, "float={:#x}", addr_of!(TRICKSTER_FLOAT) as usize).unwrap();
+    writeln!(stdout, "string={:#x}", addr_of!(TRICKSTER_STRING) as usize).unwrap();
+    writeln!(
+      stdout,
+      "signature={:#x}",
+      addr_of!(TRICKSTER_SIGNATURE) as usize
+    )
+    .unwrap();
+    writeln!(
+      stdout,
+      "pointer={:#x}",
+      addr_of!(TRICKSTER_POINTER) as usize
+    )
+    .unwrap();
+    writeln!(
+      stdout,
+      "heap_value={:#x}",
+      addr_of!((*object).value) as usize
+    )
+    .unwrap();
+  }
+  writeln!(stdout, "ready").unwrap();
+  stdout.flush().unwrap();
+
+  for line in io::stdin().lock().lines() {
+    let line = match line {
+      Ok(line) => line,
+      Err(_) => break,
+    };
+    let value = unsafe {
+      match line.trim().strip_prefix("get ") {
+        Some("integer") => addr_of!(TRICKSTER_INTEGER).read_volatile().to_string(),
+        Some("float") => addr_of!(TRICKSTER_FLOAT).read_volatile().to_string(),
+        Some("string") => {
+          let string = addr_of!(TRICKSTER_STRING).read_volatile();
+          let length = string.iter().position(|byte| *byte == 0).unwrap_or(string.len());
+          String::from_utf8_lossy(&string[..length]).into_owned()
+        }
+        Some("heap_value") => addr_of!((*object).value).read_volatile().to_string(),
+        _ => String::from("unknown"),
+      }
+    };
+    writeln!(stdout, "{}", value).unwrap();
+    stdout.flush().unwrap();
+  }
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "serde-support")]
+use std::{fs, io, path};
+
+use super::{Process, Signature, SignatureSet};
+
+/// Location of 32-bit displacement relative to the end of instruction,  
+/// see `Process::resolve_rip_relative()`.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct RipRelative {
+  pub displacement_offset: usize,
+  pub instruction_length: usize,
+}
+
+/// Recipe describing how to find named address in process memory,  
+/// used by [`AddressTable`].
+///
+/// [`AddressTable`]: struct.AddressTable.html
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize),
+  serde(untagged)
+)]
+pub enum AddressRecipe {
+  /// First match of IDA-style `pattern` (see `Signature::new()`), searched in module  
+  /// `module` or in all readable memory if it is [`None`]. Displacement of instruction  
+  /// at the match is resolved if `rip_relative` is set, then `adjustment` is added,  
+  /// and finally pointer at the result is read if `deref` is **true**.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  Signature {
+    pattern: String,
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    module: Option<String>,
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    rip_relative: Option<RipRelative>,
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    adjustment: isize,
+    #[cfg_attr(feature = "serde-support", serde(default))]
+    deref: bool,
+  },
+  /// Base address of module `module` plus `offset`.
+  ModuleOffset { module: String, offset: usize },
+}
+
+/// Set of named address recipes, which separates reverse engineered offsets  
+/// and signatures from tool code. Recipes are resolved into addresses with  
+/// `resolve()`, usually right after attaching to the process. With  
+/// `serde-support` feature enabled, table can be loaded from JSON file,  
+/// which maps names to recipes (see [`AddressRecipe`]), e.g.  
+/// `{"health": {"module": "game", "offset": 1715024}, "local_player": {"pattern":  
+/// "48 8B 05 ?? ?? ?? ?? 48 85 C0", "module": "game", "rip_relative":  
+/// {"displacement_offset": 3, "instruction_length": 7}, "deref": true}}`.
+///
+/// [`AddressRecipe`]: enum.AddressRecipe.html
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{AddressRecipe, AddressTable, Process};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.parse_maps()?;
+///
+///   let mut table = AddressTable::new();
+///   table.add(
+///     "health",
+///     AddressRecipe::ModuleOffset {
+///       module: "game".to_string(),
+///       offset: 0x1a2b50,
+///     },
+///   );
+///
+///   let addresses = table.resolve(&ctx)?;
+///   println!("health: {}", ctx.read::<i32>(addresses["health"])?);
+///   Ok(())
+/// }
+/// ```
+/// This prints output like:  
+/// `health: 100`
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize),
+  serde(transparent)
+)]
+pub struct AddressTable {
+  recipes: BTreeMap<String, AddressRecipe>,
+}
+
+impl AddressTable {
+  /// AddressTable object constructor. Creates empty table.
+  pub fn new() -> AddressTable {
+    AddressTable::default()
+  }
+
+  /// Adds `recipe` of address named `name`, replacing previous recipe of that name.
+  pub fn add(&mut self, name: &str, recipe: AddressRecipe) -> &mut AddressTable {
+    self.recipes.insert(name.to_string(), recipe);
+    self
+  }
+
+  /// Returns recipes of the table by name.
+  pub fn recipes(&self) -> &BTreeMap<String, AddressRecipe> {
+    &self.recipes
+  }
+
+  /// Loads table from JSON file located at `path`.
+  #[cfg(feature = "serde-support")]
+  pub fn load<P: AsRef<path::Path>>(path: P) -> Result<AddressTable> {
+    let file = fs::File::open(path)?;
+    serde_json::from_reader(io::BufReader::new(file))
+      .map_err(|error| anyhow!("Could not load address table ({}).", error))
+  }
+
+  /// Saves table as JSON to file located at `path`.
+  #[cfg(feature = "serde-support")]
+  pub fn save<P: AsRef<path::Path>>(&self, path: P) -> Result<()> {
+    let file = fs::File::create(path)?;
+    serde_json::to_writer_pretty(io::BufWriter::new(file), self)
+      .map_err(|error| anyhow!("Could not save address table ({}).", error))
+  }
+
+  /// Resolves all recipes of the table in `process` and returns addresses by name.  
+  /// Signatures are searched in a single pass over memory (see `Process::resolve_signatures()`).  
+  /// If any address could not be resolved, [`Err`] listing all of them is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `resolve();`.
+  pub fn resolve(&self, process: &Process) -> Result<HashMap<String, usize>> {
+    let mut signatures = SignatureSet::new();
+    for (name, recipe) in &self.recipes {
+      if let AddressRecipe::Signature {
+        pattern, module, ..
+      } = recipe
+      {
+        let signature = Signature::new(pattern)
+          .map_err(|error| anyhow!("Could not parse signature of {} ({}).", name, error))?;
+        match module {
+          Some(module) => signatures.add_in_module(name, module, signature),
+          None => signatures.add(name, signature),
+        };
+      }
+    }
+    let matches = process.resolve_signatures(&signatures)?;
+
+    let mut addresses: HashMap<String, usize> = HashMap::new();
+    let mut errors: Vec<String> = Vec::new();
+    for (name, recipe) in &self.recipes {
+      let address = match recipe {
+        AddressRecipe::ModuleOffset { module, offset } => {
+          process.module(module).map(|module| module.base().wrapping_add(*offset))
+        }
+        AddressRecipe::Signature {
+          rip_relative,
+          adjustment,
+          deref,
+          ..
+        } => matches.get(name).and_then(|address| {
+          resolve_signature_match(process, address, rip_relative, *adjustment, *deref)
+        }),
+      };
+
+      match address {
+        Ok(address) => {
+          addresses.insert(name.clone(), address);
+        }
+        Err(error) => errors.push(format!("{}: {}", name, error)),
+      }
+    }
+
+    if !errors.is_empty() {
+      return Err(anyhow!(
+        "Could not resolve address table ({}).",
+        errors.join(" ")
+      ));
+    }
+    Ok(addresses)
+  }
+}
+
+/// Applies steps of `AddressRecipe::Signature` to `address` of signature match.
+fn resolve_signature_match(
+  process: &Process,
+  address: usize,
+  rip_relative: &Option<RipRelative>,
+  adjustment: isize,
+  deref: bool,
+) -> Result<usize> {
+  let address = match rip_relative {
+    Some(operand) => process.resolve_rip_relative(
+      address,
+      operand.displacement_offset,
+      operand.instruction_length,
+    )?,
+    None => address,
+  };
+  let address = address.wrapping_add(adjustment as usize);
+
+  if deref {
+    process.read::<usize>(address)
+  } else {
+    Ok(address)
+  }
+}
@@ -0,0 +1,320 @@
+use anyhow::Result;
+use nix::errno::Errno;
+use std::mem;
+
+use super::pagemap::page_size;
+use super::{RegionPermissions, TracedProcess};
+
+/// Flag of **mmap(2)** which makes it fail instead of replacing existing mapping.
+const MAP_FIXED_NOREPLACE: libc::c_int = 0x10_0000;
+/// Lowest address memory is allocated at, default value of `vm.mmap_min_addr`.
+const MINIMAL_MAPPING_ADDRESS: usize = 0x1_0000;
+/// Maximal distance between allocation made by `remote_mmap_near()` and its target,  
+/// slightly below 2 GB, so 32-bit displacements from code around target reach it.
+const NEAR_DISTANCE: usize = 0x7fff_0000;
+/// Number of unmapped gaps `remote_mmap_near()` tries to allocate memory in.
+const NEAR_ALLOCATION_ATTEMPTS: usize = 16;
+
+/// Memory allocated in traced process with `TracedProcess::remote_mmap()`.  
+/// Memory is unmapped when allocation is dropped, unless `leak()` is called.
+pub struct RemoteAllocation<'a> {
+  traced: &'a TracedProcess,
+  address: usize,
+  size: usize,
+}
+
+impl<'a> RemoteAllocation<'a> {
+  /// Returns address of the allocated memory in traced process.
+  pub fn address(&self) -> usize {
+    self.address
+  }
+
+  /// Returns size of the allocated memory in bytes.
+  pub fn size(&self) -> usize {
+    self.size
+  }
+
+  /// Unmaps the memory, returning [`Err`] if it could not be unmapped.  
+  /// Dropping the allocation does the same, but ignores errors.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn free(self) -> Result<()> {
+    let result = self.unmap();
+    mem::forget(self);
+    result
+  }
+
+  /// Keeps the memory mapped in traced process and returns its address,  
+  /// e.g. when it is used by code injected into the process.
+  pub fn leak(self) -> usize {
+    let address = self.address;
+    mem::forget(self);
+    address
+  }
+
+  fn unmap(&self) -> Result<()> {
+    let result = self
+      .traced
+      .remote_syscall(libc::SYS_munmap, &[self.address as u64, self.size as u64])?;
+
+    if result < 0 {
+      return Err(anyhow!(
+        "Could not unmap remote memory at {:#x} ({}).",
+        self.address,
+        Errno::from_i32(-result as i32)
+      ));
+    }
+    Ok(())
+  }
+}
+
+impl<'a> Drop for RemoteAllocation<'a> {
+  fn drop(&mut self) {
+    let _ = self.unmap();
+  }
+}
+
+/// Bytes placed in memory of traced process with `RemoteBuffer::place()`,  
+/// e.g. string passed to function called with `TracedProcess::call_function()`.  
+/// Memory is unmapped when buffer is dropped, unless `leak()` is called.
+pub struct RemoteBuffer<'a> {
+  allocation: RemoteAllocation<'a>,
+  length: usize,
+}
+
+impl<'a> RemoteBuffer<'a> {
+  /// Allocates readable and writeable memory in `traced` process  
+  /// with `TracedProcess::remote_mmap()` and writes `bytes` to it.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RemoteArg, RemoteBuffer};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   let path = RemoteBuffer::place(&traced, b"/tmp/injected.so\0")?;
+  ///   let dlopen_address = 0x7f3e1c2f1f30;
+  ///   let handle = traced.call_function(
+  ///     dlopen_address,
+  ///     &[RemoteArg::Int(path.address() as u64), RemoteArg::Int(2)],
+  ///   )?;
+  ///   println!("handle: {:#x}", handle);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `handle: 0x55d4c1a4f6c0`
+  pub fn place(traced: &'a TracedProcess, bytes: &[u8]) -> Result<RemoteBuffer<'a>> {
+    let permissions = RegionPermissions::RW;
+    // Memory can not be mapped with zero size.
+    let allocation = traced.remote_mmap(bytes.len().max(1), &permissions)?;
+    traced.write_memory_bytes(allocation.address(), bytes)?;
+
+    Ok(RemoteBuffer {
+      allocation,
+      length: bytes.len(),
+    })
+  }
+
+  /// Places `string` with terminating null byte in `traced` process,  
+  /// see `place()`.
+  pub fn place_str(traced: &'a TracedProcess, string: &str) -> Result<RemoteBuffer<'a>> {
+    let mut bytes = Vec::with_capacity(string.len() + 1);
+    bytes.extend_from_slice(string.as_bytes());
+    bytes.push(0);
+    RemoteBuffer::place(traced, &bytes)
+  }
+
+  /// Returns address of the placed bytes in traced process.
+  pub fn address(&self) -> usize {
+    self.allocation.address()
+  }
+
+  /// Returns number of the placed bytes.
+  pub fn len(&self) -> usize {
+    self.length
+  }
+
+  /// Returns `true` if no bytes were placed.
+  pub fn is_empty(&self) -> bool {
+    self.length == 0
+  }
+
+  /// Unmaps the memory, see `RemoteAllocation::free()`.
+  pub fn free(self) -> Result<()> {
+    self.allocation.free()
+  }
+
+  /// Keeps the memory mapped in traced process and returns its address,  
+  /// see `RemoteAllocation::leak()`.
+  pub fn leak(self) -> usize {
+    self.allocation.leak()
+  }
+}
+
+impl TracedProcess {
+  /// Allocates `size` bytes of memory in the process with `permissions`,  
+  /// by executing **mmap(2)** in it (see `remote_syscall()`). Memory is  
+  /// private, anonymous and zero-filled. `shared` field of `permissions`  
+  /// is ignored.
+  ///
+  /// Returned [`RemoteAllocation`] unmaps the memory when dropped,  
+  /// use `RemoteAllocation::leak()` to keep it mapped.
+  ///
+  /// [`RemoteAllocation`]: struct.RemoteAllocation.html
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RegionPermissions};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   let permissions = RegionPermissions::RW;
+  ///   let allocation = traced.remote_mmap(0x1000, &permissions)?;
+  ///   traced.write_memory_bytes(allocation.address(), b"/tmp/injected.so\0")?;
+  ///   println!("allocated at {:#x}", allocation.address());
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `allocated at 0x7f0c8e6a7000`
+  pub fn remote_mmap(
+    &self,
+    size: usize,
+    permissions: &RegionPermissions,
+  ) -> Result<RemoteAllocation<'_>> {
+    self.remote_mmap_at(0, size, permissions, 0)
+  }
+
+  /// Allocates memory like `remote_mmap()`, but within ±2 GB of `target` address,  
+  /// so code at `target` can reach it with 32-bit relative displacement, e.g. with  
+  /// 5 byte `jmp rel32` on x86_64. Unmapped gaps between memory regions of the process  
+  /// (read from `/proc/\[pid\]/maps`) are tried in order of distance from `target`.  
+  /// If there is no suitable gap, or memory could not be allocated in any of them,  
+  /// [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RegionPermissions};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let target = ctx.resolve_symbol("game", "update_health")?;
+  ///   let traced = ctx.attach()?;
+  ///   let permissions = RegionPermissions::RX;
+  ///   let allocation = traced.remote_mmap_near(target, 0x1000, &permissions)?;
+  ///   println!("allocated at {:#x}", allocation.address());
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `allocated at 0x55d4bee3e000`
+  pub fn remote_mmap_near(
+    &self,
+    target: usize,
+    size: usize,
+    permissions: &RegionPermissions,
+  ) -> Result<RemoteAllocation<'_>> {
+    let page_size = page_size();
+    let aligned_size = size.saturating_add(page_size - 1) & !(page_size - 1);
+    let aligned_target = target & !(page_size - 1);
+
+    let mut candidates: Vec<usize> = Vec::new();
+    let mut previous_end = MINIMAL_MAPPING_ADDRESS;
+    for region in self.iter_maps()? {
+      let region = region?;
+      if let Some(address) = near_address(previous_end, region.start, aligned_target, aligned_size)
+      {
+        candidates.push(address);
+      }
+      previous_end = previous_end.max(region.end);
+    }
+    candidates.sort_by_key(|address| address.abs_diff(target));
+
+    for address in candidates.into_iter().take(NEAR_ALLOCATION_ATTEMPTS) {
+      // Gap can be taken by the process in the meantime, so failures are not fatal.
+      if let Ok(allocation) = self.remote_mmap_at(address, size, permissions, MAP_FIXED_NOREPLACE) {
+        if allocation.address() == address {
+          return Ok(allocation);
+        }
+        // Kernels older than 4.17 ignore MAP_FIXED_NOREPLACE and treat address as a hint.
+        allocation.free()?;
+      }
+    }
+
+    Err(anyhow!(
+      "Could not allocate {} bytes of remote memory near {:#x} (no free address range within 2 GB).",
+      size,
+      target
+    ))
+  }
+
+  /// Allocates memory like `remote_mmap()`, preferably at `address`, passing `flags`  
+  /// to **mmap(2)** in addition to **MAP_PRIVATE** and **MAP_ANONYMOUS**.
+  fn remote_mmap_at(
+    &self,
+    address: usize,
+    size: usize,
+    permissions: &RegionPermissions,
+    flags: libc::c_int,
+  ) -> Result<RemoteAllocation<'_>> {
+    let result = self.remote_syscall(
+      libc::SYS_mmap,
+      &[
+        address as u64,
+        size as u64,
+        permissions.protection_flags() as u64,
+        (libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | flags) as u64,
+        -1i64 as u64,
+        0,
+      ],
+    )?;
+
+    // Values in this range are negated error numbers, not addresses.
+    if (-4095..0).contains(&result) {
+      return Err(anyhow!(
+        "Could not allocate {} bytes of remote memory ({}).",
+        size,
+        Errno::from_i32(-result as i32)
+      ));
+    }
+
+    Ok(RemoteAllocation {
+      traced: self,
+      address: result as usize,
+      size,
+    })
+  }
+}
+
+/// Returns address in unmapped gap from `start` to `end` closest to page aligned  
+/// `target`, at which `size` bytes can be mapped within `NEAR_DISTANCE` of `target`.
+fn near_address(start: usize, end: usize, target: usize, size: usize) -> Option<usize> {
+  if end < start.checked_add(size)? {
+    return None;
+  }
+
+  let address = if end <= target {
+    end - size
+  } else if start >= target {
+    start
+  } else {
+    target.min(end - size)
+  };
+
+  if address.abs_diff(target) <= NEAR_DISTANCE && (address + size).abs_diff(target) <= NEAR_DISTANCE
+  {
+    Some(address)
+  } else {
+    None
+  }
+}
@@ -0,0 +1,281 @@
+use anyhow::Result;
+use bytemuck::Pod;
+use std::collections::HashMap;
+
+use super::Process;
+
+/// Size of pages `CachedReader` caches memory in.
+const CACHE_PAGE_SIZE: usize = 0x1000;
+
+/// Cached page together with value of access counter from its last use.
+struct CachedPage {
+  bytes: Vec<u8>,
+  last_used: u64,
+}
+
+/// Reader of remote process memory which caches it in 4 KB pages, so repeated  
+/// reads of the same memory (e.g. following pointer chains every frame) cost  
+/// one system call per page instead of one per read. At most `capacity` pages  
+/// are cached, the least recently used page is evicted when more are needed.
+///
+/// Cached memory is not updated when the process changes it, so the cache  
+/// should be invalidated with `invalidate()` (e.g. once per frame),  
+/// or with `invalidate_range()` after writing memory.
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{CachedReader, Process};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let ctx = Process::new("current_process_name")?;
+///   let mut reader = CachedReader::new(&ctx, 256);
+///
+///   loop {
+///     for entity in 0..64 {
+///       let health_address = reader.resolve_pointer_chain(0x601040, &[0x10, entity * 8, 0x30])?;
+///       println!("entity {} health: {}", entity, reader.read::<i32>(health_address)?);
+///     }
+///     reader.invalidate();
+///     # break;
+///   }
+///   Ok(())
+/// }
+/// ```
+pub struct CachedReader<'a> {
+  process: &'a Process,
+  capacity: usize,
+  pages: HashMap<usize, CachedPage>,
+  access_counter: u64,
+}
+
+impl<'a> CachedReader<'a> {
+  /// CachedReader object constructor. Creates empty cache of memory  
+  /// of `process` holding at most `capacity` pages (minimum one).
+  pub fn new(process: &'a Process, capacity: usize) -> CachedReader<'a> {
+    CachedReader {
+      process,
+      capacity: capacity.max(1),
+      pages: HashMap::new(),
+      access_counter: 0,
+    }
+  }
+
+  /// Returns maximal number of cached pages.
+  pub fn capacity(&self) -> usize {
+    self.capacity
+  }
+
+  /// Returns number of currently cached pages.
+  pub fn cached_pages(&self) -> usize {
+    self.pages.len()
+  }
+
+  /// Reads `length` bytes at `address`, see `Process::read_memory_bytes()`.  
+  /// Pages which are not cached yet are read from the process. If any  
+  /// of the pages could not be read, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn read_memory_bytes(&mut self, address: usize, length: usize) -> Result<Vec<u8>> {
+    let mut buffer = vec![0u8; length];
+    self.read_memory_into(address, &mut buffer)?;
+    Ok(buffer)
+  }
+
+  /// Reads value of generic type (`T`) at `address`, see `Process::read()`.
+  pub fn read<T: Pod>(&mut self, address: usize) -> Result<T> {
+    let mut value = T::zeroed();
+    self.read_memory_into(address, bytemuck::bytes_of_mut(&mut value))?;
+    Ok(value)
+  }
+
+  /// Resolves multi-level pointer path starting at `base`, reading pointers  
+  /// through the cache, see `Process::resolve_pointer_chain()`.
+  pub fn resolve_pointer_chain(&mut self, base: usize, offsets: &[usize]) -> Result<usize> {
+    let mut address = base;
+
+    for (level, offset) in offsets.iter().enumerate() {
+      let pointer_address = address.wrapping_add(*offset);
+
+      if level == offsets.len() - 1 {
+        return Ok(pointer_address);
+      }
+
+      address = self.read::<usize>(pointer_address).map_err(|error| {
+        anyhow!(
+          "Pointer chain broke at level {} (could not dereference {:#x}: {}).",
+          level,
+          pointer_address,
+          error
+        )
+      })?;
+
+      if address == 0 {
+        return Err(anyhow!(
+          "Pointer chain broke at level {} (null pointer at {:#x}).",
+          level,
+          pointer_address
+        ));
+      }
+    }
+
+    Ok(address)
+  }
+
+  /// Removes all pages from the cache, so following reads see current memory.
+  pub fn invalidate(&mut self) {
+    self.pages.clear();
+  }
+
+  /// Removes pages overlapping memory from `start` to `end` (exclusive) from the cache.
+  pub fn invalidate_range(&mut self, start: usize, end: usize) {
+    let first_page = start & !(CACHE_PAGE_SIZE - 1);
+    self.pages.retain(|page, _| *page < first_page || *page >= end);
+  }
+
+  /// Fills `buffer` with memory at `address`, page by page.
+  fn read_memory_into(&mut self, address: usize, buffer: &mut [u8]) -> Result<()> {
+    let mut copied = 0;
+
+    while copied < buffer.len() {
+      let current = address.wrapping_add(copied);
+      let page = current & !(CACHE_PAGE_SIZE - 1);
+      let page_offset = current - page;
+      let length = (CACHE_PAGE_SIZE - page_offset).min(buffer.len() - copied);
+
+      let bytes = self.page(page)?;
+      buffer[copied..copied + length].copy_from_slice(&bytes[page_offset..page_offset + length]);
+      copied += length;
+    }
+
+    Ok(())
+  }
+
+  /// Returns cached page at `page` address, reading it from the process if needed.
+  fn page(&mut self, page: usize) -> Result<&[u8]> {
+    self.access_counter += 1;
+
+    if !self.pages.contains_key(&page) {
+      let bytes = self.process.read_memory_bytes(page, CACHE_PAGE_SIZE)?;
+      if self.pages.len() >= self.capacity {
+        self.evict_least_recently_used();
+      }
+      self.pages.insert(
+        page,
+        CachedPage {
+          bytes,
+          last_used: 0,
+        },
+      );
+    }
+
+    let cached = self.pages.get_mut(&page).unwrap();
+    cached.last_used = self.access_counter;
+    Ok(&cached.bytes)
+  }
+
+  fn evict_least_recently_used(&mut self) {
+    let least_recently_used = self
+      .pages
+      .iter()
+      .min_by_key(|(_, cached)| cached.last_used)
+      .map(|(page, _)| *page);
+
+    if let Some(page) = least_recently_used {
+      self.pages.remove(&page);
+    }
+  }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+  use super::super::{MockMemory, Process};
+  use super::CachedReader;
+
+  /// Start of heap of process returned by `process_with_pages()`.
+  const HEAP: usize = 0x10000000;
+
+  /// Returns process with three pages of heap, with `page` value  
+  /// at offset `0x10` of each page, followed by page which can not be read.
+  fn process_with_pages() -> Process {
+    let mut heap = vec![0; 0x4000];
+    for page in 0..3u32 {
+      let offset = page as usize * 0x1000 + 0x10;
+      heap[offset..offset + 4].copy_from_slice(&page.to_ne_bytes());
+    }
+    heap[0xffe..0x1002].copy_from_slice(&[1, 2, 3, 4]);
+    MockMemory::new()
+      .region(HEAP, "rw-p", Some("[heap]"), heap)
+      .hole(HEAP + 0x3000, 0x1000)
+      .into_process("game")
+      .unwrap()
+  }
+
+  /// Overwrites `page` value of each page in the process, see `process_with_pages()`.
+  fn write_pages(process: &Process, value: u32) {
+    for page in 0..3 {
+      process.write::<u32>(HEAP + page * 0x1000 + 0x10, &value).unwrap();
+    }
+  }
+
+  #[test]
+  fn reads_across_pages() {
+    let process = process_with_pages();
+    let mut reader = CachedReader::new(&process, 4);
+
+    assert_eq!(
+      reader.read_memory_bytes(HEAP + 0xffe, 4).unwrap(),
+      vec![1, 2, 3, 4]
+    );
+    assert_eq!(reader.cached_pages(), 2);
+    assert_eq!(reader.read::<u32>(HEAP + 0x1010).unwrap(), 1);
+    assert_eq!(reader.cached_pages(), 2);
+
+    assert!(reader.read::<u32>(HEAP + 0x2ffe).is_err());
+    assert!(reader.read::<u32>(HEAP + 0x3010).is_err());
+  }
+
+  #[test]
+  fn evicts_least_recently_used_page() {
+    let process = process_with_pages();
+    let mut reader = CachedReader::new(&process, 2);
+
+    reader.read::<u32>(HEAP + 0x10).unwrap();
+    reader.read::<u32>(HEAP + 0x1010).unwrap();
+    reader.read::<u32>(HEAP + 0x10).unwrap();
+    reader.read::<u32>(HEAP + 0x2010).unwrap();
+    assert_eq!(reader.cached_pages(), 2);
+
+    // Only the evicted page is read from the process again.
+    write_pages(&process, 7);
+    assert_eq!(reader.read::<u32>(HEAP + 0x10).unwrap(), 0);
+    assert_eq!(reader.read::<u32>(HEAP + 0x2010).unwrap(), 2);
+    assert_eq!(reader.read::<u32>(HEAP + 0x1010).unwrap(), 7);
+    assert_eq!(reader.cached_pages(), 2);
+  }
+
+  #[test]
+  fn invalidates_range() {
+    let process = process_with_pages();
+    let mut reader = CachedReader::new(&process, 4);
+    reader.read_memory_bytes(HEAP, 0x3000).unwrap();
+    assert_eq!(reader.cached_pages(), 3);
+    write_pages(&process, 7);
+
+    reader.invalidate_range(HEAP + 0x1010, HEAP + 0x1014);
+    assert_eq!(reader.cached_pages(), 2);
+    assert_eq!(reader.read::<u32>(HEAP + 0x10).unwrap(), 0);
+    assert_eq!(reader.read::<u32>(HEAP + 0x1010).unwrap(), 7);
+    assert_eq!(reader.read::<u32>(HEAP + 0x2010).unwrap(), 2);
+
+    // End of the range is exclusive, so the following page stays cached.
+    reader.invalidate_range(HEAP + 0xff0, HEAP + 0x2000);
+    assert_eq!(reader.cached_pages(), 1);
+    assert_eq!(reader.read::<u32>(HEAP + 0x10).unwrap(), 7);
+    assert_eq!(reader.read::<u32>(HEAP + 0x2010).unwrap(), 2);
+
+    reader.invalidate();
+    assert_eq!(reader.cached_pages(), 0);
+    assert_eq!(reader.read::<u32>(HEAP + 0x2010).unwrap(), 7);
+  }
+}
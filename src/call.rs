@@ -0,0 +1,265 @@
+use anyhow::Result;
+use nix::sys::ptrace;
+use nix::sys::signal::Signal;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+
+use super::error::system_call_error;
+use super::{Registers, RemoteBuffer, TracedProcess};
+
+/// Number of arguments passed in registers, the rest is passed on stack.
+#[cfg(target_arch = "x86_64")]
+const REGISTER_ARGS: usize = 6;
+#[cfg(target_arch = "aarch64")]
+const REGISTER_ARGS: usize = 8;
+
+/// Size of area below stack pointer which called function may use  
+/// without adjusting stack pointer, it is skipped to not corrupt it.
+const RED_ZONE: usize = 128;
+
+/// Address called function returns to. Nothing is mapped at it,  
+/// so returning raises **SIGSEGV** with instruction pointer set to it.
+const RETURN_ADDRESS: usize = 0;
+
+/// Argument of function called with `TracedProcess::call_function()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteArg<'a> {
+  /// Integer or pointer passed as is.
+  Int(u64),
+  /// Bytes copied to temporarily allocated remote memory,  
+  /// pointer to the copy is passed, e.g. for structure arguments.
+  Bytes(&'a [u8]),
+  /// String copied to temporarily allocated remote memory with  
+  /// terminating null byte, pointer to the copy is passed.
+  Str(&'a str),
+}
+
+#[cfg(target_arch = "x86_64")]
+impl Registers {
+  /// Prepares registers for calling function at `address` with `args`  
+  /// passed in registers, according to System V AMD64 ABI.  
+  /// `stack_pointer` must point to the return address.
+  fn prepare_call(&mut self, address: usize, args: &[u64], stack_pointer: usize) {
+    let mut all_args = [0u64; REGISTER_ARGS];
+    all_args[..args.len()].copy_from_slice(args);
+    self.rdi = all_args[0];
+    self.rsi = all_args[1];
+    self.rdx = all_args[2];
+    self.rcx = all_args[3];
+    self.r8 = all_args[4];
+    self.r9 = all_args[5];
+
+    // Number of vector registers used by variadic function arguments.
+    self.rax = 0;
+    // Interrupted system call must not be restarted at the function address.
+    self.orig_rax = u64::MAX;
+    self.rip = address as u64;
+    self.rsp = stack_pointer as u64;
+  }
+
+  /// Returns value returned by called function.
+  fn return_value(&self) -> u64 {
+    self.rax
+  }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl Registers {
+  /// Prepares registers for calling function at `address` with `args`  
+  /// passed in registers, according to AAPCS64.  
+  /// `stack_pointer` must point to arguments passed on stack.
+  fn prepare_call(&mut self, address: usize, args: &[u64], stack_pointer: usize) {
+    self.regs[..REGISTER_ARGS].copy_from_slice(&[0; REGISTER_ARGS]);
+    self.regs[..args.len()].copy_from_slice(args);
+
+    // Link register holds the return address.
+    self.regs[30] = RETURN_ADDRESS as u64;
+    self.pc = address as u64;
+    self.sp = stack_pointer as u64;
+  }
+
+  /// Returns value returned by called function.
+  fn return_value(&self) -> u64 {
+    self.regs[0]
+  }
+}
+
+impl TracedProcess {
+  /// Calls function at `address` in the process with `args` and returns  
+  /// its raw return value (contents of **rax** on x86_64 and **x0** on aarch64).  
+  /// Arguments are passed according to System V AMD64 ABI on x86_64 and AAPCS64  
+  /// on aarch64, which covers integer and pointer arguments. Floating point  
+  /// arguments and structures passed by value are not supported.
+  ///
+  /// [`RemoteArg::Bytes`] and [`RemoteArg::Str`] arguments are copied to memory  
+  /// allocated in the process with `RemoteBuffer::place()`, which is unmapped after  
+  /// the function returns.
+  ///
+  /// Function is called in main thread of the process, whose registers  
+  /// are restored afterwards. Other threads are stopped during the call,  
+  /// so the function must not wait for them, e.g. for lock held by one of them.  
+  /// Signals received during the call are delivered once the thread is resumed.
+  ///
+  /// [`RemoteArg::Bytes`]: enum.RemoteArg.html
+  /// [`RemoteArg::Str`]: enum.RemoteArg.html
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RemoteArg};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   let strlen_address = 0x7f3e1c2a4e40;
+  ///   let length = traced.call_function(strlen_address, &[RemoteArg::Str("trickster")])?;
+  ///   println!("length: {}", length);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `length: 9`
+  pub fn call_function(&self, address: usize, args: &[RemoteArg]) -> Result<u64> {
+    let buffers = self.place_arguments(args)?;
+    let values: Vec<u64> = args
+      .iter()
+      .scan(
+        buffers.as_ref().map(|buffer| buffer.address()),
+        |next_buffer, arg| {
+          Some(match *arg {
+            RemoteArg::Int(value) => value,
+            RemoteArg::Bytes(bytes) => take_buffer(next_buffer, bytes.len()),
+            RemoteArg::Str(string) => take_buffer(next_buffer, string.len() + 1),
+          })
+        },
+      )
+      .collect();
+
+    let result = self.with_all_stopped(|| self.inject_call(address, &values));
+
+    if let Some(buffers) = buffers {
+      buffers.free()?;
+    }
+    result
+  }
+
+  /// Copies contents of buffer arguments to memory allocated in the process,  
+  /// one after another with 16 byte alignment.
+  fn place_arguments(&self, args: &[RemoteArg]) -> Result<Option<RemoteBuffer<'_>>> {
+    let mut contents: Vec<u8> = Vec::new();
+    for arg in args {
+      match *arg {
+        RemoteArg::Int(_) => continue,
+        RemoteArg::Bytes(bytes) => contents.extend_from_slice(bytes),
+        RemoteArg::Str(string) => {
+          contents.extend_from_slice(string.as_bytes());
+          contents.push(0);
+        }
+      }
+      contents.resize(align_up(contents.len(), 16), 0);
+    }
+
+    if contents.is_empty() {
+      return Ok(None);
+    }
+    RemoteBuffer::place(self, &contents).map(Some)
+  }
+
+  /// Calls function in one of stopped threads of the process  
+  /// and restores its state, see `call_function()`.
+  fn inject_call(&self, address: usize, args: &[u64]) -> Result<u64> {
+    let tid = self.injection_thread()?;
+
+    let saved_registers = self.get_regs(tid)?;
+    #[cfg(target_arch = "aarch64")]
+    let saved_syscall_number = self.set_syscall_number(tid, -1)?;
+
+    let (register_args, stack_args) = args.split_at(args.len().min(REGISTER_ARGS));
+    let stack_args_address =
+      (saved_registers.stack_pointer() - RED_ZONE - stack_args.len() * 8) & !0xf;
+    let stack_contents: Vec<u8> =
+      stack_args.iter().flat_map(|arg| arg.to_ne_bytes().to_vec()).collect();
+
+    // On x86_64 return address is pushed on stack, right below the arguments.
+    #[cfg(target_arch = "x86_64")]
+    let (stack_pointer, stack_contents) = (
+      stack_args_address - 8,
+      [&(RETURN_ADDRESS as u64).to_ne_bytes()[..], &stack_contents].concat(),
+    );
+    #[cfg(target_arch = "aarch64")]
+    let stack_pointer = stack_args_address;
+
+    let mut registers = saved_registers;
+    registers.prepare_call(address, register_args, stack_pointer);
+
+    let outcome = self
+      .write_memory_bytes(stack_pointer, &stack_contents)
+      .and_then(|()| self.set_regs(tid, &registers))
+      .and_then(|()| self.run_until_return(tid, address));
+
+    // State is restored even if the function crashed, unless thread is gone.
+    let restored = self.set_regs(tid, &saved_registers);
+    #[cfg(target_arch = "aarch64")]
+    let restored =
+      restored.and_then(|()| self.set_syscall_number(tid, saved_syscall_number).map(drop));
+
+    let (registers, pending_signals) = outcome?;
+    restored?;
+
+    self.resend_signals(tid, &pending_signals);
+
+    Ok(registers.return_value())
+  }
+
+  /// Continues thread `tid` until function called at `address` returns  
+  /// and returns registers at that point, together with signals which  
+  /// were suppressed in the meantime.
+  fn run_until_return(&self, tid: Pid, address: usize) -> Result<(Registers, Vec<Signal>)> {
+    let mut pending_signals: Vec<Signal> = Vec::new();
+
+    loop {
+      ptrace::cont(tid, None).map_err(|error| {
+        system_call_error(tid, error, format!("Could not continue thread {}", tid))
+      })?;
+
+      let status = waitpid(tid, Some(WaitPidFlag::__WALL))
+        .map_err(|error| anyhow!("Could not wait for thread {} ({}).", tid, error))?;
+      match status {
+        WaitStatus::Stopped(_, Signal::SIGSEGV) => {
+          let registers = self.get_regs(tid)?;
+          if registers.instruction_pointer() == RETURN_ADDRESS {
+            return Ok((registers, pending_signals));
+          }
+          return Err(anyhow!(
+            "Could not call function at {:#x} (segmentation fault at {:#x}).",
+            address,
+            registers.instruction_pointer()
+          ));
+        }
+        WaitStatus::Stopped(_, signal) => pending_signals.push(signal),
+        WaitStatus::Exited(..) | WaitStatus::Signaled(..) => {
+          self.set_stopped(tid, false);
+          return Err(anyhow!(
+            "Could not call function at {:#x} (thread {} exited).",
+            address,
+            tid
+          ));
+        }
+        // Interrupt stops and other ptrace events, the thread is continued.
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Returns address of the next buffer argument of `length` bytes  
+/// and advances `next_buffer` past it, see `place_arguments()`.
+fn take_buffer(next_buffer: &mut Option<usize>, length: usize) -> u64 {
+  let address = next_buffer.unwrap_or(0);
+  *next_buffer = Some(address + align_up(length, 16));
+  address as u64
+}
+
+fn align_up(value: usize, alignment: usize) -> usize {
+  (value + alignment - 1) & !(alignment - 1)
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+
+use super::Process;
+
+/// Default size of chunks large reads are split into, see `Process::set_read_chunk_size()`.
+pub(crate) const DEFAULT_READ_CHUNK_SIZE: usize = 0x200000;
+
+/// Granularity in which unreadable memory is skipped while reading in chunks.
+const HOLE_PAGE_SIZE: usize = 0x1000;
+
+/// What `Process::read_memory_chunked()` does with parts of the range  
+/// which could not be read (holes), e.g. unmapped or guard pages.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum HolePolicy {
+  /// Return [`Err`] at the first hole.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  Fail,
+  /// Skip holes page by page, leaving them zero-filled, and continue reading.
+  ZeroFill,
+}
+
+/// Result of `Process::read_memory_chunked()`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct ChunkedRead {
+  /// Memory read, as long as the requested range. Holes are zero-filled.
+  pub bytes: Vec<u8>,
+  /// Starting and ending (exclusive) addresses of holes, sorted by address.  
+  /// Adjacent holes are merged.
+  pub holes: Vec<(usize, usize)>,
+}
+
+impl ChunkedRead {
+  /// Returns **true** if whole range could be read.
+  pub fn is_complete(&self) -> bool {
+    self.holes.is_empty()
+  }
+}
+
+impl Process {
+  /// Reads `length` bytes of memory at `address` in remote process in chunks  
+  /// (see `set_read_chunk_size()`), handling parts which could not be read  
+  /// according to `policy`. Unlike single huge read, which stops at the first  
+  /// unreadable byte, this reads everything readable in the range and reports  
+  /// exactly which sub-ranges failed. Errors other than unreadable memory  
+  /// (e.g. denied permission) are returned regardless of `policy`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{HolePolicy, Process};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let read = ctx.read_memory_chunked(0x7f0000000000, 0x4000000, HolePolicy::ZeroFill)?;
+  ///   for (start, end) in &read.holes {
+  ///     println!("could not read {:#x}-{:#x}", start, end);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `could not read 0x7f0000200000-0x7f0000201000`
+  pub fn read_memory_chunked(
+    &self,
+    address: usize,
+    length: usize,
+    policy: HolePolicy,
+  ) -> Result<ChunkedRead> {
+    let mut bytes = vec![0u8; length];
+    let mut holes: Vec<(usize, usize)> = Vec::new();
+    let mut offset = 0;
+
+    while offset < length {
+      let chunk_length = self.read_chunk_size().min(length - offset);
+      let bytes_read = self
+        .read_memory_into_partial(address + offset, &mut bytes[offset..offset + chunk_length])?;
+      offset += bytes_read;
+      if bytes_read == chunk_length {
+        continue;
+      }
+
+      let hole_start = address + offset;
+      if policy == HolePolicy::Fail {
+        return Err(anyhow!(
+          "Could not read memory at {:#x}. Partial read occurred.",
+          hole_start
+        ));
+      }
+      let hole_end = (hole_start + 1).next_multiple_of(HOLE_PAGE_SIZE).min(address + length);
+      match holes.last_mut() {
+        Some(last) if last.1 == hole_start => last.1 = hole_end,
+        _ => holes.push((hole_start, hole_end)),
+      }
+      offset = hole_end - address;
+    }
+
+    Ok(ChunkedRead { bytes, holes })
+  }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+  use super::super::{MockMemory, Process};
+  use super::HolePolicy;
+
+  /// Start of heap of process returned by `process_with_holes()`.
+  const HEAP: usize = 0x10000000;
+
+  /// Returns process with six pages of heap filled with `0xAB`, whose second,  
+  /// fourth and fifth page can not be read. Reads are split into chunks  
+  /// which are not multiples of page size.
+  fn process_with_holes() -> Process {
+    let mut process = MockMemory::new()
+      .region(HEAP, "rw-p", Some("[heap]"), vec![0xAB; 0x6000])
+      .hole(HEAP + 0x1000, 0x1000)
+      .hole(HEAP + 0x3000, 0x2000)
+      .into_process("game")
+      .unwrap();
+    process.set_read_chunk_size(0x1800);
+    process
+  }
+
+  #[test]
+  fn zero_fills_and_merges_holes() {
+    let process = process_with_holes();
+    let read = process.read_memory_chunked(HEAP + 0x800, 0x5000, HolePolicy::ZeroFill).unwrap();
+
+    assert!(!read.is_complete());
+    assert_eq!(
+      read.holes,
+      vec![
+        (HEAP + 0x1000, HEAP + 0x2000),
+        (HEAP + 0x3000, HEAP + 0x5000)
+      ]
+    );
+    assert_eq!(read.bytes.len(), 0x5000);
+    assert!(read.bytes[..0x800].iter().all(|byte| *byte == 0xAB));
+    assert!(read.bytes[0x800..0x1800].iter().all(|byte| *byte == 0));
+    assert!(read.bytes[0x1800..0x2800].iter().all(|byte| *byte == 0xAB));
+    assert!(read.bytes[0x2800..0x4800].iter().all(|byte| *byte == 0));
+    assert!(read.bytes[0x4800..].iter().all(|byte| *byte == 0xAB));
+  }
+
+  #[test]
+  fn ends_holes_at_end_of_range() {
+    let process = process_with_holes();
+
+    let read = process.read_memory_chunked(HEAP + 0x800, 0x1000, HolePolicy::ZeroFill).unwrap();
+    assert_eq!(read.holes, vec![(HEAP + 0x1000, HEAP + 0x1800)]);
+
+    // Memory past the heap is not mapped at all.
+    let read = process
+      .read_memory_chunked(HEAP + 0x5800, 0x1000, HolePolicy::ZeroFill)
+      .unwrap();
+    assert_eq!(read.holes, vec![(HEAP + 0x6000, HEAP + 0x6800)]);
+  }
+
+  #[test]
+  fn fails_at_first_hole() {
+    let process = process_with_holes();
+
+    let error = process.read_memory_chunked(HEAP + 0x800, 0x5000, HolePolicy::Fail).unwrap_err();
+    assert!(error.to_string().contains("0x10001000"));
+
+    let read = process.read_memory_chunked(HEAP + 0x2000, 0x1000, HolePolicy::Fail).unwrap();
+    assert!(read.is_complete());
+    assert_eq!(read.bytes, vec![0xAB; 0x1000]);
+  }
+}
@@ -0,0 +1,331 @@
+use anyhow::Result;
+use nix::sys::signal::Signal;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::time::Duration;
+
+use super::dump_process::{CORE_OWNER, NT_TRICKSTER_MAPS, TRICKSTER_OWNER};
+use super::maps::parse_maps_line;
+use super::pagemap::page_size;
+use super::{MemoryRegion, ProcessStat, Registers, Thread, TracedProcess};
+
+const ELF_HEADER_SIZE: usize = 0x40;
+const PROGRAM_HEADER_SIZE: usize = 0x38;
+/// ELF file type of core dumps.
+const ET_CORE: u16 = 4;
+#[cfg(target_arch = "x86_64")]
+const EM_MACHINE: u16 = 62;
+#[cfg(target_arch = "aarch64")]
+const EM_MACHINE: u16 = 183;
+#[cfg(target_endian = "little")]
+const ELF_DATA: u8 = 1;
+#[cfg(target_endian = "big")]
+const ELF_DATA: u8 = 2;
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+const PF_X: u32 = 1;
+const PF_W: u32 = 2;
+const PF_R: u32 = 4;
+/// Types of notes written by the kernel into core dumps, with `CORE` owner.
+const NT_PRSTATUS: u32 = 1;
+const NT_PRPSINFO: u32 = 3;
+const NT_AUXV: u32 = 6;
+const NT_FILE: u32 = 0x4649_4c45;
+/// Offset of `pr_reg` in `struct elf_prstatus`, fields before it are  
+/// the same on all 64-bit architectures.
+const PRSTATUS_REGISTERS_OFFSET: usize = 0x70;
+/// Size of `struct elf_prpsinfo` on 64-bit architectures.
+const PRPSINFO_SIZE: usize = 0x88;
+
+impl TracedProcess {
+  /// Writes memory and registers of the process to file located at `path`  
+  /// in ELF core dump format, the same one kernel and **gcore** use,  
+  /// so it can be opened with `gdb <executable> <path>` and other standard  
+  /// tooling. Every mapping of the process becomes `PT_LOAD` segment  
+  /// (mappings which can not be read have no contents, unreadable pages  
+  /// are zero-filled), and registers of every traced thread of the process  
+  /// are stored in **NT_PRSTATUS** notes, main thread first. Process  
+  /// information, auxiliary vector and mapped files are stored  
+  /// in **NT_PRPSINFO**, **NT_AUXV** and **NT_FILE** notes. The file  
+  /// can be opened with `DumpProcess::from_core_file()` as well.
+  ///
+  /// All threads have to be stopped, which they are right after attaching.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///   traced.write_core_dump("current_process_name.core")?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn write_core_dump<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+    let maps_path = self.proc_path("maps");
+    let maps = fs::read_to_string(&maps_path).map_err(|error| {
+      anyhow!(
+        "Could not read {} ({}).",
+        maps_path.display(),
+        error
+      )
+    })?;
+    let regions: Vec<MemoryRegion> = maps
+      .lines()
+      .map(|line| parse_maps_line(line.as_bytes()))
+      .collect::<Result<_>>()?;
+    let mut notes = self.core_notes(&regions)?;
+    push_note(&mut notes, TRICKSTER_OWNER, NT_TRICKSTER_MAPS, maps.as_bytes());
+    let page_size = page_size();
+
+    let notes_offset = ELF_HEADER_SIZE + PROGRAM_HEADER_SIZE * (regions.len() + 1);
+    let mut headers = elf_header(regions.len() + 1);
+    headers.extend(program_header(
+      PT_NOTE,
+      0,
+      notes_offset,
+      0,
+      notes.len(),
+      0,
+      4,
+    ));
+
+    let contents_offset = (notes_offset + notes.len()).next_multiple_of(page_size);
+    let mut segment_offset = contents_offset;
+    for region in &regions {
+      let size = region.size();
+      let file_size = if region.permissions.readable { size } else { 0 };
+      headers.extend(program_header(
+        PT_LOAD,
+        segment_flags(region),
+        segment_offset,
+        region.start,
+        file_size,
+        size,
+        page_size,
+      ));
+      segment_offset += file_size;
+    }
+
+    let file = fs::File::create(path.as_ref()).map_err(|error| {
+      anyhow!(
+        "Could not create core dump file {} ({}).",
+        path.as_ref().display(),
+        error
+      )
+    })?;
+    let mut writer = BufWriter::new(file);
+    writer.write_all(&headers)?;
+    writer.write_all(&notes)?;
+    writer.write_all(&vec![0u8; contents_offset - notes_offset - notes.len()])?;
+    for region in regions.iter().filter(|region| region.permissions.readable) {
+      self.write_memory_to(region.start, region.end, &mut writer)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+  }
+
+  /// Builds contents of `PT_NOTE` segment of core dump of mappings `regions`.
+  fn core_notes(&self, regions: &[MemoryRegion]) -> Result<Vec<u8>> {
+    let traced_threads = self.traced_threads();
+    let mut threads: Vec<Thread> = self
+      .threads()?
+      .into_iter()
+      .filter(|thread| traced_threads.contains(&thread.tid))
+      .collect();
+    threads.sort_by_key(|thread| !thread.is_main_thread());
+
+    let process_stat = self.stat()?;
+    let mut notes = Vec::new();
+    for (index, thread) in threads.iter().enumerate() {
+      let registers = self.get_regs(thread.tid)?;
+      let thread_stat = thread.stat()?;
+      push_note(
+        &mut notes,
+        CORE_OWNER,
+        NT_PRSTATUS,
+        &prstatus(&process_stat, &thread_stat, &registers)?,
+      );
+
+      // Kernel writes process wide notes right after status of the main thread.
+      if index == 0 {
+        push_note(
+          &mut notes,
+          CORE_OWNER,
+          NT_PRPSINFO,
+          &self.prpsinfo(&process_stat)?,
+        );
+        let auxv_path = format!("/proc/{}/auxv", self.get_pid());
+        let auxv = fs::read(&auxv_path)
+          .map_err(|error| anyhow!("Could not read {} ({}).", auxv_path, error))?;
+        push_note(&mut notes, CORE_OWNER, NT_AUXV, &auxv);
+        push_note(&mut notes, CORE_OWNER, NT_FILE, &mapped_files(regions));
+      }
+    }
+
+    Ok(notes)
+  }
+
+  /// Builds `struct elf_prpsinfo` of the process.
+  fn prpsinfo(&self, stat: &ProcessStat) -> Result<Vec<u8>> {
+    let owner = fs::metadata(format!("/proc/{}", self.get_pid()))?;
+    let arguments = self.cmdline().unwrap_or_default().join(" ");
+
+    let mut info = vec![0u8; PRPSINFO_SIZE];
+    // All threads are stopped by the tracer.
+    info[0] = 3;
+    info[1] = b'T';
+    info[3] = stat.nice as i8 as u8;
+    info[0x10..0x14].copy_from_slice(&owner.uid().to_ne_bytes());
+    info[0x14..0x18].copy_from_slice(&owner.gid().to_ne_bytes());
+    info[0x18..0x1c].copy_from_slice(&stat.pid.as_raw().to_ne_bytes());
+    info[0x1c..0x20].copy_from_slice(&stat.ppid.as_raw().to_ne_bytes());
+    info[0x20..0x24].copy_from_slice(&stat.pgrp.to_ne_bytes());
+    info[0x24..0x28].copy_from_slice(&stat.session.to_ne_bytes());
+    copy_truncated(&mut info[0x28..0x38], stat.comm.as_bytes());
+    copy_truncated(&mut info[0x38..0x88], arguments.as_bytes());
+    Ok(info)
+  }
+}
+
+/// Builds `struct elf_prstatus` of thread with status `thread_stat`  
+/// and `registers`, which belongs to process with status `process_stat`.
+fn prstatus(
+  process_stat: &ProcessStat,
+  thread_stat: &ProcessStat,
+  registers: &Registers,
+) -> Result<Vec<u8>> {
+  let registers = bytemuck::bytes_of(registers);
+  let mut status = vec![0u8; PRSTATUS_REGISTERS_OFFSET + registers.len() + 8];
+
+  let signal = Signal::SIGSTOP as i32;
+  status[0x00..0x04].copy_from_slice(&signal.to_ne_bytes());
+  status[0x0c..0x0e].copy_from_slice(&(signal as i16).to_ne_bytes());
+  status[0x20..0x24].copy_from_slice(&thread_stat.pid.as_raw().to_ne_bytes());
+  status[0x24..0x28].copy_from_slice(&process_stat.ppid.as_raw().to_ne_bytes());
+  status[0x28..0x2c].copy_from_slice(&process_stat.pgrp.to_ne_bytes());
+  status[0x2c..0x30].copy_from_slice(&process_stat.session.to_ne_bytes());
+  write_timeval(
+    &mut status[0x30..0x40],
+    ProcessStat::ticks_to_duration(thread_stat.utime)?,
+  );
+  write_timeval(
+    &mut status[0x40..0x50],
+    ProcessStat::ticks_to_duration(thread_stat.stime)?,
+  );
+  status[PRSTATUS_REGISTERS_OFFSET..PRSTATUS_REGISTERS_OFFSET + registers.len()]
+    .copy_from_slice(registers);
+  Ok(status)
+}
+
+/// Builds contents of **NT_FILE** note, which lists mappings of files.
+fn mapped_files(regions: &[MemoryRegion]) -> Vec<u8> {
+  let page_size = page_size();
+  let files: Vec<(&MemoryRegion, &str)> = regions
+    .iter()
+    .filter_map(|region| match &region.path {
+      Some(path) if path.starts_with('/') => Some((region, path.as_str())),
+      _ => None,
+    })
+    .collect();
+
+  let mut note = Vec::new();
+  note.extend_from_slice(&(files.len() as u64).to_ne_bytes());
+  note.extend_from_slice(&(page_size as u64).to_ne_bytes());
+  for (region, _) in &files {
+    note.extend_from_slice(&(region.start as u64).to_ne_bytes());
+    note.extend_from_slice(&(region.end as u64).to_ne_bytes());
+    note.extend_from_slice(&((region.offset / page_size) as u64).to_ne_bytes());
+  }
+  for (_, path) in &files {
+    note.extend_from_slice(path.as_bytes());
+    note.push(0);
+  }
+  note
+}
+
+/// Appends note of `owner`, type `note_type` and `description` to `notes`.
+fn push_note(notes: &mut Vec<u8>, owner: &[u8], note_type: u32, description: &[u8]) {
+  notes.extend_from_slice(&(owner.len() as u32 + 1).to_ne_bytes());
+  notes.extend_from_slice(&(description.len() as u32).to_ne_bytes());
+  notes.extend_from_slice(&note_type.to_ne_bytes());
+  notes.extend_from_slice(owner);
+  notes.push(0);
+  notes.resize(notes.len().next_multiple_of(4), 0);
+  notes.extend_from_slice(description);
+  notes.resize(notes.len().next_multiple_of(4), 0);
+}
+
+/// Builds 64-bit ELF header of core dump with `program_headers` program headers  
+/// following it.
+fn elf_header(program_headers: usize) -> Vec<u8> {
+  let mut header = vec![0u8; ELF_HEADER_SIZE];
+  header[0..4].copy_from_slice(b"\x7fELF");
+  // 64-bit objects of local machine's byte order, current version, System V ABI.
+  header[4] = 2;
+  header[5] = ELF_DATA;
+  header[6] = 1;
+  header[0x10..0x12].copy_from_slice(&ET_CORE.to_ne_bytes());
+  header[0x12..0x14].copy_from_slice(&EM_MACHINE.to_ne_bytes());
+  header[0x14..0x18].copy_from_slice(&1u32.to_ne_bytes());
+  header[0x20..0x28].copy_from_slice(&(ELF_HEADER_SIZE as u64).to_ne_bytes());
+  header[0x34..0x36].copy_from_slice(&(ELF_HEADER_SIZE as u16).to_ne_bytes());
+  header[0x36..0x38].copy_from_slice(&(PROGRAM_HEADER_SIZE as u16).to_ne_bytes());
+  header[0x38..0x3a].copy_from_slice(&(program_headers as u16).to_ne_bytes());
+  header
+}
+
+fn program_header(
+  p_type: u32,
+  flags: u32,
+  offset: usize,
+  virtual_address: usize,
+  file_size: usize,
+  memory_size: usize,
+  alignment: usize,
+) -> Vec<u8> {
+  let mut header = Vec::with_capacity(PROGRAM_HEADER_SIZE);
+  header.extend_from_slice(&p_type.to_ne_bytes());
+  header.extend_from_slice(&flags.to_ne_bytes());
+  for value in &[
+    offset,
+    virtual_address,
+    0,
+    file_size,
+    memory_size,
+    alignment,
+  ] {
+    header.extend_from_slice(&(*value as u64).to_ne_bytes());
+  }
+  header
+}
+
+fn segment_flags(region: &MemoryRegion) -> u32 {
+  let mut flags = 0;
+  if region.permissions.readable {
+    flags |= PF_R;
+  }
+  if region.permissions.writeable {
+    flags |= PF_W;
+  }
+  if region.permissions.executable {
+    flags |= PF_X;
+  }
+  flags
+}
+
+fn write_timeval(buffer: &mut [u8], duration: Duration) {
+  buffer[0..8].copy_from_slice(&duration.as_secs().to_ne_bytes());
+  buffer[8..16].copy_from_slice(&u64::from(duration.subsec_micros()).to_ne_bytes());
+}
+
+/// Copies as much of `bytes` as fits into `buffer`, leaving  
+/// the last byte of `buffer` for null terminator.
+fn copy_truncated(buffer: &mut [u8], bytes: &[u8]) {
+  let length = bytes.len().min(buffer.len() - 1);
+  buffer[..length].copy_from_slice(&bytes[..length]);
+}
@@ -0,0 +1,44 @@
+use cpp_demangle::{DemangleOptions, Symbol};
+
+/// Demangles Rust (both legacy and v0) or Itanium C++ mangled symbol `name`,  
+/// returning [`None`] if it is not mangled. Hashes of Rust legacy symbols  
+/// are omitted, e.g. `_ZN3std2io5stdio6_print17h4ac5c0d1e8b0b6e4E` is demangled  
+/// as `std::io::stdio::_print`.
+///
+/// [`None`]: https://doc.rust-lang.org/std/option/
+///
+/// # Examples
+/// ```
+/// extern crate trickster;
+/// use trickster::demangle;
+///
+/// fn main() {
+///   assert_eq!(
+///     demangle("_ZN6Player10takeDamageEi"),
+///     Some("Player::takeDamage(int)".to_string())
+///   );
+///   assert_eq!(demangle("malloc"), None);
+/// }
+/// ```
+pub fn demangle(name: &str) -> Option<String> {
+  demangle_with(name, &DemangleOptions::new())
+}
+
+/// Demangles `name` like `demangle()`, but omits parameter list  
+/// of C++ functions, e.g. `Player::takeDamage`.
+pub(crate) fn demangle_without_params(name: &str) -> Option<String> {
+  demangle_with(name, &DemangleOptions::new().no_params())
+}
+
+fn demangle_with(name: &str, options: &DemangleOptions) -> Option<String> {
+  // Rust legacy symbols are valid Itanium C++ symbols too, they are recognized
+  // by hash, which is the only part omitted in alternate format.
+  if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+    let without_hash = format!("{:#}", demangled);
+    if name.starts_with("_R") || without_hash != demangled.to_string() {
+      return Some(without_hash);
+    }
+  }
+
+  Symbol::new(name).ok()?.demangle(options).ok()
+}
@@ -0,0 +1,177 @@
+use anyhow::Result;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::scan::SCAN_CHUNK_SIZE;
+use super::{MemoryRegion, Process};
+
+/// Granularity in which unreadable memory is skipped while dumping.
+const DUMP_PAGE_SIZE: usize = 0x1000;
+
+/// Entry of JSON index written by `Process::dump_all()`, describing  
+/// one dumped region.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct RegionDump {
+  /// Name of the dump file, relative to the dump directory.
+  pub file: String,
+  /// Starting address of the region.
+  pub start: usize,
+  /// Ending address of the region.
+  pub end: usize,
+  /// Permissions of the region in `/proc/\[pid\]/maps` format, e.g. `r-xp`.
+  pub permissions: String,
+  /// Offset in the file the region is mapped from.
+  #[cfg_attr(feature = "serde-support", serde(default))]
+  pub offset: usize,
+  /// Path of the file the region is mapped from, if any.
+  pub path: Option<String>,
+  /// Number of bytes which could be read, the rest of the dump is zero-filled.
+  pub bytes_read: usize,
+}
+
+impl Process {
+  /// Writes raw contents of `region` to file located at `path`, so the file  
+  /// can be loaded at `region.start` in disassemblers like Ghidra. Memory  
+  /// is read in chunks, and parts which could not be read are zero-filled,  
+  /// so size of the file always equals size of the region.  
+  /// Returns number of bytes which could be read.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let heap = ctx.region_find_first_by_name("[heap]", None)?;
+  ///   let bytes_read = ctx.dump_region(heap, "heap.bin")?;
+  ///   println!("dumped {:#x} bytes of heap", bytes_read);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `dumped 0x21000 bytes of heap`
+  pub fn dump_region<P: AsRef<Path>>(&self, region: &MemoryRegion, path: P) -> Result<usize> {
+    let file = fs::File::create(path.as_ref()).map_err(|error| {
+      anyhow!(
+        "Could not create dump file {} ({}).",
+        path.as_ref().display(),
+        error
+      )
+    })?;
+    let mut writer = BufWriter::new(file);
+    let bytes_read = self.write_memory_to(region.start, region.end, &mut writer)?;
+    writer.flush()?;
+    Ok(bytes_read)
+  }
+
+  /// Writes memory from `start` to `end` (exclusive) to `writer` in chunks,  
+  /// zero-filling pages which could not be read. Returns number of bytes read.
+  pub(crate) fn write_memory_to<W: Write>(
+    &self,
+    start: usize,
+    end: usize,
+    writer: &mut W,
+  ) -> Result<usize> {
+    let mut bytes_read = 0;
+
+    let mut chunk_start = start;
+    while chunk_start < end {
+      let chunk_length = SCAN_CHUNK_SIZE.min(end - chunk_start);
+      let (chunk, chunk_read) = self
+        .read_memory_partial(chunk_start, chunk_length)
+        .unwrap_or_else(|_| (vec![0u8; chunk_length], 0));
+      bytes_read += chunk_read;
+
+      // Reading stops at the first unreadable page, so only that page
+      // is zero-filled and reading continues right after it.
+      let chunk_end = if chunk_read == chunk_length {
+        chunk_length
+      } else {
+        (chunk_read + 1).next_multiple_of(DUMP_PAGE_SIZE).min(chunk_length)
+      };
+      writer.write_all(&chunk[..chunk_end])?;
+      chunk_start += chunk_end;
+    }
+
+    Ok(bytes_read)
+  }
+
+  /// Dumps every readable region for which `filter` returns **true**  
+  /// into directory located at `directory` (created if it does not exist),  
+  /// see `dump_region()`. Dump files are named after address range  
+  /// of the region (e.g. `7f1c2a000000-7f1c2a021000.bin`), and `index.json`  
+  /// file listing all of them (see [`RegionDump`]) is written next to them.  
+  /// Requires `serde-support` feature.
+  ///
+  /// [`RegionDump`]: struct.RegionDump.html
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `dump_all();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let dumps = ctx.dump_all("dump", |region| region.path.is_none())?;
+  ///   for dump in &dumps {
+  ///     println!("{:#x}-{:#x} {} -> {}", dump.start, dump.end, dump.permissions, dump.file);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x55d4c1e84000-0x55d4c1ea5000 rw-p -> 55d4c1e84000-55d4c1ea5000.bin`
+  #[cfg(feature = "serde-support")]
+  pub fn dump_all<P, F>(&self, directory: P, filter: F) -> Result<Vec<RegionDump>>
+  where
+    P: AsRef<Path>,
+    F: Fn(&MemoryRegion) -> bool,
+  {
+    let directory = directory.as_ref();
+    fs::create_dir_all(directory).map_err(|error| {
+      anyhow!(
+        "Could not create dump directory {} ({}).",
+        directory.display(),
+        error
+      )
+    })?;
+
+    let mut dumps = Vec::new();
+    for region in self.get_memory_regions()? {
+      if !region.permissions.readable || !filter(region) {
+        continue;
+      }
+
+      let file = format!("{:x}-{:x}.bin", region.start, region.end);
+      let bytes_read = self.dump_region(region, directory.join(&file))?;
+      dumps.push(RegionDump {
+        file,
+        start: region.start,
+        end: region.end,
+        permissions: region.permissions.to_string(),
+        offset: region.offset,
+        path: region.path.clone(),
+        bytes_read,
+      });
+    }
+
+    let index = fs::File::create(directory.join("index.json"))?;
+    serde_json::to_writer_pretty(BufWriter::new(index), &dumps)
+      .map_err(|error| anyhow!("Could not write dump index ({}).", error))?;
+
+    Ok(dumps)
+  }
+}
@@ -0,0 +1,555 @@
+use anyhow::Result;
+use nix::unistd::Pid;
+use std::convert::TryInto;
+use std::fs;
+use std::io;
+use std::ops::{Deref, DerefMut};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+#[cfg(feature = "serde-support")]
+use super::RegionDump;
+use super::{MemoryBackend, MemoryRegion, MemorySource, Process};
+
+const ELF_HEADER_SIZE: usize = 0x40;
+const PROGRAM_HEADER_SIZE: usize = 0x38;
+/// ELF file type of core dumps.
+const ET_CORE: u16 = 4;
+#[cfg(target_endian = "little")]
+const ELF_DATA: u8 = 1;
+#[cfg(target_endian = "big")]
+const ELF_DATA: u8 = 2;
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+const PF_X: u32 = 1;
+const PF_W: u32 = 2;
+const PF_R: u32 = 4;
+const NT_PRPSINFO: u32 = 3;
+const NT_FILE: u32 = 0x4649_4c45;
+/// Type of note with `TRICKSTER` owner containing `/proc/\[pid\]/maps` file  
+/// of dumped process, which keeps names of special regions like `[heap]`.  
+/// Debuggers skip notes of unknown owners.
+pub(crate) const NT_TRICKSTER_MAPS: u32 = 1;
+pub(crate) const CORE_OWNER: &[u8] = b"CORE";
+pub(crate) const TRICKSTER_OWNER: &[u8] = b"TRICKSTER";
+
+/// Contiguous range of captured memory stored in one of dump files.
+struct DumpSegment {
+  start: usize,
+  end: usize,
+  /// Index of the file in `DumpMemory::files`.
+  file: usize,
+  file_offset: u64,
+}
+
+/// Captured memory of a process, read from dump files on demand,  
+/// so dumps larger than available memory can be opened. It is memory  
+/// backend of `DumpProcess`, providing its memory regions too.
+pub(crate) struct DumpMemory {
+  /// Contents of `/proc/\[pid\]/maps` file of the dumped process.
+  maps: String,
+  files: Vec<fs::File>,
+  /// Captured memory ranges, sorted by address.
+  segments: Vec<DumpSegment>,
+}
+
+impl DumpMemory {
+  fn new(maps: String, files: Vec<fs::File>, mut segments: Vec<DumpSegment>) -> DumpMemory {
+    segments.sort_by_key(|segment| segment.start);
+    DumpMemory {
+      maps,
+      files,
+      segments,
+    }
+  }
+}
+
+impl MemoryBackend for DumpMemory {
+  /// Fills `buffer` with captured memory at `address`, stopping at the first  
+  /// byte which was not captured, like **process_vm_readv(2)** does.
+  fn read(&self, _pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut copied = 0;
+
+    while copied < buffer.len() {
+      let current = address.wrapping_add(copied);
+      let index = self.segments.partition_point(|segment| segment.start <= current);
+      let segment = match index.checked_sub(1).map(|index| &self.segments[index]) {
+        Some(segment) if current < segment.end => segment,
+        _ => break,
+      };
+
+      let length = (segment.end - current).min(buffer.len() - copied);
+      self.files[segment.file].read_exact_at(
+        &mut buffer[copied..copied + length],
+        segment.file_offset + (current - segment.start) as u64,
+      )?;
+      copied += length;
+    }
+
+    if copied == 0 && !buffer.is_empty() {
+      return Err(io::Error::from_raw_os_error(libc::EFAULT));
+    }
+    Ok(copied)
+  }
+
+  fn write(&self, _pid: Pid, _address: usize, _buffer: &[u8]) -> io::Result<usize> {
+    Err(io::Error::other("captured memory can only be read"))
+  }
+
+  fn maps(&self, _pid: Pid) -> Option<String> {
+    Some(self.maps.clone())
+  }
+}
+
+/// Process memory captured earlier, loaded from directory written  
+/// by `Process::dump_all()` or from ELF core dump (written by  
+/// `TracedProcess::write_core_dump()`, **gcore** or kernel), which allows  
+/// reproducible offline analysis.
+///
+/// `DumpProcess` dereferences to [`Process`] with memory regions already  
+/// parsed, so memory reading, scanning, region query and symbolication  
+/// methods work the same as on live process. Memory is read from dump files  
+/// on demand. Writing memory, tracing and methods reading `/proc/\[pid\]/`  
+/// files (e.g. `threads()`) return [`Err`], as there is no live process.
+///
+/// [`Process`]: struct.Process.html
+/// [`Err`]: https://doc.rust-lang.org/std/result/
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{DumpProcess, Signature};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let dump = DumpProcess::from_core_file("current_process_name.core")?;
+///
+///   let signature = Signature::new("48 8B 05 ?? ?? ?? ?? 48 85 C0")?;
+///   for address in dump.scan_module("current_process_name", &signature)? {
+///     println!("{:#x}: {:?}", address, dump.symbolicate(address)?);
+///   }
+///   Ok(())
+/// }
+/// ```
+pub struct DumpProcess {
+  process: Process,
+}
+
+impl DumpProcess {
+  /// Loads dump directory written by `Process::dump_all()`, located at `directory`.  
+  /// Only dumped regions are available. Process is named after the directory.  
+  /// Requires `serde-support` feature.
+  #[cfg(feature = "serde-support")]
+  pub fn from_directory<P: AsRef<Path>>(directory: P) -> Result<DumpProcess> {
+    let directory = directory.as_ref();
+    let index_path = directory.join("index.json");
+    let index = fs::File::open(&index_path).map_err(|error| {
+      anyhow!(
+        "Could not open dump index {} ({}).",
+        index_path.display(),
+        error
+      )
+    })?;
+    let dumps: Vec<RegionDump> = serde_json::from_reader(std::io::BufReader::new(index))
+      .map_err(|error| anyhow!("Could not parse dump index ({}).", error))?;
+
+    let mut maps = String::new();
+    let mut files = Vec::new();
+    let mut segments = Vec::new();
+    for dump in dumps {
+      let file_path = directory.join(&dump.file);
+      let file = fs::File::open(&file_path).map_err(|error| {
+        anyhow!(
+          "Could not open dump file {} ({}).",
+          file_path.display(),
+          error
+        )
+      })?;
+      let file_length = file.metadata()?.len() as usize;
+      let region_length = dump.end.checked_sub(dump.start).ok_or_else(|| {
+        anyhow!(
+          "Could not parse dump index (invalid region {:#x}-{:#x}).",
+          dump.start,
+          dump.end
+        )
+      })?;
+
+      maps.push_str(&maps_line(
+        dump.start,
+        dump.end,
+        &dump.permissions,
+        dump.offset,
+        dump.path.as_deref(),
+      ));
+      segments.push(DumpSegment {
+        start: dump.start,
+        end: dump.start + file_length.min(region_length),
+        file: files.len(),
+        file_offset: 0,
+      });
+      files.push(file);
+    }
+
+    let name = directory
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    Ok(DumpProcess {
+      process: Process::from_dump(name, DumpMemory::new(maps, files, segments))?,
+    })
+  }
+
+  /// Loads 64-bit ELF core dump located at `path`. Every `PT_LOAD` segment  
+  /// becomes memory region, and names of mapped files are taken from  
+  /// **NT_FILE** note. Names of special regions like `[heap]` are stored only  
+  /// in core dumps written by `TracedProcess::write_core_dump()`. Process is named  
+  /// after the name stored in **NT_PRPSINFO** note.
+  pub fn from_core_file<P: AsRef<Path>>(path: P) -> Result<DumpProcess> {
+    let path = path.as_ref();
+    let file = fs::File::open(path)
+      .map_err(|error| anyhow!("Could not open core dump {} ({}).", path.display(), error))?;
+    let invalid_core =
+      |reason: &str| anyhow!("Could not load core dump {} ({}).", path.display(), reason);
+
+    let core_length = file
+      .metadata()
+      .map_err(|error| anyhow!("Could not open core dump {} ({}).", path.display(), error))?
+      .len();
+
+    let mut header = [0u8; ELF_HEADER_SIZE];
+    file
+      .read_exact_at(&mut header, 0)
+      .map_err(|_| invalid_core("file is too short"))?;
+    if &header[0..4] != b"\x7fELF" || header[4] != 2 || header[5] != ELF_DATA {
+      return Err(invalid_core(
+        "not 64-bit ELF file of local machine's byte order",
+      ));
+    }
+    if read_u16(&header, 0x10) != ET_CORE {
+      return Err(invalid_core("not a core dump"));
+    }
+
+    let program_headers_offset = read_u64(&header, 0x20);
+    let program_header_count = read_u16(&header, 0x38) as usize;
+    let mut program_headers = vec![0u8; program_header_count * PROGRAM_HEADER_SIZE];
+    file
+      .read_exact_at(&mut program_headers, program_headers_offset)
+      .map_err(|_| invalid_core("program headers are truncated"))?;
+
+    let mut notes = Vec::new();
+    let mut loads = Vec::new();
+    for header in program_headers.chunks(PROGRAM_HEADER_SIZE) {
+      let offset = read_u64(header, 0x08);
+      let file_size = read_u64(header, 0x20) as usize;
+      match read_u32(header, 0x00) {
+        PT_NOTE => {
+          // Size is checked against the file, so corrupted one does not exhaust memory.
+          if offset.checked_add(file_size as u64).is_none_or(|end| end > core_length) {
+            return Err(invalid_core("notes are truncated"));
+          }
+          let mut data = vec![0u8; file_size];
+          file
+            .read_exact_at(&mut data, offset)
+            .map_err(|_| invalid_core("notes are truncated"))?;
+          notes.extend(parse_notes(&data));
+        }
+        PT_LOAD => {
+          let load = LoadSegment {
+            flags: read_u32(header, 0x04),
+            offset,
+            start: read_u64(header, 0x10) as usize,
+            file_size,
+            memory_size: read_u64(header, 0x28) as usize,
+          };
+          // Ends of segments are computed from their sizes later.
+          if load.start.checked_add(load.file_size.max(load.memory_size)).is_none() {
+            return Err(invalid_core("segment exceeds address space"));
+          }
+          loads.push(load);
+        }
+        _ => {}
+      }
+    }
+
+    let find_note = |owner: &[u8], note_type: u32| {
+      notes
+        .iter()
+        .find(|note| note.owner == owner && note.note_type == note_type)
+        .map(|note| note.description.as_slice())
+    };
+
+    let maps = match find_note(TRICKSTER_OWNER, NT_TRICKSTER_MAPS) {
+      Some(maps) => String::from_utf8_lossy(maps).into_owned(),
+      None => {
+        let files = find_note(CORE_OWNER, NT_FILE).map(parse_file_note).unwrap_or_default();
+        loads
+          .iter()
+          .map(|load| {
+            let file = files.iter().find(|file| file.0 == load.start);
+            maps_line(
+              load.start,
+              load.start + load.memory_size,
+              &load.permissions(),
+              file.map_or(0, |file| file.1),
+              file.map(|file| file.2.as_str()),
+            )
+          })
+          .collect()
+      }
+    };
+
+    // Name is `pr_fname` field of `struct elf_prpsinfo`.
+    let name = find_note(CORE_OWNER, NT_PRPSINFO)
+      .and_then(|info| info.get(0x28..0x38))
+      .map(|name| {
+        let length = name.iter().position(|byte| *byte == 0).unwrap_or(name.len());
+        String::from_utf8_lossy(&name[..length]).into_owned()
+      })
+      .unwrap_or_default();
+
+    let segments = loads
+      .iter()
+      .filter(|load| load.file_size > 0)
+      .map(|load| DumpSegment {
+        start: load.start,
+        end: load.start + load.file_size,
+        file: 0,
+        file_offset: load.offset,
+      })
+      .collect();
+
+    Ok(DumpProcess {
+      process: Process::from_dump(name, DumpMemory::new(maps, vec![file], segments))?,
+    })
+  }
+}
+
+impl Deref for DumpProcess {
+  type Target = Process;
+
+  fn deref(&self) -> &Process {
+    &self.process
+  }
+}
+
+impl DerefMut for DumpProcess {
+  fn deref_mut(&mut self) -> &mut Process {
+    &mut self.process
+  }
+}
+
+impl MemorySource for DumpProcess {
+  fn read_memory_into(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+    self.process.read_memory_into(address, buffer)
+  }
+
+  fn memory_regions(&self) -> Result<&[MemoryRegion]> {
+    MemorySource::memory_regions(&self.process)
+  }
+}
+
+/// Loadable segment of core dump.
+struct LoadSegment {
+  flags: u32,
+  offset: u64,
+  start: usize,
+  file_size: usize,
+  memory_size: usize,
+}
+
+impl LoadSegment {
+  /// Returns permissions of the segment in `/proc/\[pid\]/maps` format.
+  fn permissions(&self) -> String {
+    let flag = |flag: u32, character: char| {
+      if self.flags & flag != 0 {
+        character
+      } else {
+        '-'
+      }
+    };
+    format!("{}{}{}p", flag(PF_R, 'r'), flag(PF_W, 'w'), flag(PF_X, 'x'))
+  }
+}
+
+struct Note {
+  owner: Vec<u8>,
+  note_type: u32,
+  description: Vec<u8>,
+}
+
+/// Parses notes of `PT_NOTE` segment, stopping at the first malformed one.
+fn parse_notes(data: &[u8]) -> Vec<Note> {
+  let mut notes = Vec::new();
+  let mut offset = 0;
+
+  while offset + 12 <= data.len() {
+    let owner_size = read_u32(data, offset) as usize;
+    let description_size = read_u32(data, offset + 4) as usize;
+    let owner_start = offset + 12;
+    // Sizes are not trusted, so corrupted ones end parsing instead of overflowing.
+    let description_end = match owner_size
+      .checked_next_multiple_of(4)
+      .and_then(|owner_size| owner_start.checked_add(owner_size))
+      .and_then(|description_start| description_start.checked_add(description_size))
+    {
+      Some(description_end) if description_end <= data.len() => description_end,
+      _ => break,
+    };
+    let description_start = description_end - description_size;
+
+    let owner = &data[owner_start..owner_start + owner_size];
+    notes.push(Note {
+      owner: owner.strip_suffix(b"\0").unwrap_or(owner).to_vec(),
+      note_type: read_u32(data, offset + 8),
+      description: data[description_start..description_end].to_vec(),
+    });
+    offset = description_end.next_multiple_of(4);
+  }
+
+  notes
+}
+
+/// Parses **NT_FILE** note into start address, file offset and path of every file mapping.
+fn parse_file_note(note: &[u8]) -> Vec<(usize, usize, String)> {
+  if note.len() < 16 {
+    return Vec::new();
+  }
+  let count = read_u64(note, 0) as usize;
+  let page_size = read_u64(note, 8) as usize;
+  let names_start = match count.checked_mul(24).and_then(|size| size.checked_add(16)) {
+    Some(names_start) if names_start <= note.len() => names_start,
+    _ => return Vec::new(),
+  };
+
+  note[names_start..]
+    .split(|byte| *byte == 0)
+    .take(count)
+    .enumerate()
+    .map(|(index, name)| {
+      let entry = 16 + index * 24;
+      (
+        read_u64(note, entry) as usize,
+        (read_u64(note, entry + 16) as usize).wrapping_mul(page_size),
+        String::from_utf8_lossy(name).into_owned(),
+      )
+    })
+    .collect()
+}
+
+/// Formats row of `/proc/\[pid\]/maps` file, see `parse_maps_line()`.
+pub(crate) fn maps_line(
+  start: usize,
+  end: usize,
+  permissions: &str,
+  offset: usize,
+  path: Option<&str>,
+) -> String {
+  format!(
+    "{:x}-{:x} {} {:08x} 00:00 0 {}\n",
+    start,
+    end,
+    permissions,
+    offset,
+    path.unwrap_or("")
+  )
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+  u16::from_ne_bytes(data[offset..offset + 2].try_into().unwrap())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+  u32::from_ne_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+  u64::from_ne_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse_file_note, parse_notes, CORE_OWNER, NT_FILE};
+
+  /// Builds ELF note of `note_type` owned by `owner`, with padding.
+  fn note(owner: &[u8], note_type: u32, description: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(owner.len() as u32 + 1).to_ne_bytes());
+    bytes.extend_from_slice(&(description.len() as u32).to_ne_bytes());
+    bytes.extend_from_slice(&note_type.to_ne_bytes());
+    bytes.extend_from_slice(owner);
+    bytes.push(0);
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+    bytes.extend_from_slice(description);
+    bytes.resize(bytes.len().next_multiple_of(4), 0);
+    bytes
+  }
+
+  #[test]
+  fn parses_notes() {
+    let mut data = note(CORE_OWNER, 3, b"prpsinfo");
+    data.extend(note(b"TRICKSTER", 1, b"maps"));
+
+    let notes = parse_notes(&data);
+    assert_eq!(notes.len(), 2);
+    assert_eq!(notes[0].owner, CORE_OWNER);
+    assert_eq!(notes[0].note_type, 3);
+    assert_eq!(notes[0].description, b"prpsinfo");
+    assert_eq!(notes[1].owner, b"TRICKSTER");
+    assert_eq!(notes[1].description, b"maps");
+  }
+
+  #[test]
+  fn stops_at_malformed_note() {
+    let mut data = note(CORE_OWNER, 3, b"prpsinfo");
+    let mut truncated = note(CORE_OWNER, 4, b"auxv");
+    truncated.truncate(truncated.len() - 4);
+    data.extend(truncated);
+    assert_eq!(parse_notes(&data).len(), 1);
+
+    let mut huge = note(CORE_OWNER, 4, b"auxv");
+    huge[0..4].copy_from_slice(&u32::MAX.to_ne_bytes());
+    huge[4..8].copy_from_slice(&u32::MAX.to_ne_bytes());
+    assert!(parse_notes(&huge).is_empty());
+    assert!(parse_notes(&[0; 11]).is_empty());
+  }
+
+  #[test]
+  fn parses_file_note() {
+    let mut description = Vec::new();
+    for value in &[
+      2u64,
+      0x1000,
+      0x400000,
+      0x401000,
+      0,
+      0x7f0000000000,
+      0x7f0000002000,
+      3,
+    ] {
+      description.extend_from_slice(&value.to_ne_bytes());
+    }
+    description.extend_from_slice(b"/bin/game\0/lib/libc.so.6\0");
+
+    let files = parse_file_note(&description);
+    assert_eq!(
+      files,
+      vec![
+        (0x400000, 0, "/bin/game".to_string()),
+        (0x7f0000000000, 0x3000, "/lib/libc.so.6".to_string()),
+      ]
+    );
+
+    let data = note(CORE_OWNER, NT_FILE, &description);
+    assert_eq!(parse_notes(&data)[0].description, description);
+  }
+
+  #[test]
+  fn rejects_malformed_file_note() {
+    assert!(parse_file_note(&[0; 15]).is_empty());
+
+    let mut description = Vec::new();
+    for value in &[u64::MAX, 0x1000] {
+      description.extend_from_slice(&value.to_ne_bytes());
+    }
+    assert!(parse_file_note(&description).is_empty());
+  }
+}
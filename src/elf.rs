@@ -0,0 +1,398 @@
+use anyhow::Result;
+use std::cmp;
+use std::convert::TryInto;
+
+#[cfg(feature = "demangle")]
+use super::demangle::{demangle, demangle_without_params};
+
+/// Program header type of loadable segment.
+pub(crate) const PT_LOAD: u32 = 1;
+/// Program header flag of executable segment.
+#[cfg(target_arch = "x86_64")]
+pub(crate) const PF_X: u32 = 1;
+/// Program header type of segment containing notes.
+const PT_NOTE: u32 = 4;
+/// Type of note containing build-id, with `GNU` owner.
+const NT_GNU_BUILD_ID: u32 = 3;
+/// Section header types of symbol tables.
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNSYM: u32 = 11;
+/// Section header type of relocations with addends.
+const SHT_RELA: u32 = 4;
+/// Section header type of symbol version table (`.gnu.version`).
+const SHT_GNU_VERSYM: u32 = 0x6fff_ffff;
+/// Types of symbols describing sections and source files, not code or data.
+const STT_SECTION: u8 = 3;
+const STT_FILE: u8 = 4;
+/// Binding of symbols not visible outside of the file.
+const STB_LOCAL: u8 = 0;
+/// Section index of undefined symbols.
+const SHN_UNDEF: u16 = 0;
+/// Bit of symbol version marking symbol which is not the default version.
+const VERSYM_HIDDEN: u16 = 0x8000;
+
+const HEADER_SIZE: usize = 0x40;
+const PROGRAM_HEADER_SIZE: usize = 0x38;
+const SECTION_HEADER_SIZE: usize = 0x40;
+const SYMBOL_SIZE: usize = 0x18;
+const RELOCATION_SIZE: usize = 0x18;
+
+/// Loadable segment described by ELF program header.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ProgramHeader {
+  pub p_type: u32,
+  pub flags: u32,
+  pub offset: usize,
+  pub vaddr: usize,
+  pub file_size: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SectionHeader {
+  sh_type: u32,
+  offset: usize,
+  size: usize,
+  link: usize,
+  entry_size: usize,
+}
+
+/// Symbol defined in ELF file, `value` is address before relocation.
+#[derive(Clone, Debug)]
+pub(crate) struct ElfSymbol {
+  pub name: String,
+  pub value: usize,
+  /// **true** for symbols with local binding, e.g. static functions.
+  pub local: bool,
+  /// **false** for symbols which are not the default version,  
+  /// e.g. `memcpy@GLIBC_2.2.5` when `memcpy@@GLIBC_2.14` is defined.
+  pub default_version: bool,
+}
+
+/// 64-bit ELF file with byte order of the running machine, read into memory.
+pub(crate) struct ElfFile {
+  data: Vec<u8>,
+}
+
+impl ElfFile {
+  /// Validates ELF header of `data`, `path` is used only in error messages.
+  pub fn parse(data: Vec<u8>, path: &str) -> Result<ElfFile> {
+    let native_data = if cfg!(target_endian = "little") { 1 } else { 2 };
+
+    if data.len() < HEADER_SIZE || data[..4] != b"\x7fELF"[..] {
+      return Err(anyhow!(
+        "Could not parse ELF file {} (invalid header).",
+        path
+      ));
+    }
+    if data[4] != 2 || data[5] != native_data {
+      return Err(anyhow!(
+        "Could not parse ELF file {} (only 64-bit files with native byte order are supported).",
+        path
+      ));
+    }
+
+    Ok(ElfFile { data })
+  }
+
+  /// Returns program headers of the file, skipping ones out of its bounds.
+  pub fn program_headers(&self) -> Vec<ProgramHeader> {
+    let offset = self.read_u64(0x20) as usize;
+    let count = self.read_u16(0x38) as usize;
+
+    (0..count)
+      .map(|index| offset.saturating_add(index * PROGRAM_HEADER_SIZE))
+      .filter(|header| header.saturating_add(PROGRAM_HEADER_SIZE) <= self.data.len())
+      .map(|header| ProgramHeader {
+        p_type: self.read_u32(header),
+        flags: self.read_u32(header + 0x04),
+        offset: self.read_u64(header + 0x08) as usize,
+        vaddr: self.read_u64(header + 0x10) as usize,
+        file_size: self.read_u64(header + 0x20) as usize,
+      })
+      .collect()
+  }
+
+  /// Returns contents of segment described by `header`, cut to bounds of the file.
+  #[cfg(target_arch = "x86_64")]
+  pub fn segment_data(&self, header: &ProgramHeader) -> &[u8] {
+    let start = header.offset.min(self.data.len());
+    let end = header.offset.saturating_add(header.file_size).min(self.data.len());
+    &self.data[start..end]
+  }
+
+  /// Returns GNU build-id of the file, as lowercase hexadecimal string.
+  pub fn build_id(&self) -> Option<String> {
+    for segment in self.program_headers().into_iter().filter(|header| header.p_type == PT_NOTE) {
+      let end = segment.offset.saturating_add(segment.file_size).min(self.data.len());
+      let mut note = segment.offset;
+
+      // Each note is a header followed by name and descriptor, both padded to 4 bytes.
+      while note.saturating_add(12) <= end {
+        let name_size = self.read_u32(note) as usize;
+        let descriptor_size = self.read_u32(note + 4) as usize;
+        let note_type = self.read_u32(note + 8);
+        let name = note + 12;
+        let descriptor = name.saturating_add(align_up(name_size, 4));
+        if descriptor.saturating_add(descriptor_size) > end {
+          break;
+        }
+
+        if note_type == NT_GNU_BUILD_ID && self.data[name..name + name_size] == b"GNU\0"[..] {
+          let build_id = &self.data[descriptor..descriptor + descriptor_size];
+          return Some(build_id.iter().map(|byte| format!("{:02x}", byte)).collect());
+        }
+        note = descriptor + align_up(descriptor_size, 4);
+      }
+    }
+
+    None
+  }
+
+  /// Returns value of symbol named `name`. Global symbols and default  
+  /// versions of them are preferred, if there are multiple such symbols.  
+  /// With `demangle` feature, demangled names of symbols are matched too,  
+  /// with or without parameter list of C++ functions.
+  pub fn find_symbol(&self, name: &str) -> Option<usize> {
+    let symbols = self.symbols();
+    let value = symbols
+      .iter()
+      .filter(|candidate| candidate.name == name)
+      .max_by_key(|candidate| (!candidate.local, candidate.default_version))
+      .map(|candidate| candidate.value);
+
+    // Mangled names are compared with `name` only if there is no exact match.
+    #[cfg(feature = "demangle")]
+    let value = value.or_else(|| {
+      symbols
+        .iter()
+        .filter(|candidate| {
+          demangle(&candidate.name).as_deref() == Some(name)
+            || demangle_without_params(&candidate.name).as_deref() == Some(name)
+        })
+        .max_by_key(|candidate| (!candidate.local, candidate.default_version))
+        .map(|candidate| candidate.value)
+    });
+
+    value
+  }
+
+  /// Returns symbol with the greatest value not greater than `value`, that is  
+  /// symbol `value` most likely belongs to. Global symbols, default versions  
+  /// of them and names with fewer leading underscores (e.g. `malloc` rather  
+  /// than `__libc_malloc`) are preferred, if there are multiple such symbols.
+  pub fn nearest_symbol(&self, value: usize) -> Option<ElfSymbol> {
+    self
+      .symbols()
+      .into_iter()
+      .filter(|candidate| candidate.value != 0 && candidate.value <= value)
+      .max_by_key(|candidate| {
+        (
+          candidate.value,
+          !candidate.local,
+          candidate.default_version,
+          cmp::Reverse(candidate.name.len() - candidate.name.trim_start_matches('_').len()),
+        )
+      })
+  }
+
+  /// Returns symbols defined in `.dynsym` and `.symtab` sections.
+  pub fn symbols(&self) -> Vec<ElfSymbol> {
+    let sections = self.section_headers();
+    let mut symbols: Vec<ElfSymbol> = Vec::new();
+
+    for section in sections
+      .iter()
+      .filter(|section| section.sh_type == SHT_DYNSYM || section.sh_type == SHT_SYMTAB)
+    {
+      let strings = match sections.get(section.link) {
+        Some(strings) => strings,
+        None => continue,
+      };
+      let versions = if section.sh_type == SHT_DYNSYM {
+        sections.iter().find(|versions| versions.sh_type == SHT_GNU_VERSYM)
+      } else {
+        None
+      };
+      let entry_size = if section.entry_size == 0 {
+        SYMBOL_SIZE
+      } else {
+        section.entry_size
+      };
+
+      for index in 0..section.size / entry_size {
+        let symbol = section.offset.saturating_add(index * entry_size);
+        if symbol.saturating_add(SYMBOL_SIZE) > self.data.len() {
+          break;
+        }
+        let symbol_type = self.data[symbol + 0x04] & 0xf;
+        if self.read_u16(symbol + 0x06) == SHN_UNDEF
+          || symbol_type == STT_SECTION
+          || symbol_type == STT_FILE
+        {
+          continue;
+        }
+
+        let name = match self.read_string(strings, self.read_u32(symbol) as usize) {
+          Some(name) if !name.is_empty() => name,
+          _ => continue,
+        };
+        let version_entry = versions.map(|versions| versions.offset.saturating_add(index * 2));
+        let default_version = match version_entry {
+          Some(entry) if entry.saturating_add(2) <= self.data.len() => {
+            self.read_u16(entry) & VERSYM_HIDDEN == 0
+          }
+          _ => true,
+        };
+
+        symbols.push(ElfSymbol {
+          name,
+          value: self.read_u64(symbol + 0x08) as usize,
+          local: self.data[symbol + 0x04] >> 4 == STB_LOCAL,
+          default_version,
+        });
+      }
+    }
+
+    symbols
+  }
+
+  fn section_headers(&self) -> Vec<SectionHeader> {
+    let offset = self.read_u64(0x28) as usize;
+    let count = self.read_u16(0x3c) as usize;
+
+    (0..count)
+      .map(|index| offset.saturating_add(index * SECTION_HEADER_SIZE))
+      .filter(|header| header.saturating_add(SECTION_HEADER_SIZE) <= self.data.len())
+      .map(|header| SectionHeader {
+        sh_type: self.read_u32(header + 0x04),
+        offset: self.read_u64(header + 0x18) as usize,
+        size: self.read_u64(header + 0x20) as usize,
+        link: self.read_u32(header + 0x28) as usize,
+        entry_size: self.read_u64(header + 0x38) as usize,
+      })
+      .collect()
+  }
+
+  /// Returns virtual addresses relocations of `types` (e.g. `R_X86_64_JUMP_SLOT`)  
+  /// referring to symbol named `name` apply to, e.g. addresses of GOT slots  
+  /// of imported function. Only `SHT_RELA` sections are supported.
+  pub fn relocation_targets(&self, name: &str, types: &[u32]) -> Vec<usize> {
+    self.relocations(types, Some(name))
+  }
+
+  /// Returns virtual addresses all relocations of `types` apply to,  
+  /// regardless of symbol they refer to, see `relocation_targets()`.
+  #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+  pub fn all_relocation_targets(&self, types: &[u32]) -> Vec<usize> {
+    self.relocations(types, None)
+  }
+
+  /// Returns virtual addresses relocations of `types` apply to,  
+  /// only of ones referring to symbol named `name` if it is given.
+  fn relocations(&self, types: &[u32], name: Option<&str>) -> Vec<usize> {
+    let sections = self.section_headers();
+    let mut targets: Vec<usize> = Vec::new();
+
+    for section in sections.iter().filter(|section| section.sh_type == SHT_RELA) {
+      let symbols = match sections.get(section.link) {
+        Some(symbols) => symbols,
+        None => continue,
+      };
+      let strings = match sections.get(symbols.link) {
+        Some(strings) => strings,
+        None => continue,
+      };
+
+      for index in 0..section.size / RELOCATION_SIZE {
+        let relocation = section.offset.saturating_add(index * RELOCATION_SIZE);
+        if relocation.saturating_add(RELOCATION_SIZE) > self.data.len() {
+          break;
+        }
+
+        let info = self.read_u64(relocation + 0x08);
+        if !types.contains(&(info as u32)) {
+          continue;
+        }
+        let name = match name {
+          Some(name) => name,
+          None => {
+            targets.push(self.read_u64(relocation) as usize);
+            continue;
+          }
+        };
+        let symbol = symbols.offset.saturating_add((info >> 32) as usize * SYMBOL_SIZE);
+        if symbol.saturating_add(SYMBOL_SIZE) > self.data.len() {
+          continue;
+        }
+
+        if self.read_string(strings, self.read_u32(symbol) as usize).as_deref() == Some(name) {
+          targets.push(self.read_u64(relocation) as usize);
+        }
+      }
+    }
+
+    targets
+  }
+
+  /// Reads null-terminated string at `index` of string table `strings`.
+  fn read_string(&self, strings: &SectionHeader, index: usize) -> Option<String> {
+    let start = strings.offset.checked_add(index)?;
+    let end = strings.offset.checked_add(strings.size)?.min(self.data.len());
+    let bytes = self.data.get(start..end)?;
+    let length = bytes.iter().position(|byte| *byte == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..length]).into_owned())
+  }
+
+  fn read_u16(&self, offset: usize) -> u16 {
+    u16::from_ne_bytes(self.data[offset..offset + 2].try_into().unwrap())
+  }
+
+  fn read_u32(&self, offset: usize) -> u32 {
+    u32::from_ne_bytes(self.data[offset..offset + 4].try_into().unwrap())
+  }
+
+  fn read_u64(&self, offset: usize) -> u64 {
+    u64::from_ne_bytes(self.data[offset..offset + 8].try_into().unwrap())
+  }
+}
+
+fn align_up(value: usize, alignment: usize) -> usize {
+  value.saturating_add(alignment - 1) & !(alignment - 1)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ElfFile, PT_LOAD};
+  use std::fs;
+
+  /// Returns parsed executable of the running test.
+  fn current_executable() -> ElfFile {
+    ElfFile::parse(fs::read("/proc/self/exe").unwrap(), "/proc/self/exe").unwrap()
+  }
+
+  #[test]
+  fn rejects_invalid_files() {
+    assert!(ElfFile::parse(b"#!/bin/sh\n".to_vec(), "script").is_err());
+
+    let mut header = vec![0u8; 0x40];
+    header[..4].copy_from_slice(b"\x7fELF");
+    header[5] = if cfg!(target_endian = "little") { 1 } else { 2 };
+    header[4] = 1;
+    assert!(ElfFile::parse(header.clone(), "32-bit").is_err());
+    header[4] = 2;
+    assert!(ElfFile::parse(header, "64-bit").is_ok());
+  }
+
+  #[test]
+  fn finds_symbols_of_executable() {
+    let elf = current_executable();
+    assert!(elf.program_headers().iter().any(|header| header.p_type == PT_LOAD));
+
+    let main = elf.find_symbol("main").unwrap();
+    assert!(elf.find_symbol("not_defined_symbol").is_none());
+    let nearest = elf.nearest_symbol(main + 1).unwrap();
+    assert_eq!(nearest.value, main);
+    assert_eq!(nearest.name, "main");
+    assert!(!nearest.local);
+  }
+}
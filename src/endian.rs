@@ -0,0 +1,49 @@
+use anyhow::Result;
+use std::mem;
+
+use super::Process;
+
+/// Generates `read_*_le()`, `read_*_be()`, `write_*_le()` and `write_*_be()`  
+/// methods of `Process` for primitive numeric types.
+macro_rules! endian_accessors {
+  ($($ty:ident => $read_le:ident, $read_be:ident, $write_le:ident, $write_be:ident;)*) => {
+    impl Process {
+      $(
+        #[doc = concat!("Reads little-endian `", stringify!($ty), "` at `address` in remote process,  ")]
+        /// regardless of local machine's endianness, see `read()`.
+        pub fn $read_le(&self, address: usize) -> Result<$ty> {
+          Ok($ty::from_le_bytes(self.read::<[u8; mem::size_of::<$ty>()]>(address)?))
+        }
+
+        #[doc = concat!("Reads big-endian `", stringify!($ty), "` at `address` in remote process,  ")]
+        /// regardless of local machine's endianness, see `read()`.
+        pub fn $read_be(&self, address: usize) -> Result<$ty> {
+          Ok($ty::from_be_bytes(self.read::<[u8; mem::size_of::<$ty>()]>(address)?))
+        }
+
+        #[doc = concat!("Writes `value` as little-endian `", stringify!($ty), "` at `address` in remote process,  ")]
+        /// regardless of local machine's endianness, see `write()`.
+        pub fn $write_le(&self, address: usize, value: $ty) -> Result<()> {
+          self.write_memory_bytes(address, &value.to_le_bytes())
+        }
+
+        #[doc = concat!("Writes `value` as big-endian `", stringify!($ty), "` at `address` in remote process,  ")]
+        /// regardless of local machine's endianness, see `write()`.
+        pub fn $write_be(&self, address: usize, value: $ty) -> Result<()> {
+          self.write_memory_bytes(address, &value.to_be_bytes())
+        }
+      )*
+    }
+  };
+}
+
+endian_accessors! {
+  u16 => read_u16_le, read_u16_be, write_u16_le, write_u16_be;
+  u32 => read_u32_le, read_u32_be, write_u32_le, write_u32_be;
+  u64 => read_u64_le, read_u64_be, write_u64_le, write_u64_be;
+  i16 => read_i16_le, read_i16_be, write_i16_le, write_i16_be;
+  i32 => read_i32_le, read_i32_be, write_i32_le, write_i32_be;
+  i64 => read_i64_le, read_i64_be, write_i64_le, write_i64_be;
+  f32 => read_f32_le, read_f32_be, write_f32_le, write_f32_be;
+  f64 => read_f64_le, read_f64_be, write_f64_le, write_f64_be;
+}
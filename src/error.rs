@@ -0,0 +1,112 @@
+use nix::errno::Errno;
+use nix::unistd::Pid;
+use std::error;
+use std::fmt;
+use std::io;
+
+use super::permission::{diagnose, PermissionDenialReason};
+
+/// Errors which callers may want to handle programmatically. Methods of this  
+/// crate return [`anyhow::Error`], which can be downcast to this type.
+///
+/// [`anyhow::Error`]: https://docs.rs/anyhow/1/anyhow/struct.Error.html
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{Process, ProcessError};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let ctx = Process::new("current_process_name")?;
+///
+///   match ctx.read::<u32>(0x601040) {
+///     Ok(value) => println!("value: {}", value),
+///     Err(error) => match error.downcast_ref::<ProcessError>() {
+///       Some(ProcessError::ProcessExited(_)) => println!("target closed"),
+///       Some(ProcessError::PermissionDenied { reason, .. }) => println!("{}", reason),
+///       _ => return Err(error.into()),
+///     },
+///   }
+///   Ok(())
+/// }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ProcessError {
+  /// Process exited (or its pid was reused by other process).
+  ProcessExited(Pid),
+  /// Caller is not allowed to access memory of the process. `reason` tells  
+  /// why (e.g. Yama `ptrace_scope` restriction) and how to fix it.
+  PermissionDenied {
+    pid: Pid,
+    reason: PermissionDenialReason,
+  },
+  /// Memory read back after write differs from written bytes,  
+  /// see `Process::set_write_verification()`. `differences` holds  
+  /// address, written byte and read byte of every differing byte.
+  WriteVerificationFailed {
+    pid: Pid,
+    address: usize,
+    differences: Vec<(usize, u8, u8)>,
+  },
+}
+
+impl fmt::Display for ProcessError {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ProcessError::ProcessExited(pid) => write!(formatter, "Process {} exited.", pid),
+      ProcessError::PermissionDenied { pid, reason } => write!(
+        formatter,
+        "Permission denied to access process {} ({}).",
+        pid, reason
+      ),
+      ProcessError::WriteVerificationFailed {
+        pid,
+        address,
+        differences,
+      } => {
+        write!(
+          formatter,
+          "Memory written at {:#x} in process {} differs from written bytes (",
+          address, pid
+        )?;
+        for (index, (address, expected, actual)) in differences.iter().enumerate() {
+          if index > 0 {
+            write!(formatter, ", ")?;
+          }
+          write!(formatter, "{:#x}: {:02x} -> {:02x}", address, expected, actual)?;
+        }
+        write!(formatter, ").")
+      }
+    }
+  }
+}
+
+impl error::Error for ProcessError {}
+
+/// Converts error of system call accessing the process (e.g. **process_vm_readv(2)**  
+/// or **ptrace(2)**) into [`ProcessError`] if it has a dedicated variant,  
+/// or into error with generic `message` otherwise.
+///
+/// [`ProcessError`]: enum.ProcessError.html
+pub(crate) fn system_call_error(pid: Pid, error: nix::Error, message: String) -> anyhow::Error {
+  match error {
+    nix::Error::Sys(Errno::ESRCH) => ProcessError::ProcessExited(pid).into(),
+    nix::Error::Sys(Errno::EPERM) | nix::Error::Sys(Errno::EACCES) => ProcessError::PermissionDenied {
+      pid,
+      reason: diagnose(pid),
+    }
+    .into(),
+    _ => anyhow!("{} ({}).", message, error),
+  }
+}
+
+/// Converts error returned by `MemoryBackend` into [`ProcessError`]  
+/// if it has a dedicated variant, see `system_call_error()`.
+///
+/// [`ProcessError`]: enum.ProcessError.html
+pub(crate) fn backend_error(pid: Pid, error: io::Error, message: String) -> anyhow::Error {
+  match error.raw_os_error() {
+    Some(errno) => system_call_error(pid, nix::Error::Sys(Errno::from_i32(errno)), message),
+    None => anyhow!("{} ({}).", message, error),
+  }
+}
@@ -0,0 +1,214 @@
+use anyhow::Result;
+use nix::sys::ptrace;
+use nix::sys::signal::Signal;
+use nix::sys::wait::WaitStatus;
+use nix::unistd::Pid;
+
+use super::error::system_call_error;
+use super::{Registers, TracedProcess};
+
+/// Event reported by traced process, see `TracedProcess::events()`.
+#[derive(Clone, Debug)]
+pub enum TraceEvent {
+  /// Thread stopped on a breakpoint (**SIGTRAP**), e.g. `int3` instruction on x86_64.  
+  /// Registers of the thread are read when the event is reported.
+  Breakpoint { tid: Pid, registers: Box<Registers> },
+  /// Signal is about to be delivered to the thread. It is delivered  
+  /// when iteration continues, unless `TraceEvents::suppress_signal()` is called.
+  Signal { tid: Pid, signal: Signal },
+  /// Thread entered system call, with raw argument registers (see `Registers::syscall_args()`).  
+  /// Reported only when system call tracing is enabled (see `TracedProcess::set_syscall_tracing()`).
+  SyscallEnter { tid: Pid, number: i64, args: [u64; 6] },
+  /// Thread returned from system call, `result` is negative error number on failure.  
+  /// Reported only when system call tracing is enabled.
+  SyscallExit { tid: Pid, number: i64, result: i64 },
+  /// Thread `tid` created thread `new_tid`, which is traced from now on.
+  ThreadCreated { tid: Pid, new_tid: Pid },
+  /// Thread, other than the main thread, exited with exit code.
+  ThreadExited { tid: Pid, code: i32 },
+  /// Thread `tid` created child process `child` with **fork(2)** or **vfork(2)**,  
+  /// which is traced from now on. Reported only when following forks  
+  /// is enabled (see `TracedProcess::set_follow_forks()`).
+  ProcessForked { tid: Pid, child: Pid },
+  /// Traced process executed new program with **execve(2)**.  
+  /// Its main thread is the only one left and is stopped.
+  Exec { pid: Pid },
+  /// All threads of traced process exited, process exit code is given.
+  ProcessExited { pid: Pid, code: i32 },
+  /// Traced process was killed by a signal.
+  ProcessKilled { pid: Pid, signal: Signal },
+}
+
+/// Iterator over events of traced process, created by `TracedProcess::events()`.
+pub struct TraceEvents<'a> {
+  traced: &'a mut TracedProcess,
+  pending_signal: Option<Signal>,
+  finished: bool,
+}
+
+impl<'a> TraceEvents<'a> {
+  /// Returns traced process, so it can be inspected or modified  
+  /// while threads are stopped by the last event.
+  pub fn traced(&mut self) -> &mut TracedProcess {
+    self.traced
+  }
+
+  /// Prevents signal of the last `TraceEvent::Signal` event from being  
+  /// delivered to the thread when iteration continues.
+  pub fn suppress_signal(&mut self) {
+    self.pending_signal = None;
+  }
+
+  fn next_event(&mut self) -> Result<TraceEvent> {
+    // Resume threads stopped by the previous event.
+    match self.pending_signal.take() {
+      Some(signal) => self.traced.cont_with_signal(signal)?,
+      None => self.traced.cont()?,
+    }
+
+    loop {
+      match self.traced.wait_for_stop()? {
+        WaitStatus::Stopped(tid, Signal::SIGTRAP) => {
+          return Ok(TraceEvent::Breakpoint {
+            tid,
+            registers: Box::new(self.traced.get_regs(tid)?),
+          })
+        }
+        WaitStatus::Stopped(tid, signal) => {
+          self.pending_signal = Some(signal);
+          return Ok(TraceEvent::Signal { tid, signal });
+        }
+        WaitStatus::PtraceSyscall(tid) => {
+          let registers = self.traced.get_regs(tid)?;
+          let number = registers.syscall_number();
+          if !self.traced.is_syscall_reported(number) {
+            self.traced.resume(tid, None)?;
+            continue;
+          }
+
+          if self.traced.is_in_syscall(tid) {
+            return Ok(TraceEvent::SyscallEnter {
+              tid,
+              number,
+              args: registers.syscall_args(),
+            });
+          }
+          return Ok(TraceEvent::SyscallExit {
+            tid,
+            number,
+            result: registers.syscall_result(),
+          });
+        }
+        WaitStatus::PtraceEvent(tid, _, libc::PTRACE_EVENT_CLONE) => {
+          let new_tid = ptrace::getevent(tid).map_err(|error| {
+            system_call_error(
+              tid,
+              error,
+              format!("Could not get id of thread created by thread {}", tid),
+            )
+          })?;
+
+          let new_tid = Pid::from_raw(new_tid as i32);
+          let pid = self.traced.thread_pid(tid).unwrap_or(tid);
+          self.traced.add_thread(new_tid, pid);
+          return Ok(TraceEvent::ThreadCreated { tid, new_tid });
+        }
+        WaitStatus::PtraceEvent(tid, _, libc::PTRACE_EVENT_FORK)
+        | WaitStatus::PtraceEvent(tid, _, libc::PTRACE_EVENT_VFORK) => {
+          let child = ptrace::getevent(tid).map_err(|error| {
+            system_call_error(
+              tid,
+              error,
+              format!("Could not get id of process created by thread {}", tid),
+            )
+          })?;
+
+          let child = Pid::from_raw(child as i32);
+          self.traced.add_process(child);
+          return Ok(TraceEvent::ProcessForked { tid, child });
+        }
+        // Process id is reported, thread which called execve(2) took it over.
+        WaitStatus::PtraceEvent(pid, _, libc::PTRACE_EVENT_EXEC) => {
+          self.traced.handle_exec(pid);
+          return Ok(TraceEvent::Exec { pid });
+        }
+        WaitStatus::Exited(pid, code) if self.traced.is_traced_process(pid) => {
+          self.traced.remove_process(pid);
+          return Ok(TraceEvent::ProcessExited { pid, code });
+        }
+        WaitStatus::Exited(tid, code) => return Ok(TraceEvent::ThreadExited { tid, code }),
+        WaitStatus::Signaled(pid, signal, _) if self.traced.is_traced_process(pid) => {
+          self.traced.remove_process(pid);
+          return Ok(TraceEvent::ProcessKilled { pid, signal });
+        }
+        // Interrupt stops, group-stops and initial stops of new threads
+        // are not reported, only the thread which stopped is resumed.
+        status => {
+          if let Some(tid) = status.pid() {
+            if self.traced.is_stopped(tid) {
+              self.traced.resume(tid, None)?;
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+impl<'a> Iterator for TraceEvents<'a> {
+  type Item = Result<TraceEvent>;
+
+  fn next(&mut self) -> Option<Result<TraceEvent>> {
+    if self.finished || self.traced.traced_threads().is_empty() {
+      return None;
+    }
+
+    let event = self.next_event();
+    if event.is_err() {
+      self.finished = true;
+    }
+
+    Some(event)
+  }
+}
+
+impl TracedProcess {
+  /// Resumes the process and returns iterator over its events. Before waiting  
+  /// for the next event, threads stopped by the previous one are resumed.  
+  /// Only the thread which reported an event is stopped while it is handled,  
+  /// other threads keep running. Iteration ends when all traced processes exit.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, TraceEvent};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   let mut events = traced.events();
+  ///   while let Some(event) = events.next() {
+  ///     match event? {
+  ///       TraceEvent::Breakpoint { tid, registers } => {
+  ///         println!("{} hit breakpoint at {:#x}", tid, registers.instruction_pointer());
+  ///         events.traced().write::<u32>(0x601040, &100)?;
+  ///       }
+  ///       TraceEvent::ProcessExited { pid, code } => println!("{} exited with {}", pid, code),
+  ///       event => println!("{:?}", event),
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `ThreadCreated { tid: Pid(2686), new_tid: Pid(2690) }`  
+  /// `2690 hit breakpoint at 0x401127`  
+  /// `2686 exited with 0`
+  pub fn events(&mut self) -> TraceEvents<'_> {
+    TraceEvents {
+      traced: self,
+      pending_signal: None,
+      finished: false,
+    }
+  }
+}
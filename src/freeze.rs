@@ -0,0 +1,92 @@
+use anyhow::Result;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use super::Process;
+
+/// Guard returned by `Process::freeze()`. While it is alive, background  
+/// thread keeps writing frozen value to remote process memory.  
+/// Dropping the guard stops the thread and waits for it to finish.
+pub struct FreezeGuard {
+  address: usize,
+  stop_sender: Option<mpsc::Sender<()>>,
+  thread: Option<thread::JoinHandle<()>>,
+}
+
+impl FreezeGuard {
+  /// Returns address of the frozen value.
+  pub fn address(&self) -> usize {
+    self.address
+  }
+
+  /// Returns **true** if value is still being frozen. Background thread  
+  /// stops on its own if write fails, e.g. because target process exited.
+  pub fn is_active(&self) -> bool {
+    self.thread.as_ref().is_some_and(|thread| !thread.is_finished())
+  }
+
+  /// Stops freezing the value. This is equivalent to dropping the guard.
+  pub fn unfreeze(self) {}
+}
+
+impl Drop for FreezeGuard {
+  fn drop(&mut self) {
+    // Dropping the sender wakes the thread up immediately,
+    // instead of waiting for the current interval to pass.
+    self.stop_sender.take();
+    if let Some(thread) = self.thread.take() {
+      let _ = thread.join();
+    }
+  }
+}
+
+impl Process {
+  /// Freezes value at `address` in remote process, that is writes `bytes`  
+  /// at `address` every `interval` in background thread, until returned  
+  /// [`FreezeGuard`] is dropped. The first write happens before this method  
+  /// returns, so if it fails, [`Err`] is returned and no thread is spawned.
+  ///
+  /// [`FreezeGuard`]: struct.FreezeGuard.html
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use std::time::Duration;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let health_freeze = ctx.freeze(0x601040, &100i32.to_ne_bytes(), Duration::from_millis(50))?;
+  ///   // ... health stays at 100 ...
+  ///   drop(health_freeze);
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn freeze(&self, address: usize, bytes: &[u8], interval: Duration) -> Result<FreezeGuard> {
+    self.write_memory_bytes(address, bytes)?;
+
+    let process = self.clone();
+    let bytes = bytes.to_vec();
+    let (stop_sender, stop_receiver) = mpsc::channel::<()>();
+
+    let thread =
+      thread::Builder::new()
+        .name(format!("trickster-freeze-{:#x}", address))
+        .spawn(move || {
+          while let Err(mpsc::RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(interval) {
+            if process.write_memory_bytes(address, &bytes).is_err() {
+              break;
+            }
+          }
+        })?;
+
+    Ok(FreezeGuard {
+      address,
+      stop_sender: Some(stop_sender),
+      thread: Some(thread),
+    })
+  }
+}
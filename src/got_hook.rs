@@ -0,0 +1,140 @@
+use anyhow::Result;
+
+use super::Process;
+
+/// Relocation types of GOT slots, for functions called through PLT  
+/// and for imported symbols which address is taken directly.
+#[cfg(target_arch = "x86_64")]
+pub(crate) const GOT_RELOCATION_TYPES: [u32; 2] = [
+  7, // R_X86_64_JUMP_SLOT
+  6, // R_X86_64_GLOB_DAT
+];
+#[cfg(target_arch = "aarch64")]
+pub(crate) const GOT_RELOCATION_TYPES: [u32; 2] = [
+  1026, // R_AARCH64_JUMP_SLOT
+  1025, // R_AARCH64_GLOB_DAT
+];
+
+/// Imported symbol redirected with `Process::hook_got()`.  
+/// It remembers original contents of GOT slots, so the hook can be  
+/// removed with `unhook()`. Dropping `GotHook` keeps the hook in place.
+#[derive(Clone, Debug)]
+pub struct GotHook {
+  module: String,
+  symbol: String,
+  slots: Vec<(usize, usize)>,
+}
+
+impl GotHook {
+  /// Returns name of module which imports of the symbol are hooked.
+  pub fn module(&self) -> &str {
+    &self.module
+  }
+
+  /// Returns name of the hooked symbol.
+  pub fn symbol(&self) -> &str {
+    &self.symbol
+  }
+
+  /// Returns addresses of GOT slots the hook was written to.
+  pub fn slots(&self) -> Vec<usize> {
+    self.slots.iter().map(|(slot, _)| *slot).collect()
+  }
+
+  /// Returns original address stored in the first GOT slot, e.g. to call  
+  /// the original function from the hook. With lazy binding it may be  
+  /// address of PLT stub, which resolves the symbol when called.
+  pub fn original(&self) -> usize {
+    self.slots[0].1
+  }
+
+  /// Restores original contents of GOT slots.
+  pub fn unhook(self, process: &Process) -> Result<()> {
+    for (slot, original) in &self.slots {
+      write_slot(process, *slot, *original)?;
+    }
+    Ok(())
+  }
+}
+
+impl Process {
+  /// Redirects calls of `symbol` imported by module `module_name` to `new_address`,  
+  /// by overwriting GOT slots of the symbol, which are located by parsing relocations  
+  /// (`.rela.plt` and `.rela.dyn`) of ELF file the module is mapped from.  
+  /// Only calls made by that module are affected. If the module does not import  
+  /// `symbol`, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// Slots are written through `/proc/\[pid\]/mem`, which ignores memory protection,  
+  /// so GOT made read-only by RELRO can be hooked too. Returned [`GotHook`] remembers  
+  /// original addresses, so the hook can be removed with `GotHook::unhook()`.
+  ///
+  /// [`GotHook`]: struct.GotHook.html
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `hook_got();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let replacement = ctx.resolve_symbol("libhook.so", "hooked_write")?;
+  ///   let hook = ctx.hook_got("game", "write", replacement)?;
+  ///   println!("original write: {:#x}", hook.original());
+  ///
+  ///   // ... later ...
+  ///   hook.unhook(&ctx)?;
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `original write: 0x7f0c8e4b2e40`
+  pub fn hook_got(&self, module_name: &str, symbol: &str, new_address: usize) -> Result<GotHook> {
+    let module = self.module(module_name)?;
+    let elf = module.read_elf()?;
+    let load_bias = module.load_bias_of(&elf)?;
+
+    let targets = elf.relocation_targets(symbol, &GOT_RELOCATION_TYPES);
+    if targets.is_empty() {
+      return Err(anyhow!(
+        "Could not find GOT slot of {} in module {}.",
+        symbol,
+        module_name
+      ));
+    }
+
+    let mut slots: Vec<(usize, usize)> = Vec::new();
+    for target in targets {
+      let slot = load_bias.wrapping_add(target);
+      slots.push((slot, self.read::<usize>(slot)?));
+    }
+
+    let hook = GotHook {
+      module: module_name.to_string(),
+      symbol: symbol.to_string(),
+      slots,
+    };
+    for (index, (slot, _)) in hook.slots.iter().enumerate() {
+      if let Err(error) = write_slot(self, *slot, new_address) {
+        // Slots hooked so far are restored, so symbol is hooked either everywhere or nowhere.
+        for (slot, original) in &hook.slots[..index] {
+          let _ = write_slot(self, *slot, *original);
+        }
+        return Err(error);
+      }
+    }
+
+    Ok(hook)
+  }
+}
+
+/// Writes `value` to GOT `slot`, see `Process::write_memory_forced()`.
+fn write_slot(process: &Process, slot: usize, value: usize) -> Result<()> {
+  process.write_memory_forced(slot, &value.to_ne_bytes())
+}
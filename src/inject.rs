@@ -0,0 +1,310 @@
+use anyhow::Result;
+use nix::errno::Errno;
+use nix::sys::ptrace;
+use nix::sys::signal::Signal;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+
+use super::error::system_call_error;
+use super::pagemap::page_size;
+use super::{RegionPermissions, Registers, TracedProcess};
+
+/// Machine code of `syscall` instruction.
+#[cfg(target_arch = "x86_64")]
+const SYSCALL_INSTRUCTION: [u8; 2] = [0x0f, 0x05];
+/// Machine code of `svc #0` instruction.
+#[cfg(target_arch = "aarch64")]
+const SYSCALL_INSTRUCTION: [u8; 4] = [0x01, 0x00, 0x00, 0xd4];
+
+/// Register set holding number of the system call thread is stopped in  
+/// (`NT_ARM_SYSTEM_CALL` from `<elf.h>`), which decides whether kernel  
+/// restarts interrupted system call when thread is resumed.
+#[cfg(target_arch = "aarch64")]
+const NT_ARM_SYSTEM_CALL: libc::c_int = 0x404;
+
+#[cfg(target_arch = "x86_64")]
+impl Registers {
+  /// Prepares registers for executing system call `number` with `args`.  
+  /// **orig_rax** is set to -1, so interrupted system call thread is stopped  
+  /// in is not restarted instead of executing the injected one.
+  fn prepare_syscall(&mut self, number: i64, args: &[u64]) {
+    self.rax = number as u64;
+    self.orig_rax = u64::MAX;
+
+    let mut all_args = [0u64; 6];
+    all_args[..args.len()].copy_from_slice(args);
+    self.rdi = all_args[0];
+    self.rsi = all_args[1];
+    self.rdx = all_args[2];
+    self.r10 = all_args[3];
+    self.r8 = all_args[4];
+    self.r9 = all_args[5];
+  }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl Registers {
+  /// Prepares registers for executing system call `number` with `args`.
+  fn prepare_syscall(&mut self, number: i64, args: &[u64]) {
+    self.regs[8] = number as u64;
+    self.regs[..6].copy_from_slice(&[0; 6]);
+    self.regs[..args.len()].copy_from_slice(args);
+  }
+}
+
+impl TracedProcess {
+  /// Executes system call `number` (e.g. `libc::SYS_getpid`) with up to six `args`  
+  /// in the process and returns its raw result, which is negative error number  
+  /// on failure. System call instruction is written at instruction pointer of  
+  /// one of threads, registers of the thread are set up and the instruction is  
+  /// single-stepped. Afterwards, the overwritten code and registers are restored.
+  ///
+  /// All threads are stopped while system call is executed, so none of them  
+  /// can run the temporarily overwritten code. Threads which were running  
+  /// are resumed afterwards.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate libc;
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///   println!("remote pid: {}", traced.remote_syscall(libc::SYS_getpid, &[])?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `remote pid: 2686`
+  pub fn remote_syscall(&self, number: i64, args: &[u64]) -> Result<i64> {
+    if args.len() > 6 {
+      return Err(anyhow!(
+        "Could not execute system call {} (too many arguments).",
+        number
+      ));
+    }
+
+    self.with_all_stopped(|| self.inject_syscall(number, args))
+  }
+
+  /// Changes protection of memory pages containing `length` bytes at `address`  
+  /// to `permissions`, by executing **mprotect(2)** in the process (see `remote_syscall()`).  
+  /// `address` is aligned down and `length` is aligned up to page boundaries,  
+  /// `shared` field of `permissions` is ignored.
+  ///
+  /// **NOTE**: Memory regions parsed with `parse_maps()` are not updated,  
+  /// call `parse_maps()` again to see new permissions.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RegionPermissions};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   let writable_code = RegionPermissions::RWX;
+  ///   traced.remote_mprotect(0x401000, 2, &writable_code)?;
+  ///   traced.write_memory_bytes(0x401000, &[0x90, 0x90])?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn remote_mprotect(
+    &self,
+    address: usize,
+    length: usize,
+    permissions: &RegionPermissions,
+  ) -> Result<()> {
+    let page_size = page_size();
+    let start = address & !(page_size - 1);
+    let end = (address + length + page_size - 1) & !(page_size - 1);
+
+    let result = self.remote_syscall(
+      libc::SYS_mprotect,
+      &[
+        start as u64,
+        (end - start) as u64,
+        permissions.protection_flags() as u64,
+      ],
+    )?;
+
+    if result < 0 {
+      return Err(anyhow!(
+        "Could not change protection of memory at {:#x} ({}).",
+        address,
+        Errno::from_i32(-result as i32)
+      ));
+    }
+    Ok(())
+  }
+
+  /// Stops all threads, calls `f` and resumes threads which were running before.
+  pub(crate) fn with_all_stopped<T, F: FnOnce() -> Result<T>>(&self, f: F) -> Result<T> {
+    let running: Vec<Pid> =
+      self.traced_threads().into_iter().filter(|tid| !self.is_stopped(*tid)).collect();
+    self.stop_all()?;
+
+    let result = f();
+
+    for tid in running {
+      if self.is_stopped(tid) {
+        self.resume(tid, None)?;
+      }
+    }
+
+    result
+  }
+
+  /// Returns thread code is injected into, which is the main thread  
+  /// if it is traced, or other thread of the process otherwise.
+  pub(crate) fn injection_thread(&self) -> Result<Pid> {
+    let pid = self.get_pid();
+    self
+      .traced_threads()
+      .into_iter()
+      .filter(|tid| self.thread_pid(*tid) == Some(pid))
+      .min_by_key(|tid| *tid != pid)
+      .ok_or_else(|| anyhow!("Could not inject code into process {} (no traced thread).", pid))
+  }
+
+  /// Executes system call in one of stopped threads of the process  
+  /// and restores its state, see `remote_syscall()`.
+  fn inject_syscall(&self, number: i64, args: &[u64]) -> Result<i64> {
+    let tid = self.injection_thread()?;
+
+    let saved_registers = self.get_regs(tid)?;
+    let instruction_pointer = saved_registers.instruction_pointer();
+    #[cfg(target_arch = "aarch64")]
+    let saved_syscall_number = self.set_syscall_number(tid, -1)?;
+
+    let saved_code =
+      ptrace::read(tid, instruction_pointer as ptrace::AddressType).map_err(|error| {
+        system_call_error(
+          tid,
+          error,
+          format!("Could not read code at {:#x}", instruction_pointer),
+        )
+      })?;
+    let mut patched_code = saved_code.to_ne_bytes();
+    patched_code[..SYSCALL_INSTRUCTION.len()].copy_from_slice(&SYSCALL_INSTRUCTION);
+    write_code(
+      tid,
+      instruction_pointer,
+      libc::c_long::from_ne_bytes(patched_code),
+    )?;
+
+    let mut registers = saved_registers;
+    registers.prepare_syscall(number, args);
+
+    let outcome = self.set_regs(tid, &registers).and_then(|()| self.step_over_syscall(tid));
+
+    // State is restored even if the system call failed, unless thread is gone.
+    let restored = write_code(tid, instruction_pointer, saved_code)
+      .and_then(|()| self.set_regs(tid, &saved_registers));
+    #[cfg(target_arch = "aarch64")]
+    let restored =
+      restored.and_then(|()| self.set_syscall_number(tid, saved_syscall_number).map(drop));
+
+    let (registers, pending_signals) = outcome?;
+    restored?;
+
+    // Signals which arrived while system call was executed were suppressed,
+    // send them again so the thread handles them once it is resumed.
+    self.resend_signals(tid, &pending_signals);
+
+    Ok(registers.syscall_result())
+  }
+
+  /// Single-steps system call instruction prepared in stopped thread `tid`  
+  /// and returns registers after it was executed, together with signals  
+  /// which were suppressed in the meantime.
+  fn step_over_syscall(&self, tid: Pid) -> Result<(Registers, Vec<Signal>)> {
+    let mut pending_signals: Vec<Signal> = Vec::new();
+
+    loop {
+      ptrace::step(tid, None).map_err(|error| {
+        system_call_error(tid, error, format!("Could not single-step thread {}", tid))
+      })?;
+
+      let status = waitpid(tid, Some(WaitPidFlag::__WALL))
+        .map_err(|error| anyhow!("Could not wait for thread {} ({}).", tid, error))?;
+      match status {
+        WaitStatus::Stopped(_, Signal::SIGTRAP) => {
+          return Ok((self.get_regs(tid)?, pending_signals))
+        }
+        // Signal-delivery-stop before the instruction was executed.
+        WaitStatus::Stopped(_, signal) => pending_signals.push(signal),
+        WaitStatus::Exited(..) | WaitStatus::Signaled(..) => {
+          self.set_stopped(tid, false);
+          return Err(anyhow!(
+            "Could not execute system call in thread {} (thread exited).",
+            tid
+          ));
+        }
+        // Interrupt stops and other ptrace events, the instruction is stepped again.
+        _ => {}
+      }
+    }
+  }
+
+  /// Sends `signals` suppressed during injection to thread `tid` again.
+  pub(crate) fn resend_signals(&self, tid: Pid, signals: &[Signal]) {
+    for signal in signals {
+      unsafe {
+        libc::syscall(
+          libc::SYS_tgkill,
+          self.get_pid().as_raw(),
+          tid.as_raw(),
+          *signal as libc::c_int,
+        );
+      }
+    }
+  }
+
+  /// Sets number of the system call thread `tid` is stopped in and returns  
+  /// the previous one, so it can be restored afterwards.
+  #[cfg(target_arch = "aarch64")]
+  pub(crate) fn set_syscall_number(&self, tid: Pid, number: libc::c_int) -> Result<libc::c_int> {
+    use super::registers::ptrace_regset;
+
+    let mut saved_number: libc::c_int = 0;
+    ptrace_regset(
+      libc::PTRACE_GETREGSET,
+      tid,
+      NT_ARM_SYSTEM_CALL,
+      &mut saved_number,
+    )
+    .and_then(|()| {
+      ptrace_regset(libc::PTRACE_SETREGSET, tid, NT_ARM_SYSTEM_CALL, &mut {
+        number
+      })
+    })
+    .map_err(|error| {
+      system_call_error(
+        tid,
+        error,
+        format!("Could not set system call number of thread {}", tid),
+      )
+    })?;
+
+    Ok(saved_number)
+  }
+}
+
+/// Writes word of machine code at `address` in thread `tid` with **PTRACE_POKEDATA**,  
+/// which ignores memory protection, unlike `Process::write_memory()`.
+fn write_code(tid: Pid, address: usize, code: libc::c_long) -> Result<()> {
+  ptrace::write(
+    tid,
+    address as ptrace::AddressType,
+    code as *mut libc::c_void,
+  )
+  .map_err(|error| {
+    system_call_error(
+      tid,
+      error,
+      format!("Could not write code at {:#x}", address),
+    )
+  })
+}
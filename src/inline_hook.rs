@@ -0,0 +1,297 @@
+use anyhow::Result;
+use iced_x86::{
+  BlockEncoder, BlockEncoderOptions, Code, Decoder, DecoderOptions, Instruction, InstructionBlock,
+  Mnemonic,
+};
+
+use super::pagemap::page_size;
+use super::{RegionPermissions, RemoteAllocation, TracedProcess};
+
+/// Size of `jmp rel32` instruction.
+const RELATIVE_JUMP_SIZE: usize = 5;
+/// Size of `jmp [rip+0]` instruction followed by 64-bit destination.
+const ABSOLUTE_JUMP_SIZE: usize = 14;
+/// Size of relay jumping to detour, placed at start of trampoline memory.
+const RELAY_SIZE: usize = 16;
+/// Number of bytes of hooked function read to find displaced instructions.
+const PROLOGUE_READ_SIZE: usize = 64;
+
+/// Function hooked with `TracedProcess::inline_hook()`. Hook can be disabled  
+/// and enabled again, and it is removed automatically when `InlineHook` is dropped.
+///
+/// **NOTE**: Trampoline is unmapped when the hook is dropped, so no thread  
+/// may execute it anymore at that point, e.g. in original function called  
+/// from detour.
+pub struct InlineHook<'a> {
+  traced: &'a TracedProcess,
+  target: usize,
+  trampoline: usize,
+  original: Vec<u8>,
+  patch: Vec<u8>,
+  enabled: bool,
+  allocation: Option<RemoteAllocation<'a>>,
+}
+
+impl<'a> InlineHook<'a> {
+  /// Returns address of the hooked function.
+  pub fn target(&self) -> usize {
+    self.target
+  }
+
+  /// Returns address of trampoline, which executes instructions displaced  
+  /// from the hooked function and jumps to the rest of it. Calling trampoline  
+  /// calls the original function, regardless of whether the hook is enabled.
+  pub fn trampoline(&self) -> usize {
+    self.trampoline
+  }
+
+  /// Returns number of bytes overwritten at start of the hooked function.
+  pub fn patch_size(&self) -> usize {
+    self.patch.len()
+  }
+
+  /// Returns **true** if calls of the hooked function jump to detour.
+  pub fn is_enabled(&self) -> bool {
+    self.enabled
+  }
+
+  /// Writes jump to detour at start of the hooked function, see `disable()`.
+  pub fn enable(&mut self) -> Result<()> {
+    if !self.enabled {
+      self.write_prologue(&self.patch)?;
+      self.enabled = true;
+    }
+    Ok(())
+  }
+
+  /// Restores original start of the hooked function, so calls of it do not  
+  /// jump to detour anymore. Trampoline stays mapped until the hook is dropped.
+  pub fn disable(&mut self) -> Result<()> {
+    if self.enabled {
+      self.write_prologue(&self.original)?;
+      self.enabled = false;
+    }
+    Ok(())
+  }
+
+  /// Disables the hook and unmaps trampoline, returning [`Err`] if it failed.  
+  /// Dropping the hook does the same, but ignores errors.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn unhook(mut self) -> Result<()> {
+    self.disable()?;
+    match self.allocation.take() {
+      Some(allocation) => allocation.free(),
+      None => Ok(()),
+    }
+  }
+
+  /// Writes `code` at start of the hooked function while all threads are stopped,  
+  /// unless any of them is stopped in the middle of overwritten instructions.
+  fn write_prologue(&self, code: &[u8]) -> Result<()> {
+    let traced = self.traced;
+    let patched = self.target + 1..self.target + self.patch.len();
+
+    traced.with_all_stopped(|| {
+      for tid in traced.traced_threads() {
+        let instruction_pointer = traced.get_regs(tid)?.instruction_pointer();
+        if patched.contains(&instruction_pointer) {
+          return Err(anyhow!(
+            "Could not patch function at {:#x} (thread {} is executing it at {:#x}).",
+            self.target,
+            tid,
+            instruction_pointer
+          ));
+        }
+      }
+      traced.write_memory_forced(self.target, code)
+    })
+  }
+}
+
+impl<'a> Drop for InlineHook<'a> {
+  fn drop(&mut self) {
+    let _ = self.disable();
+  }
+}
+
+impl TracedProcess {
+  /// Hooks function at `target`, so its calls jump to `detour` instead, e.g. to  
+  /// function of library injected into the process. Start of the function is  
+  /// overwritten with jump to detour, and instructions it displaced are relocated  
+  /// to trampoline (see `InlineHook::trampoline()`), so detour can call the original  
+  /// function. Hook is enabled before it is returned.
+  ///
+  /// Trampoline is allocated near `target` if possible, which allows 5 byte  
+  /// `jmp rel32` to be used, otherwise 14 byte absolute jump is written. Instructions  
+  /// are decoded with [iced-x86](https://docs.rs/iced-x86), so `inline-hook` feature  
+  /// has to be enabled. If the function is too short to be hooked or its displaced  
+  /// instructions can not be relocated, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: Jumps from the rest of the function back into the displaced  
+  /// instructions are not detected, such functions can not be hooked safely.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let target = ctx.resolve_symbol("game", "update_health")?;
+  ///   let detour = ctx.resolve_symbol("libhook.so", "hooked_update_health")?;
+  ///   let original = ctx.resolve_symbol("libhook.so", "original_update_health")?;
+  ///
+  ///   let traced = ctx.attach()?;
+  ///   let hook = traced.inline_hook(target, detour)?;
+  ///   traced.write::<usize>(original, &hook.trampoline())?;
+  ///   println!("trampoline at {:#x}", hook.trampoline());
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `trampoline at 0x55d4c0e3f010`
+  pub fn inline_hook(&self, target: usize, detour: usize) -> Result<InlineHook<'_>> {
+    let allocation = self.allocate_near(target)?;
+    let relay = allocation.address();
+    let trampoline = relay + RELAY_SIZE;
+
+    let patch = if is_near(target + RELATIVE_JUMP_SIZE, relay) {
+      relative_jump(target, relay)
+    } else {
+      absolute_jump(detour)
+    };
+
+    let (code, code_size) = self.read_memory_partial(target, PROLOGUE_READ_SIZE)?;
+    let mut instructions = displaced_instructions(target, &code[..code_size], patch.len())?;
+    let displaced_size: usize = instructions.iter().map(Instruction::len).sum();
+
+    let jump_back = Instruction::with_branch(Code::Jmp_rel32_64, (target + displaced_size) as u64)
+      .map_err(|error| anyhow!("Could not hook function at {:#x} ({}).", target, error))?;
+    instructions.push(jump_back);
+    let relocated = BlockEncoder::encode(
+      64,
+      InstructionBlock::new(&instructions, trampoline as u64),
+      BlockEncoderOptions::NONE,
+    )
+    .map_err(|error| {
+      anyhow!(
+        "Could not relocate instructions of function at {:#x} ({}).",
+        target,
+        error
+      )
+    })?
+    .code_buffer;
+
+    if RELAY_SIZE + relocated.len() > allocation.size() {
+      return Err(anyhow!(
+        "Could not hook function at {:#x} (trampoline is too large).",
+        target
+      ));
+    }
+
+    let mut trampoline_code = absolute_jump(detour);
+    trampoline_code.resize(RELAY_SIZE, 0xcc);
+    trampoline_code.extend_from_slice(&relocated);
+    self.write_memory_forced(relay, &trampoline_code)?;
+
+    let mut patch = patch;
+    patch.resize(displaced_size, 0x90);
+
+    let mut hook = InlineHook {
+      traced: self,
+      target,
+      trampoline,
+      original: code[..displaced_size].to_vec(),
+      patch,
+      enabled: false,
+      allocation: Some(allocation),
+    };
+    hook.enable()?;
+
+    Ok(hook)
+  }
+
+  /// Allocates executable page for trampoline, preferably within `jmp rel32`  
+  /// range of `target` (see `remote_mmap_near()`).
+  fn allocate_near(&self, target: usize) -> Result<RemoteAllocation<'_>> {
+    let permissions = RegionPermissions::RX;
+
+    match self.remote_mmap_near(target, page_size(), &permissions) {
+      Ok(allocation) => Ok(allocation),
+      Err(_) => self.remote_mmap(page_size(), &permissions),
+    }
+  }
+}
+
+/// Decodes instructions at start of function at `address`, until at least  
+/// `patch_size` bytes of them are decoded. These are instructions overwritten  
+/// by the hook, which have to be relocated to trampoline.
+fn displaced_instructions(
+  address: usize,
+  code: &[u8],
+  patch_size: usize,
+) -> Result<Vec<Instruction>> {
+  let mut decoder = Decoder::with_ip(64, code, address as u64, DecoderOptions::NONE);
+  let mut instructions: Vec<Instruction> = Vec::new();
+  let mut size = 0;
+
+  while size < patch_size {
+    if !decoder.can_decode() {
+      return Err(anyhow!(
+        "Could not decode instructions of function at {:#x}.",
+        address
+      ));
+    }
+
+    let instruction = decoder.decode();
+    if instruction.is_invalid() {
+      return Err(anyhow!(
+        "Could not decode instruction at {:#x}.",
+        instruction.ip()
+      ));
+    }
+    size += instruction.len();
+
+    // Bytes after these instructions may not belong to the function.
+    let ends_function = matches!(
+      instruction.mnemonic(),
+      Mnemonic::Ret | Mnemonic::Jmp | Mnemonic::Int3 | Mnemonic::Ud2
+    );
+    instructions.push(instruction);
+    if ends_function && size < patch_size {
+      return Err(anyhow!(
+        "Could not hook function at {:#x} (function is too short).",
+        address
+      ));
+    }
+  }
+
+  Ok(instructions)
+}
+
+/// Returns **true** if `destination` is within `jmp rel32` range from `source`.
+fn is_near(source: usize, destination: usize) -> bool {
+  let distance = destination as i64 - source as i64;
+  distance >= i64::from(i32::MIN) && distance <= i64::from(i32::MAX)
+}
+
+/// Returns `jmp rel32` instruction at `address` jumping to `destination`.
+fn relative_jump(address: usize, destination: usize) -> Vec<u8> {
+  let displacement = destination as i64 - (address + RELATIVE_JUMP_SIZE) as i64;
+  let mut code = vec![0xe9];
+  code.extend_from_slice(&(displacement as i32).to_le_bytes());
+  code
+}
+
+/// Returns `jmp [rip+0]` instruction followed by 64-bit `destination`.
+fn absolute_jump(destination: usize) -> Vec<u8> {
+  let mut code = vec![0xff, 0x25, 0x00, 0x00, 0x00, 0x00];
+  code.extend_from_slice(&(destination as u64).to_le_bytes());
+  debug_assert_eq!(code.len(), ABSOLUTE_JUMP_SIZE);
+  code
+}
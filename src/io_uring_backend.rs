@@ -0,0 +1,293 @@
+use anyhow::Result;
+use io_uring::{opcode, types, IoUring};
+use nix::unistd::Pid;
+use std::io;
+use std::mem;
+use std::os::unix::io::AsRawFd;
+use std::ptr;
+use std::sync::Mutex;
+
+use super::memory_backend::{normalize_fault, MemoryBackend, ProcMemBackend};
+
+/// Number of reads queued in the ring at once.
+const QUEUE_DEPTH: u32 = 64;
+
+/// Size of pieces large reads are split into, so they are executed concurrently.  
+/// Smaller reads are done with single **pread(2)**, which is cheaper for them.
+const URING_CHUNK_SIZE: usize = 0x10000;
+
+/// Piece of a read queued in the ring.
+struct Piece {
+  /// Index of the range the piece belongs to.
+  range: usize,
+  address: usize,
+  /// Destination the piece is copied to once read.
+  pointer: *mut u8,
+  length: usize,
+}
+
+/// Backend queueing many **pread(2)** calls on `/proc/\[pid\]/mem` file  
+/// concurrently with [**io_uring(7)**](http://man7.org/linux/man-pages/man7/io_uring.7.html),  
+/// for bulk reads like dumping or scanning huge processes. Large reads  
+/// and batches (see `Process::read_memory_batch()`) are split into 64 KiB pieces  
+/// which the kernel reads in parallel, small reads and writes are done  
+/// the same way as by [`ProcMemBackend`]. Requires `io-uring-backend` feature.
+///
+/// [`ProcMemBackend`]: struct.ProcMemBackend.html
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{IoUringBackend, Process};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.set_memory_backend(IoUringBackend::new()?);
+///   ctx.parse_maps()?;
+///
+///   for region in ctx.regions()?.readable() {
+///     ctx.dump_region(region, format!("{:x}.bin", region.start))?;
+///   }
+///   Ok(())
+/// }
+/// ```
+pub struct IoUringBackend {
+  file: ProcMemBackend,
+  ring: Mutex<Option<Ring>>,
+}
+
+/// Ring together with buffers its reads are done into. Reads never go directly  
+/// into buffers of the caller, so they can not outlive them if the ring fails.
+struct Ring {
+  ring: IoUring,
+  buffers: Vec<Box<[u8]>>,
+  /// Number of reads queued and not completed yet.
+  in_flight: usize,
+}
+
+impl IoUringBackend {
+  /// IoUringBackend object constructor. If **io_uring(7)** is not supported  
+  /// or disabled (e.g. by `kernel.io_uring_disabled` sysctl), [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn new() -> Result<IoUringBackend> {
+    let ring =
+      Ring::new().map_err(|error| anyhow!("Could not create io_uring instance ({}).", error))?;
+
+    Ok(IoUringBackend {
+      file: ProcMemBackend::new(),
+      ring: Mutex::new(Some(ring)),
+    })
+  }
+
+  /// Reads every `(address, buffer)` pair of `ranges` concurrently and returns  
+  /// number of bytes read of every range, stopping at the first byte which  
+  /// could not be read, or error of range which could not be read at all.
+  fn read_ranges(
+    &self,
+    pid: Pid,
+    ranges: &mut [(usize, &mut [u8])],
+  ) -> io::Result<Vec<io::Result<usize>>> {
+    let mut pieces = Vec::new();
+    for (index, (address, buffer)) in ranges.iter_mut().enumerate() {
+      let mut offset = 0;
+      while offset < buffer.len() {
+        let length = URING_CHUNK_SIZE.min(buffer.len() - offset);
+        pieces.push(Piece {
+          range: index,
+          address: *address + offset,
+          pointer: buffer[offset..].as_mut_ptr(),
+          length,
+        });
+        offset += length;
+      }
+    }
+
+    let results = self.file.with_file(pid, |file| self.submit(file.as_raw_fd(), &pieces))?;
+
+    let mut counts: Vec<io::Result<usize>> = (0..ranges.len()).map(|_| Ok(0)).collect();
+    let mut finished = vec![false; ranges.len()];
+    for (piece, result) in pieces.iter().zip(results) {
+      if finished[piece.range] {
+        continue;
+      }
+      let count = match counts[piece.range] {
+        Ok(count) => count,
+        Err(_) => continue,
+      };
+
+      if result < 0 {
+        if count == 0 {
+          counts[piece.range] = Err(normalize_fault(io::Error::from_raw_os_error(-result)));
+        }
+        finished[piece.range] = true;
+        continue;
+      }
+      counts[piece.range] = Ok(count + result as usize);
+      if (result as usize) < piece.length {
+        finished[piece.range] = true;
+      }
+    }
+
+    for (count, (_, buffer)) in counts.iter_mut().zip(ranges.iter()) {
+      if let Ok(0) = count {
+        if !buffer.is_empty() {
+          *count = Err(io::Error::from_raw_os_error(libc::EFAULT));
+        }
+      }
+    }
+    Ok(counts)
+  }
+
+  /// Queues reads of all `pieces` from file `fd` in the ring and waits until all  
+  /// of them complete. Returns result of every piece: number of bytes read,  
+  /// or negated error number. If the ring fails while reads are in flight  
+  /// and they could not be waited for, the ring is abandoned and a new one  
+  /// is created by the next call.
+  fn submit(&self, fd: i32, pieces: &[Piece]) -> io::Result<Vec<i32>> {
+    let mut ring = self.ring.lock().unwrap_or_else(|error| error.into_inner());
+    if ring.is_none() {
+      *ring = Some(Ring::new()?);
+    }
+
+    let results = ring.as_mut().unwrap().read_pieces(fd, pieces);
+    if results.is_err() && !ring.as_mut().unwrap().drain() {
+      // Kernel may still write to buffers of the ring, so they are leaked instead of freed.
+      mem::forget(ring.take());
+    }
+    results
+  }
+}
+
+impl Ring {
+  fn new() -> io::Result<Ring> {
+    Ok(Ring {
+      ring: IoUring::new(QUEUE_DEPTH)?,
+      buffers: (0..QUEUE_DEPTH)
+        .map(|_| vec![0u8; URING_CHUNK_SIZE].into_boxed_slice())
+        .collect(),
+      in_flight: 0,
+    })
+  }
+
+  /// Reads all `pieces` from file `fd` into buffers of the ring, copying  
+  /// every piece to its destination once its read completes.
+  fn read_pieces(&mut self, fd: i32, pieces: &[Piece]) -> io::Result<Vec<i32>> {
+    let mut results = vec![0i32; pieces.len()];
+    // Index of piece read into every buffer.
+    let mut buffer_pieces: Vec<Option<usize>> = vec![None; self.buffers.len()];
+    let mut free_buffers: Vec<usize> = (0..self.buffers.len()).collect();
+    let mut queued = 0;
+    let mut completed = 0;
+
+    while completed < pieces.len() {
+      {
+        let mut submission = self.ring.submission();
+        while queued < pieces.len() {
+          let buffer = match free_buffers.pop() {
+            Some(buffer) => buffer,
+            None => break,
+          };
+          let piece = &pieces[queued];
+          let entry = opcode::Read::new(
+            types::Fd(fd),
+            self.buffers[buffer].as_mut_ptr(),
+            piece.length as u32,
+          )
+          .offset(piece.address as u64)
+          .build()
+          .user_data(buffer as u64);
+          // Buffers of the ring are freed only when no read is in flight, see `drain()`.
+          // There are as many buffers as entries in submission queue, so it is never full.
+          unsafe {
+            submission.push(&entry).expect("Could not queue read in full submission queue.");
+          }
+          buffer_pieces[buffer] = Some(queued);
+          queued += 1;
+          self.in_flight += 1;
+        }
+      }
+
+      self.wait()?;
+
+      for entry in self.ring.completion() {
+        self.in_flight -= 1;
+        let buffer = entry.user_data() as usize;
+        let index = match buffer_pieces[buffer].take() {
+          Some(index) => index,
+          None => continue,
+        };
+
+        let result = entry.result();
+        if result > 0 {
+          let length = (result as usize).min(pieces[index].length);
+          unsafe {
+            ptr::copy_nonoverlapping(self.buffers[buffer].as_ptr(), pieces[index].pointer, length);
+          }
+        }
+        results[index] = result;
+        free_buffers.push(buffer);
+        completed += 1;
+      }
+    }
+
+    Ok(results)
+  }
+
+  /// Waits until all reads in flight complete, discarding their results.  
+  /// Returns **false** if waiting failed and some of them may be still in flight.
+  fn drain(&mut self) -> bool {
+    while self.in_flight > 0 {
+      if self.wait().is_err() {
+        return false;
+      }
+      self.in_flight -= self.ring.completion().count();
+    }
+    true
+  }
+
+  /// Submits queued reads and waits for at least one completion.
+  fn wait(&mut self) -> io::Result<()> {
+    loop {
+      match self.ring.submit_and_wait(1) {
+        Ok(_) => return Ok(()),
+        Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+        Err(error) => return Err(error),
+      }
+    }
+  }
+}
+
+impl MemoryBackend for IoUringBackend {
+  fn read(&self, pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+    if buffer.len() <= URING_CHUNK_SIZE {
+      return self.file.read(pid, address, buffer);
+    }
+    self.read_ranges(pid, &mut [(address, buffer)])?.remove(0)
+  }
+
+  fn write(&self, pid: Pid, address: usize, buffer: &[u8]) -> io::Result<usize> {
+    self.file.write(pid, address, buffer)
+  }
+
+  fn read_batch(&self, pid: Pid, ranges: &mut [(usize, &mut [u8])]) -> io::Result<usize> {
+    let lengths: Vec<usize> = ranges.iter().map(|(_, buffer)| buffer.len()).collect();
+    let mut bytes_read = 0;
+
+    for (index, (count, length)) in
+      self.read_ranges(pid, ranges)?.into_iter().zip(lengths).enumerate()
+    {
+      match count {
+        Ok(count) => {
+          bytes_read += count;
+          if count != length {
+            break;
+          }
+        }
+        Err(error) if index == 0 => return Err(error),
+        Err(_) => break,
+      }
+    }
+    Ok(bytes_read)
+  }
+}
@@ -1,15 +1,159 @@
 #[macro_use]
 extern crate anyhow;
+extern crate bytemuck;
+extern crate glob;
+extern crate libc;
+extern crate memchr;
 extern crate nix;
-#[macro_use]
-extern crate scan_fmt;
+extern crate regex;
 
 #[cfg(feature = "byteorder-utils")]
 extern crate byteorder;
+#[cfg(feature = "parallel-scan")]
+extern crate rayon;
+#[cfg(feature = "serde-support")]
+extern crate serde;
+#[cfg(feature = "serde-support")]
+extern crate serde_json;
+#[cfg(feature = "demangle")]
+extern crate cpp_demangle;
+#[cfg(feature = "demangle")]
+extern crate rustc_demangle;
+#[cfg(all(feature = "inline-hook", target_arch = "x86_64"))]
+extern crate iced_x86;
+#[cfg(feature = "derive")]
+extern crate trickster_derive;
+#[cfg(feature = "io-uring-backend")]
+extern crate io_uring;
 
-pub use self::process::Process;
-pub use self::memory_region::MemoryRegion;
+pub use bytemuck::Pod;
+pub use nix::sys::signal::Signal;
+pub use nix::sys::wait::WaitStatus;
+pub use nix::unistd::Pid;
+pub use self::address_table::{AddressRecipe, AddressTable, RipRelative};
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub use self::allocation::{RemoteAllocation, RemoteBuffer};
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub use self::call::RemoteArg;
+pub use self::cached_reader::CachedReader;
+pub use self::chunked_read::{ChunkedRead, HolePolicy};
+#[cfg(feature = "demangle")]
+pub use self::demangle::demangle;
+pub use self::dump::RegionDump;
+pub use self::dump_process::DumpProcess;
+pub use self::error::ProcessError;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub use self::events::{TraceEvent, TraceEvents};
+pub use self::fd::{FdTarget, OpenFd};
+pub use self::freeze::FreezeGuard;
+#[cfg(all(feature = "inline-hook", target_arch = "x86_64"))]
+pub use self::inline_hook::InlineHook;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub use self::got_hook::GotHook;
+pub use self::process::{CmdlineMatch, Process};
+pub use self::maps::MapsIter;
+#[cfg(feature = "io-uring-backend")]
+pub use self::io_uring_backend::IoUringBackend;
+pub use self::memory_backend::{
+  FallbackBackend, MemoryBackend, ProcMemBackend, ProcessVmBackend, PtraceBackend,
+};
+pub use self::memory_region::{MemoryRegion, RegionKind};
+pub use self::memory_source::MemorySource;
+#[cfg(feature = "testing")]
+pub use self::mock::MockMemory;
+pub use self::module::Module;
+#[cfg(feature = "serde-support")]
+pub use self::module_cache::ModuleCache;
 pub use self::memory_region::RegionPermissions;
+pub use self::patch_set::PatchSet;
+pub use self::permission::{AccessReport, PermissionDenialReason};
+pub use self::remote_slice::{RemoteSlice, RemoteSliceIter};
+#[cfg(feature = "derive")]
+pub use self::remote_struct::RemoteStruct;
+#[cfg(feature = "derive")]
+pub use trickster_derive::RemoteStruct;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use self::remote_struct::private as __private;
+pub use self::region_query::RegionQuery;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+pub use self::registers::Registers;
+pub use self::pointer_scan::{PointerMap, PointerPath, PointerScanOptions};
+pub use self::scan::Signature;
+pub use self::security::{SecurityFinding, SecurityFindingKind};
+pub use self::scan_session::{ScanRefinement, ScanSession, ScanValue, ScanValueType};
+pub use self::signature_set::{ResolvedSignatures, SignatureSet};
+pub use self::smaps::{MemoryStats, ModuleFootprint, SmapsRegion};
+pub use self::stat::{ProcessStat, ProcessState};
+pub use self::strings::{FoundString, StringEncoding, StringsIter};
+pub use self::suspend::SuspendGuard;
+pub use self::symbols::SymbolInfo;
+pub use self::syscall::{DecodedSyscall, SocketAddress, SyscallArg};
+pub use self::thread::Thread;
+pub use self::trace::TracedProcess;
+pub use self::snapshot::{ChangedRange, RegionSnapshot};
 
+mod address_table;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod allocation;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod call;
+mod cached_reader;
+mod chunked_read;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod core_dump;
+#[cfg(feature = "demangle")]
+mod demangle;
+mod dump;
+mod dump_process;
+mod elf;
+mod endian;
+mod error;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod events;
+mod fd;
+mod freeze;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod got_hook;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod inject;
+#[cfg(feature = "io-uring-backend")]
+mod io_uring_backend;
+#[cfg(all(feature = "inline-hook", target_arch = "x86_64"))]
+mod inline_hook;
 mod process;
+mod maps;
+mod memory_backend;
 mod memory_region;
+mod memory_source;
+#[cfg(feature = "testing")]
+mod mock;
+mod module;
+#[cfg(feature = "serde-support")]
+mod module_cache;
+mod pagemap;
+mod patch_set;
+mod permission;
+mod pointer_scan;
+mod proc_info;
+mod region_query;
+mod remote_slice;
+#[cfg(feature = "derive")]
+mod remote_struct;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod registers;
+mod scan;
+mod scan_session;
+mod security;
+mod signature_set;
+mod smaps;
+mod snapshot;
+mod stat;
+mod strings;
+mod suspend;
+mod symbols;
+mod syscall;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod thread;
+mod trace;
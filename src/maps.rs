@@ -0,0 +1,360 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path;
+
+use super::memory_region::format_size;
+use super::{MemoryRegion, Module, Process, RegionPermissions};
+
+/// Suffix appended by kernel to paths of mapped files which were deleted.
+const DELETED_SUFFIX: &[u8] = b" (deleted)";
+
+/// Lazy iterator over rows of `/proc/\[pid\]/maps` file. Every row is parsed  
+/// only when it is requested, so no memory is spent on regions which are  
+/// filtered out, and iteration can stop early. Created by `Process::iter_maps()`.
+pub struct MapsIter {
+  reader: Box<dyn BufRead + Send>,
+  buffer: Vec<u8>,
+  main_executable: MainExecutable,
+}
+
+impl Iterator for MapsIter {
+  type Item = Result<MemoryRegion>;
+
+  fn next(&mut self) -> Option<Result<MemoryRegion>> {
+    self.buffer.clear();
+    match self.reader.read_until(b'\n', &mut self.buffer) {
+      Ok(0) => None,
+      Ok(_) => Some(parse_maps_line(&self.buffer).map(|mut region| {
+        self.main_executable.mark(&mut region);
+        region
+      })),
+      Err(error) => Some(Err(anyhow!("Could not read maps file ({}).", error))),
+    }
+  }
+}
+
+impl Process {
+  /// Returns lazy iterator over memory regions described in  
+  /// `/proc/\[pid\]/maps` file. Unlike `parse_maps();`, regions are  
+  /// not stored in `self.memory_regions`, but parsed one by one  
+  /// while iterating, which is much cheaper for processes with  
+  /// huge number of mappings when only some of them are needed.  
+  /// Regions of process backed by captured memory (see `DumpProcess`)  
+  /// are read from the dump instead, and regions of process backed by memory  
+  /// backend providing them (see `MemoryBackend::maps()`) from the backend.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RegionKind};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let stack = ctx
+  ///     .iter_maps()?
+  ///     .filter_map(Result::ok)
+  ///     .find(|region| region.kind() == RegionKind::Stack);
+  ///   println!("{:?}", stack);
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn iter_maps(&self) -> Result<MapsIter> {
+    if let Some(maps) = self.memory_backend().maps(self.get_pid()) {
+      return Ok(MapsIter {
+        reader: Box::new(io::Cursor::new(maps.into_bytes())),
+        buffer: Vec::new(),
+        main_executable: MainExecutable::default(),
+      });
+    }
+
+    let maps_path = path::Path::new("/proc/").join(self.get_pid().to_string()).join("maps");
+
+    let file = fs::File::open(maps_path)
+      .map_err(|error| anyhow!("Could not open maps file ({}).", error))?;
+
+    Ok(MapsIter {
+      reader: Box::new(io::BufReader::new(file)),
+      buffer: Vec::new(),
+      main_executable: MainExecutable::default(),
+    })
+  }
+
+  /// Formats memory regions of the process as table with aligned columns  
+  /// of address ranges, permissions, sizes in human readable units and paths,  
+  /// followed by total size. If `group_by_module` is **true**, regions mapped  
+  /// from the same file are listed together under their module (see `Process::modules()`),  
+  /// at position of the first one. Every region is formatted by its `Display` implementation.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `format_maps();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   print!("{}", ctx.format_maps(true)?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `       START-END          PERM       SIZE  PATH`  
+  /// `55d0c0ffe000-55d0c1003000        12.0 KiB  /usr/bin/game (2 regions)`  
+  /// `  55d0c0ffe000-55d0c1000000 r--p    8.0 KiB  /usr/bin/game`  
+  /// `  55d0c1000000-55d0c1003000 r-xp    4.0 KiB  /usr/bin/game`  
+  /// `55d0c2a1e000-55d0c2a3f000 rw-p  132.0 KiB  [heap]`  
+  /// `...`  
+  /// `total 24.3 MiB in 27 regions`
+  pub fn format_maps(&self, group_by_module: bool) -> Result<String> {
+    let regions = self.get_memory_regions()?;
+    let modules = if group_by_module {
+      self.modules()?
+    } else {
+      Vec::new()
+    };
+    let modules_by_base: HashMap<usize, &Module> =
+      modules.iter().map(|module| (module.base(), module)).collect();
+
+    let mut table = String::new();
+    let mut total_size = 0;
+    // Writing to String never fails.
+    let _ = writeln!(
+      table,
+      "{:>12}-{:<12} PERM {:>10}  PATH",
+      "START", "END", "SIZE"
+    );
+
+    for region in regions {
+      total_size += region.size();
+      if !group_by_module || !region.path.as_ref().is_some_and(|path| path.starts_with('/')) {
+        let _ = writeln!(table, "{}", region);
+        continue;
+      }
+
+      // Regions of a module are listed when its first region is reached.
+      if let Some(module) = modules_by_base.get(&region.start) {
+        let _ = writeln!(table, "{}", module);
+        for module_region in module.regions() {
+          let _ = writeln!(table, "  {}", module_region);
+        }
+      }
+    }
+
+    let _ = writeln!(
+      table,
+      "total {} in {} regions",
+      format_size(total_size),
+      regions.len()
+    );
+    Ok(table)
+  }
+}
+
+/// Parses single row of `/proc/\[pid\]/maps` file, which looks like:  
+/// `08048000-08056000 r-xp 00000000 03:0c 64593   /usr/sbin/gpm`  
+/// Fields are parsed straight from the byte slice, the only allocation  
+/// made is the one for `path`.
+pub(crate) fn parse_maps_line(line: &[u8]) -> Result<MemoryRegion> {
+  let invalid_line = || {
+    anyhow!(
+      "Could not parse maps line ({}).",
+      String::from_utf8_lossy(line).trim_end()
+    )
+  };
+
+  let mut rest = line.strip_suffix(b"\n").unwrap_or(line);
+
+  let (start, end) = next_field(&mut rest)
+    .and_then(|range| split_once(range, b'-'))
+    .ok_or_else(invalid_line)?;
+  let permissions_string = next_field(&mut rest).ok_or_else(invalid_line)?;
+  let offset = next_field(&mut rest).ok_or_else(invalid_line)?;
+  let (dev_major, dev_minor) = next_field(&mut rest)
+    .and_then(|device| split_once(device, b':'))
+    .ok_or_else(invalid_line)?;
+  let inode = next_field(&mut rest).ok_or_else(invalid_line)?;
+
+  let mut permissions = RegionPermissions::NONE;
+
+  for character in permissions_string {
+    match character {
+      b'r' => permissions.readable = true,
+      b'w' => permissions.writeable = true,
+      b'x' => permissions.executable = true,
+      b's' => permissions.shared = true,
+      _ => continue,
+    }
+  }
+
+  // Path is everything after the inode column, so it can contain spaces.
+  // Kernel appends " (deleted)" to paths of files unlinked after mapping.
+  let path = skip_spaces(rest);
+  let (path, deleted) = match path.strip_suffix(DELETED_SUFFIX) {
+    Some(path) => (path, true),
+    None => (path, false),
+  };
+
+  Ok(MemoryRegion {
+    start: parse_number(start, 16).ok_or_else(invalid_line)?,
+    end: parse_number(end, 16).ok_or_else(invalid_line)?,
+    permissions,
+    offset: parse_number(offset, 16).ok_or_else(invalid_line)?,
+    dev_major: parse_device_number(dev_major).ok_or_else(invalid_line)?,
+    dev_minor: parse_device_number(dev_minor).ok_or_else(invalid_line)?,
+    inode: parse_number(inode, 10).ok_or_else(invalid_line)?,
+    path: if path.is_empty() {
+      None
+    } else {
+      Some(String::from_utf8_lossy(path).into_owned())
+    },
+    deleted,
+    main_executable: false,
+  })
+}
+
+/// Marks regions mapped from the main executable while regions are parsed  
+/// in address order. The executable is assumed to be the file of the first  
+/// file-backed region, since the kernel maps it below libraries and other files.
+#[derive(Default)]
+pub(crate) struct MainExecutable {
+  path: Option<String>,
+}
+
+impl MainExecutable {
+  /// Sets `main_executable` field of `region` which is the next parsed region.
+  pub(crate) fn mark(&mut self, region: &mut MemoryRegion) {
+    let path = match &region.path {
+      Some(path) if path.starts_with('/') => path,
+      _ => return,
+    };
+    let main_path = self.path.get_or_insert_with(|| path.clone());
+    region.main_executable = main_path == path;
+  }
+}
+
+/// Returns `bytes` without leading spaces.
+fn skip_spaces(bytes: &[u8]) -> &[u8] {
+  let first_non_space = bytes.iter().position(|byte| *byte != b' ').unwrap_or(bytes.len());
+  &bytes[first_non_space..]
+}
+
+/// Returns the next space delimited field of `rest` and advances `rest` past it.  
+/// If there are no more fields, [`None`] is returned.
+///
+/// [`None`]: https://doc.rust-lang.org/std/option/
+fn next_field<'a>(rest: &mut &'a [u8]) -> Option<&'a [u8]> {
+  let bytes = skip_spaces(rest);
+  let field_length = bytes.iter().position(|byte| *byte == b' ').unwrap_or(bytes.len());
+  if field_length == 0 {
+    return None;
+  }
+
+  *rest = &bytes[field_length..];
+  Some(&bytes[..field_length])
+}
+
+/// Splits `bytes` at the first occurrence of `delimiter`.
+fn split_once(bytes: &[u8], delimiter: u8) -> Option<(&[u8], &[u8])> {
+  let index = bytes.iter().position(|byte| *byte == delimiter)?;
+  Some((&bytes[..index], &bytes[index + 1..]))
+}
+
+/// Parses unsigned number written with digits of given `radix`.  
+/// Returns [`None`] if `bytes` is empty, contains invalid digit or overflows.
+///
+/// [`None`]: https://doc.rust-lang.org/std/option/
+fn parse_number(bytes: &[u8], radix: u32) -> Option<usize> {
+  if bytes.is_empty() {
+    return None;
+  }
+
+  bytes.iter().try_fold(0usize, |number, byte| {
+    let digit = (*byte as char).to_digit(radix)?;
+    number.checked_mul(radix as usize)?.checked_add(digit as usize)
+  })
+}
+
+/// Parses hexadecimal device number. Major and minor device numbers  
+/// are 12 and 20 bits wide, so they do not always fit in a byte.
+fn parse_device_number(bytes: &[u8]) -> Option<u32> {
+  parse_number(bytes, 16).and_then(|number| u32::try_from(number).ok())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::RegionPermissions;
+  use super::parse_maps_line;
+
+  #[test]
+  fn parses_file_backed_line() {
+    let region = parse_maps_line(
+      b"55d0c1000000-55d0c1021000 r-xp 00002000 fd:01 1835029                    /usr/bin/game\n",
+    )
+    .unwrap();
+
+    assert_eq!(region.start, 0x55d0c1000000);
+    assert_eq!(region.end, 0x55d0c1021000);
+    assert_eq!(region.permissions, RegionPermissions::RX);
+    assert_eq!(region.offset, 0x2000);
+    assert_eq!((region.dev_major, region.dev_minor), (0xfd, 0x01));
+    assert_eq!(region.inode, 1835029);
+    assert_eq!(region.path.as_deref(), Some("/usr/bin/game"));
+    assert!(!region.deleted);
+  }
+
+  #[test]
+  fn parses_path_with_spaces() {
+    let region =
+      parse_maps_line(b"7f0000000000-7f0000001000 r--p 00000000 08:02 42   /opt/My Game/lib  x.so")
+        .unwrap();
+
+    assert_eq!(region.path.as_deref(), Some("/opt/My Game/lib  x.so"));
+  }
+
+  #[test]
+  fn strips_deleted_suffix() {
+    let region =
+      parse_maps_line(b"7f0000000000-7f0000001000 rw-s 00000000 00:05 1234   /memfd:jit (deleted)")
+        .unwrap();
+
+    assert_eq!(region.path.as_deref(), Some("/memfd:jit"));
+    assert!(region.deleted);
+    assert!(region.permissions.shared);
+  }
+
+  #[test]
+  fn parses_special_regions() {
+    let region =
+      parse_maps_line(b"7ffd1c000000-7ffd1c021000 rw-p 00000000 00:00 0   [stack:1234]").unwrap();
+    assert_eq!(region.path.as_deref(), Some("[stack:1234]"));
+
+    let region = parse_maps_line(b"7f0000000000-7f0000004000 ---p 00000000 00:00 0\n").unwrap();
+    assert_eq!(region.path, None);
+    assert_eq!(region.permissions, RegionPermissions::NONE);
+    assert_eq!(region.inode, 0);
+  }
+
+  #[test]
+  fn parses_hex_device_numbers() {
+    let region = parse_maps_line(b"00400000-00401000 r--p 00000000 103:0a 77 /bin/true").unwrap();
+
+    assert_eq!((region.dev_major, region.dev_minor), (0x103, 0x0a));
+  }
+
+  #[test]
+  fn rejects_malformed_lines() {
+    assert!(parse_maps_line(b"").is_err());
+    assert!(parse_maps_line(b"00400000 r--p 00000000 08:01 77 /bin/true").is_err());
+    assert!(parse_maps_line(b"00400000-00401000 r--p 00000000 0801 77 /bin/true").is_err());
+    assert!(parse_maps_line(b"0040000g-00401000 r--p 00000000 08:01 77 /bin/true").is_err());
+  }
+}
@@ -0,0 +1,461 @@
+use nix::errno::Errno;
+use nix::sys::ptrace;
+use nix::sys::uio::{process_vm_readv, process_vm_writev, IoVec, RemoteIoVec};
+use nix::unistd::Pid;
+use std::fs;
+use std::io;
+use std::mem;
+use std::os::unix::fs::FileExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Maximum number of iovecs accepted by single `process_vm_readv(2)`  
+/// and `process_vm_writev(2)` call (see **IOV_MAX** in `<limits.h>`).
+const IOV_MAX: usize = 1024;
+
+/// Size of word transferred by single **PTRACE_PEEKDATA** or **PTRACE_POKEDATA** request.
+const WORD_SIZE: usize = mem::size_of::<usize>();
+
+/// Method of accessing memory of remote process, selected per process  
+/// with `Process::set_memory_backend()`. Every read and write of `Process`  
+/// (except patching read-only memory) goes through it.
+///
+/// Backends report memory which can not be accessed with **EFAULT** error,  
+/// regardless of what underlying interface returns, so that callers can tell  
+/// holes in address space apart from failures of the backend itself.
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{FallbackBackend, Process};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.set_memory_backend(FallbackBackend::default());
+///
+///   println!("value: {}", ctx.read::<u32>(0x601040)?);
+///   Ok(())
+/// }
+/// ```
+pub trait MemoryBackend: Send + Sync {
+  /// Fills `buffer` with memory at `address` in process `pid` and returns  
+  /// number of bytes read. Reading stops at the first byte which can not  
+  /// be read, if it is the first byte of `buffer`, **EFAULT** error is returned.
+  fn read(&self, pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize>;
+
+  /// Writes `buffer` at `address` in process `pid` and returns number of bytes  
+  /// written. Writing stops at the first byte which can not be written,  
+  /// if it is the first byte of `buffer`, **EFAULT** error is returned.
+  fn write(&self, pid: Pid, address: usize, buffer: &[u8]) -> io::Result<usize>;
+
+  /// Returns content of `/proc/\[pid\]/maps` file describing memory of process `pid`,  
+  /// if the backend provides memory layout itself instead of the kernel  
+  /// (e.g. `MockMemory`). Default implementation returns [`None`].
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  fn maps(&self, _pid: Pid) -> Option<String> {
+    None
+  }
+
+  /// Fills buffer of every `(address, buffer)` pair of `ranges` with memory  
+  /// in process `pid`, in order, and returns total number of bytes read.  
+  /// Reading stops at the first range which could not be read completely.
+  fn read_batch(&self, pid: Pid, ranges: &mut [(usize, &mut [u8])]) -> io::Result<usize> {
+    let mut bytes_read = 0;
+    for (address, buffer) in ranges.iter_mut() {
+      let range_read = self.read(pid, *address, buffer)?;
+      bytes_read += range_read;
+      if range_read != buffer.len() {
+        break;
+      }
+    }
+    Ok(bytes_read)
+  }
+
+  /// Writes buffer of every `(address, buffer)` pair of `writes` in process `pid`,  
+  /// in order, and returns total number of bytes written. Writing stops  
+  /// at the first buffer which could not be written completely.
+  fn write_batch(&self, pid: Pid, writes: &[(usize, &[u8])]) -> io::Result<usize> {
+    let mut bytes_written = 0;
+    for (address, buffer) in writes {
+      let buffer_written = self.write(pid, *address, buffer)?;
+      bytes_written += buffer_written;
+      if buffer_written != buffer.len() {
+        break;
+      }
+    }
+    Ok(bytes_written)
+  }
+}
+
+/// Converts error returned by **nix** into [`io::Error`].
+///
+/// [`io::Error`]: https://doc.rust-lang.org/std/io/struct.Error.html
+fn nix_to_io_error(error: nix::Error) -> io::Error {
+  match error {
+    nix::Error::Sys(errno) => io::Error::from_raw_os_error(errno as i32),
+    _ => io::Error::other(error),
+  }
+}
+
+/// Replaces **EIO** error, which `/proc/\[pid\]/mem` and **ptrace(2)** return  
+/// for unmapped memory, with **EFAULT** (see [`MemoryBackend`]).
+///
+/// [`MemoryBackend`]: trait.MemoryBackend.html
+pub(crate) fn normalize_fault(error: io::Error) -> io::Error {
+  match error.raw_os_error() {
+    Some(libc::EIO) => io::Error::from_raw_os_error(libc::EFAULT),
+    _ => error,
+  }
+}
+
+/// Backend using [**process_vm_readv(2)**](http://man7.org/linux/man-pages/man2/process_vm_readv.2.html)  
+/// and **process_vm_writev(2)** system calls. It is the default one, as it does not  
+/// require attaching to the process and batches are packed into single system call.  
+/// It respects memory protection, so read-only memory can not be written.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ProcessVmBackend;
+
+impl MemoryBackend for ProcessVmBackend {
+  fn read(&self, pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+    let remote = RemoteIoVec {
+      base: address,
+      len: buffer.len(),
+    };
+    process_vm_readv(pid, &[IoVec::from_mut_slice(buffer)], &[remote]).map_err(nix_to_io_error)
+  }
+
+  fn write(&self, pid: Pid, address: usize, buffer: &[u8]) -> io::Result<usize> {
+    let remote = RemoteIoVec {
+      base: address,
+      len: buffer.len(),
+    };
+    process_vm_writev(pid, &[IoVec::from_slice(buffer)], &[remote]).map_err(nix_to_io_error)
+  }
+
+  fn read_batch(&self, pid: Pid, ranges: &mut [(usize, &mut [u8])]) -> io::Result<usize> {
+    let mut bytes_read = 0;
+
+    for ranges_chunk in ranges.chunks_mut(IOV_MAX) {
+      let bytes_requested: usize = ranges_chunk.iter().map(|(_, buffer)| buffer.len()).sum();
+
+      let remote: Vec<RemoteIoVec> = ranges_chunk
+        .iter()
+        .map(|(address, buffer)| RemoteIoVec {
+          base: *address,
+          len: buffer.len(),
+        })
+        .collect();
+
+      let local: Vec<IoVec<&mut [u8]>> = ranges_chunk
+        .iter_mut()
+        .map(|(_, buffer)| IoVec::from_mut_slice(&mut buffer[..]))
+        .collect();
+
+      let chunk_read = process_vm_readv(pid, &local, &remote).map_err(nix_to_io_error)?;
+      bytes_read += chunk_read;
+      if chunk_read != bytes_requested {
+        break;
+      }
+    }
+
+    Ok(bytes_read)
+  }
+
+  fn write_batch(&self, pid: Pid, writes: &[(usize, &[u8])]) -> io::Result<usize> {
+    let mut bytes_written = 0;
+
+    for writes_chunk in writes.chunks(IOV_MAX) {
+      let bytes_requested: usize = writes_chunk.iter().map(|(_, buffer)| buffer.len()).sum();
+
+      let remote: Vec<RemoteIoVec> = writes_chunk
+        .iter()
+        .map(|(address, buffer)| RemoteIoVec {
+          base: *address,
+          len: buffer.len(),
+        })
+        .collect();
+
+      let local: Vec<IoVec<&[u8]>> =
+        writes_chunk.iter().map(|(_, buffer)| IoVec::from_slice(buffer)).collect();
+
+      let chunk_written = process_vm_writev(pid, &local, &remote).map_err(nix_to_io_error)?;
+      bytes_written += chunk_written;
+      if chunk_written != bytes_requested {
+        break;
+      }
+    }
+
+    Ok(bytes_written)
+  }
+}
+
+/// Backend using **pread(2)** and **pwrite(2)** on `/proc/\[pid\]/mem` file.  
+/// It works on kernels where **process_vm_readv(2)** is disabled or restricted,  
+/// e.g. by Yama when the caller is already attached to the process as a tracer.  
+/// Like **ptrace(2)**, it ignores memory protection, so read-only memory  
+/// (e.g. code) can be written too.
+///
+/// The file is opened on first access and kept open, so it stays bound  
+/// to the original process even if its pid is reused later.
+#[derive(Default, Debug)]
+pub struct ProcMemBackend {
+  file: Mutex<Option<(Pid, fs::File)>>,
+}
+
+impl ProcMemBackend {
+  /// ProcMemBackend object constructor.
+  pub fn new() -> ProcMemBackend {
+    ProcMemBackend::default()
+  }
+
+  /// Calls `operation` with `/proc/\[pid\]/mem` file, opening it first  
+  /// if it is not open yet.
+  pub(crate) fn with_file<T, F>(&self, pid: Pid, operation: F) -> io::Result<T>
+  where
+    F: FnOnce(&fs::File) -> io::Result<T>,
+  {
+    let mut file = self.file.lock().unwrap_or_else(|error| error.into_inner());
+    if file.as_ref().is_none_or(|(file_pid, _)| *file_pid != pid) {
+      let path = format!("/proc/{}/mem", pid);
+      let opened = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(&path)
+        .or_else(|_| fs::File::open(&path))?;
+      *file = Some((pid, opened));
+    }
+    match file.as_ref() {
+      Some((_, file)) => operation(file),
+      None => unreachable!(),
+    }
+  }
+}
+
+impl MemoryBackend for ProcMemBackend {
+  fn read(&self, pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+    self.with_file(pid, |file| {
+      let mut bytes_read = 0;
+      while bytes_read < buffer.len() {
+        match file.read_at(&mut buffer[bytes_read..], (address + bytes_read) as u64) {
+          Ok(0) => break,
+          Ok(chunk_read) => bytes_read += chunk_read,
+          Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {}
+          Err(_) if bytes_read > 0 => break,
+          Err(error) => return Err(normalize_fault(error)),
+        }
+      }
+      if bytes_read == 0 && !buffer.is_empty() {
+        return Err(io::Error::from_raw_os_error(libc::EFAULT));
+      }
+      Ok(bytes_read)
+    })
+  }
+
+  fn write(&self, pid: Pid, address: usize, buffer: &[u8]) -> io::Result<usize> {
+    self.with_file(pid, |file| {
+      let mut bytes_written = 0;
+      while bytes_written < buffer.len() {
+        match file.write_at(&buffer[bytes_written..], (address + bytes_written) as u64) {
+          Ok(0) => break,
+          Ok(chunk_written) => bytes_written += chunk_written,
+          Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {}
+          Err(_) if bytes_written > 0 => break,
+          Err(error) => return Err(normalize_fault(error)),
+        }
+      }
+      if bytes_written == 0 && !buffer.is_empty() {
+        return Err(io::Error::from_raw_os_error(libc::EFAULT));
+      }
+      Ok(bytes_written)
+    })
+  }
+}
+
+/// Backend using **PTRACE_PEEKDATA** and **PTRACE_POKEDATA** requests, transferring  
+/// one word at a time. It is the slowest one, but works wherever the caller  
+/// is able to trace the process. It ignores memory protection.
+///
+/// **NOTE**: Requests are issued for thread with id equal to `pid` (the main thread),  
+/// which has to be traced by the calling thread and stopped, e.g. right after  
+/// `Process::attach()`. Otherwise every access fails with **ESRCH** error.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct PtraceBackend;
+
+impl PtraceBackend {
+  /// Returns word at aligned `address` in process `pid`.
+  fn peek(pid: Pid, address: usize) -> io::Result<usize> {
+    ptrace::read(pid, address as ptrace::AddressType)
+      .map(|word| word as usize)
+      .map_err(|error| normalize_fault(nix_to_io_error(error)))
+  }
+
+  /// Writes `word` at aligned `address` in process `pid`.
+  fn poke(pid: Pid, address: usize, word: usize) -> io::Result<()> {
+    ptrace::write(
+      pid,
+      address as ptrace::AddressType,
+      word as *mut libc::c_void,
+    )
+    .map_err(|error| normalize_fault(nix_to_io_error(error)))
+  }
+}
+
+impl MemoryBackend for PtraceBackend {
+  fn read(&self, pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut bytes_read = 0;
+
+    while bytes_read < buffer.len() {
+      let current = address + bytes_read;
+      let word_address = current - current % WORD_SIZE;
+      let word = match PtraceBackend::peek(pid, word_address) {
+        Ok(word) => word.to_ne_bytes(),
+        Err(_) if bytes_read > 0 => break,
+        Err(error) => return Err(error),
+      };
+
+      let word_offset = current - word_address;
+      let length = (WORD_SIZE - word_offset).min(buffer.len() - bytes_read);
+      buffer[bytes_read..bytes_read + length]
+        .copy_from_slice(&word[word_offset..word_offset + length]);
+      bytes_read += length;
+    }
+
+    Ok(bytes_read)
+  }
+
+  fn write(&self, pid: Pid, address: usize, buffer: &[u8]) -> io::Result<usize> {
+    let mut bytes_written = 0;
+
+    while bytes_written < buffer.len() {
+      let current = address + bytes_written;
+      let word_address = current - current % WORD_SIZE;
+      let word_offset = current - word_address;
+      let length = (WORD_SIZE - word_offset).min(buffer.len() - bytes_written);
+
+      // Words only partially covered by `buffer` are read first,
+      // so bytes around it are written back unchanged.
+      let result = if length == WORD_SIZE {
+        Ok([0u8; WORD_SIZE])
+      } else {
+        PtraceBackend::peek(pid, word_address).map(usize::to_ne_bytes)
+      }
+      .and_then(|mut word| {
+        word[word_offset..word_offset + length]
+          .copy_from_slice(&buffer[bytes_written..bytes_written + length]);
+        PtraceBackend::poke(pid, word_address, usize::from_ne_bytes(word))
+      });
+
+      match result {
+        Ok(()) => bytes_written += length,
+        Err(_) if bytes_written > 0 => break,
+        Err(error) => return Err(error),
+      }
+    }
+
+    Ok(bytes_written)
+  }
+}
+
+/// Backend trying every backend of the list in order, until one of them  
+/// succeeds. Backend which succeeded is remembered and tried first on next  
+/// accesses, so the cost of failing ones is paid only once. If all of them  
+/// fail, error of the first one is returned. **EFAULT** errors are returned  
+/// right away, since unreadable memory is unreadable for all backends.
+///
+/// Default list is [`ProcessVmBackend`], [`ProcMemBackend`] and [`PtraceBackend`].
+///
+/// [`ProcessVmBackend`]: struct.ProcessVmBackend.html
+/// [`ProcMemBackend`]: struct.ProcMemBackend.html
+/// [`PtraceBackend`]: struct.PtraceBackend.html
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{FallbackBackend, ProcMemBackend, Process, PtraceBackend};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.set_memory_backend(FallbackBackend::new(vec![
+///     Box::new(ProcMemBackend::new()),
+///     Box::new(PtraceBackend),
+///   ]));
+///
+///   println!("value: {}", ctx.read::<u32>(0x601040)?);
+///   Ok(())
+/// }
+/// ```
+pub struct FallbackBackend {
+  backends: Vec<Box<dyn MemoryBackend>>,
+  selected: AtomicUsize,
+}
+
+impl FallbackBackend {
+  /// FallbackBackend object constructor.
+  pub fn new(backends: Vec<Box<dyn MemoryBackend>>) -> FallbackBackend {
+    FallbackBackend {
+      backends,
+      selected: AtomicUsize::new(0),
+    }
+  }
+
+  /// Calls `operation` with selected backend and then with the rest  
+  /// of them in order, until one of them succeeds.
+  fn try_each<T, F>(&self, mut operation: F) -> io::Result<T>
+  where
+    F: FnMut(&dyn MemoryBackend) -> io::Result<T>,
+  {
+    let selected = self.selected.load(Ordering::Relaxed);
+    let order = Some(selected)
+      .into_iter()
+      .chain((0..self.backends.len()).filter(|index| *index != selected));
+
+    let mut first_error = None;
+    for index in order {
+      let backend = match self.backends.get(index) {
+        Some(backend) => backend,
+        None => continue,
+      };
+      match operation(backend.as_ref()) {
+        Ok(value) => {
+          self.selected.store(index, Ordering::Relaxed);
+          return Ok(value);
+        }
+        Err(error) if error.raw_os_error() == Some(libc::EFAULT) => return Err(error),
+        Err(error) => {
+          first_error.get_or_insert(error);
+        }
+      }
+    }
+
+    Err(first_error.unwrap_or_else(|| io::Error::from_raw_os_error(Errno::ENOSYS as i32)))
+  }
+}
+
+impl Default for FallbackBackend {
+  fn default() -> FallbackBackend {
+    FallbackBackend::new(vec![
+      Box::new(ProcessVmBackend),
+      Box::new(ProcMemBackend::new()),
+      Box::new(PtraceBackend),
+    ])
+  }
+}
+
+impl MemoryBackend for FallbackBackend {
+  fn read(&self, pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+    self.try_each(|backend| backend.read(pid, address, buffer))
+  }
+
+  fn write(&self, pid: Pid, address: usize, buffer: &[u8]) -> io::Result<usize> {
+    self.try_each(|backend| backend.write(pid, address, buffer))
+  }
+
+  fn read_batch(&self, pid: Pid, ranges: &mut [(usize, &mut [u8])]) -> io::Result<usize> {
+    self.try_each(|backend| backend.read_batch(pid, ranges))
+  }
+
+  fn write_batch(&self, pid: Pid, writes: &[(usize, &[u8])]) -> io::Result<usize> {
+    self.try_each(|backend| backend.write_batch(pid, writes))
+  }
+}
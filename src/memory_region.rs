@@ -1,3 +1,6 @@
+use std::fmt;
+use std::ops::Range;
+
 /// This describes how pages in the region can ba ccessed.  
 /// There are four different permissions, lets assume that  
 /// we have region with permissions == `r-xp` .  
@@ -7,19 +10,102 @@
 ///  
 /// You can find more detailed permissions description  
 /// in `MemoryRegion.permissions` field documentation.
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
 pub struct RegionPermissions {
   pub readable: bool,
   pub writeable: bool,
   pub executable: bool,
   pub shared: bool,
 }
+
+impl RegionPermissions {
+  /// Private region without any access permissions, `---p`.
+  pub const NONE: RegionPermissions = RegionPermissions::private(false, false, false);
+  /// Private read only region, `r--p`.
+  pub const R: RegionPermissions = RegionPermissions::private(true, false, false);
+  /// Private readable and writeable region, `rw-p`.
+  pub const RW: RegionPermissions = RegionPermissions::private(true, true, false);
+  /// Private readable and executable region, `r-xp`.
+  pub const RX: RegionPermissions = RegionPermissions::private(true, false, true);
+  /// Private readable, writeable and executable region, `rwxp`.
+  pub const RWX: RegionPermissions = RegionPermissions::private(true, true, true);
+
+  /// Returns permissions of private (copy on write) region, usable in constants.
+  pub const fn private(readable: bool, writeable: bool, executable: bool) -> RegionPermissions {
+    RegionPermissions {
+      readable,
+      writeable,
+      executable,
+      shared: false,
+    }
+  }
+
+  /// Returns **true** if every permission set in `required` is set in these  
+  /// permissions too, so `r-xp` matches at least `r--p`, but not `rw-p`.  
+  /// `shared` is treated like a permission, so private regions do not match  
+  /// shared `required`, but shared regions match private one.
+  ///
+  /// # Examples
+  /// ```
+  /// extern crate trickster;
+  /// use trickster::RegionPermissions;
+  ///
+  /// assert!(RegionPermissions::RWX.matches_at_least(&RegionPermissions::RW));
+  /// assert!(RegionPermissions::RX.matches_at_least(&RegionPermissions::R));
+  /// assert!(!RegionPermissions::RX.matches_at_least(&RegionPermissions::RW));
+  /// ```
+  pub fn matches_at_least(&self, required: &RegionPermissions) -> bool {
+    (self.readable || !required.readable)
+      && (self.writeable || !required.writeable)
+      && (self.executable || !required.executable)
+      && (self.shared || !required.shared)
+  }
+
+  /// Returns **PROT_READ**, **PROT_WRITE** and **PROT_EXEC** flags accepted  
+  /// by **mprotect(2)** and **mmap(2)**. `shared` field is not a protection  
+  /// flag, so it is ignored.
+  pub(crate) fn protection_flags(&self) -> libc::c_int {
+    let mut flags = libc::PROT_NONE;
+    if self.readable {
+      flags |= libc::PROT_READ;
+    }
+    if self.writeable {
+      flags |= libc::PROT_WRITE;
+    }
+    if self.executable {
+      flags |= libc::PROT_EXEC;
+    }
+    flags
+  }
+}
+
+impl fmt::Display for RegionPermissions {
+  /// Formats permissions like `/proc/\[pid\]/maps` does, e.g. `r-xp`.
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      formatter,
+      "{}{}{}{}",
+      if self.readable { 'r' } else { '-' },
+      if self.writeable { 'w' } else { '-' },
+      if self.executable { 'x' } else { '-' },
+      if self.shared { 's' } else { 'p' }
+    )
+  }
+}
 /// Each row in /proc/\[pid\]/maps describes a region of
 /// contiguous virtual memory in a process or thread.
 //  Each row has the following fields:
 //  address           perms offset  dev   inode   pathname
 //  08048000-08056000 r-xp 00000000 03:0c 64593   /usr/sbin/gpm
-#[derive(Debug)]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
 pub struct MemoryRegion {
   /// This is the starting address of the region in the process's address space.
   pub start: usize,
@@ -39,13 +125,14 @@ pub struct MemoryRegion {
   pub offset: usize,
   /// If the region was mapped from a file, this is the  
   /// major device number (in hex) where the file lives.
-  pub dev_major: u8,
+  pub dev_major: u32,
   /// If the region was mapped from a file, this is the  
   /// minor device number (in hex) where the file lives.
-  pub dev_minor: u8,
+  pub dev_minor: u32,
   /// If the region was mapped from a file, this is the file number.
   pub inode: usize,
   /// If the region was mapped from a file, this is the name of the file.  
+  /// Path is everything after the inode column, so it can contain spaces.  
   /// This field is [`None`] for anonymous mapped regions.  
   /// There are also special regions with names like  
   /// `[heap]`, `[stack]`, or `[vdso]` .  
@@ -54,4 +141,216 @@ pub struct MemoryRegion {
   ///
   /// [`None`]: https://doc.rust-lang.org/std/option/ 
   pub path: Option<String>,
+  /// **true** if the file region was mapped from was deleted after mapping.  
+  /// Kernel marks such regions with ` (deleted)` suffix after the path,  
+  /// which is not included in `path` field.
+  pub deleted: bool,
+  /// **true** if the region is mapped from the main executable of the process,  
+  /// see `RegionKind::MainExecutable`. It is set while regions are parsed,  
+  /// as path of the region alone does not tell the executable from other files.
+  #[cfg_attr(feature = "serde-support", serde(default))]
+  pub main_executable: bool,
+}
+
+/// Category of memory region, derived from its path and permissions  
+/// by `MemoryRegion::kind()`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub enum RegionKind {
+  /// Heap of the main program, `[heap]` region.
+  Heap,
+  /// Stack of the main thread, `[stack]` region.
+  Stack,
+  /// Region mapped from the main executable of the process. It is assumed  
+  /// to be the file of the first file-backed region, since the kernel maps  
+  /// the executable below libraries and other files.
+  MainExecutable,
+  /// Region mapped from shared library, that is file which name  
+  /// ends with `.so` or contains `.so.` (e.g. `libc.so.6`).
+  SharedLibrary,
+  /// Virtual dynamic shared object, `[vdso]` region, or legacy `[vsyscall]` page.
+  Vdso,
+  /// Variables of virtual dynamic shared object, `[vvar]` region and similar.  
+  /// They can not be read by other processes.
+  Vvar,
+  /// Region not mapped from a file, like memory allocated with **mmap(2)**  
+  /// or heap of threads.
+  Anonymous,
+  /// Region mapped from file which is neither executable nor library,  
+  /// e.g. `/usr/lib/locale/locale-archive`.
+  MappedFile,
+  /// Private region without any access permissions, like stack guard pages  
+  /// or gaps between segments of libraries.
+  Guard,
+}
+
+impl MemoryRegion {
+  /// Returns size of the region in bytes.
+  pub fn size(&self) -> usize {
+    self.end - self.start
+  }
+
+  /// Returns **true** if `address` lies within the region.
+  pub fn contains(&self, address: usize) -> bool {
+    self.start <= address && address < self.end
+  }
+
+  /// Returns **true** if the region shares at least one address with `range`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let structure = 0x601040..0x601440;
+  ///   for region in ctx.get_memory_regions()?.iter().filter(|region| region.overlaps(&structure)) {
+  ///     println!("{:#x} {} {:#x} bytes", region.start, region.permissions, region.size());
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn overlaps(&self, range: &Range<usize>) -> bool {
+    self.start < range.end && range.start < self.end
+  }
+
+  /// Returns category of the region, derived from its path and permissions,  
+  /// so regions can be filtered without comparing paths like `[heap]`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RegionKind};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for region in ctx.get_memory_regions()? {
+  ///     if region.kind() == RegionKind::SharedLibrary {
+  ///       println!("{:#x} {}", region.start, region.path.as_ref().unwrap());
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x7f1c2a400000 /usr/lib/x86_64-linux-gnu/libc.so.6`
+  pub fn kind(&self) -> RegionKind {
+    let path = match &self.path {
+      Some(path) => path.as_str(),
+      None => "",
+    };
+
+    match path {
+      "[heap]" => return RegionKind::Heap,
+      "[vdso]" | "[vsyscall]" => return RegionKind::Vdso,
+      _ if path == "[stack]" || path.starts_with("[stack:") => return RegionKind::Stack,
+      _ if path.starts_with("[vvar") => return RegionKind::Vvar,
+      _ => {}
+    }
+
+    if self.permissions.protection_flags() == libc::PROT_NONE && !self.permissions.shared {
+      return RegionKind::Guard;
+    }
+
+    if !path.starts_with('/') {
+      RegionKind::Anonymous
+    } else if self.main_executable {
+      RegionKind::MainExecutable
+    } else if is_shared_library(self.file_name()) {
+      RegionKind::SharedLibrary
+    } else {
+      RegionKind::MappedFile
+    }
+  }
+
+  /// Returns file name of the region, that is `path` field trimmed  
+  /// to contain only part after the last `/`. Regions without  
+  /// path are named `[anonymous_region]`.
+  pub(crate) fn file_name(&self) -> &str {
+    match &self.path {
+      Some(path) => match path.rfind('/') {
+        Some(index_to_split) => &path[index_to_split + 1..],
+        None => path,
+      },
+      None => "[anonymous_region]",
+    }
+  }
+}
+
+impl fmt::Display for MemoryRegion {
+  /// Formats the region as row of aligned columns: address range, permissions,  
+  /// size in human readable units and path, e.g.  
+  /// `7f1c2a400000-7f1c2a426000 r--p  152.0 KiB  /usr/lib/libc.so.6`.
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      formatter,
+      "{:>12x}-{:<12x} {} {:>10}",
+      self.start,
+      self.end,
+      self.permissions,
+      format_size(self.size())
+    )?;
+    if let Some(path) = &self.path {
+      write!(formatter, "  {}", path)?;
+    }
+    if self.deleted {
+      write!(formatter, " (deleted)")?;
+    }
+    Ok(())
+  }
+}
+
+/// Formats `size` in bytes in human readable binary units, e.g. `152.0 KiB`.
+pub(crate) fn format_size(size: usize) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+  if size < 1024 {
+    return format!("{} B", size);
+  }
+  let mut value = size as f64 / 1024.0;
+  let mut unit = 0;
+  while value >= 1024.0 && unit < UNITS.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+  format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Returns **true** if `file_name` looks like name of shared library,  
+/// that is ends with `.so` or contains `.so.` (e.g. `libc.so.6`).
+fn is_shared_library(file_name: &str) -> bool {
+  file_name.ends_with(".so") || file_name.contains(".so.")
+}
+
+/// Region name pattern used by region lookups. Name matches the pattern  
+/// if it is equal to it, or if it matches it as a glob pattern (e.g. `libssl*.so*`).  
+/// Exact comparison comes first, so special names like `[heap]` are not  
+/// treated as glob character classes.
+#[derive(Clone, Debug)]
+pub(crate) struct RegionNamePattern {
+  name: String,
+  glob: Option<glob::Pattern>,
 }
+
+impl RegionNamePattern {
+  pub(crate) fn new(name: &str) -> RegionNamePattern {
+    RegionNamePattern {
+      name: name.to_string(),
+      glob: glob::Pattern::new(name).ok(),
+    }
+  }
+
+  /// Returns **true** if file name of `region` matches the pattern.
+  pub(crate) fn matches(&self, region: &MemoryRegion) -> bool {
+    let file_name = region.file_name();
+    file_name == self.name || self.glob.as_ref().is_some_and(|glob| glob.matches(file_name))
+  }
+}
\ No newline at end of file
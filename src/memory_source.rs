@@ -0,0 +1,85 @@
+use anyhow::Result;
+use bytemuck::Pod;
+use std::mem;
+
+use super::{MemoryRegion, Process};
+
+/// Source of process memory and its layout, implemented by live [`Process`]  
+/// and by [`DumpProcess`] backed by memory captured earlier. Code written  
+/// against `MemorySource` works the same on both, e.g. analysis which is  
+/// developed on a dump and later run on live process.
+///
+/// [`Process`]: struct.Process.html
+/// [`DumpProcess`]: struct.DumpProcess.html
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{DumpProcess, MemorySource, Process};
+///
+/// fn count_players<M: MemorySource>(source: &M) -> Result<u32, Box<dyn std::error::Error>> {
+///   Ok(source.read::<u32>(0x601038)?)
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.parse_maps()?;
+///   let dump = DumpProcess::from_core_file("current_process_name.core")?;
+///
+///   println!("live: {}, dump: {}", count_players(&ctx)?, count_players(&dump)?);
+///   Ok(())
+/// }
+/// ```
+/// This prints output like:  
+/// `live: 12, dump: 10`
+pub trait MemorySource {
+  /// Fills `buffer` with memory at `address`, see `Process::read_memory_into()`.  
+  /// Returns number of bytes read, which is lower than length of `buffer`  
+  /// if reading stopped at memory which is not available.
+  fn read_memory_into(&self, address: usize, buffer: &mut [u8]) -> Result<usize>;
+
+  /// Returns memory regions, see `Process::get_memory_regions()`.
+  fn memory_regions(&self) -> Result<&[MemoryRegion]>;
+
+  /// Returns `length` bytes of memory read at `address`. If less than `length`  
+  /// bytes were read, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  fn read_memory_bytes(&self, address: usize, length: usize) -> Result<Vec<u8>> {
+    let mut buffer = vec![0u8; length];
+    if self.read_memory_into(address, &mut buffer)? != length {
+      return Err(anyhow!("Could not read memory. Partial read occurred."));
+    }
+    Ok(buffer)
+  }
+
+  /// Returns value of generic type (`T`) read at `address`, see `Process::read()`.
+  fn read<T: Pod>(&self, address: usize) -> Result<T>
+  where
+    Self: Sized,
+  {
+    let mut value = T::zeroed();
+    if self.read_memory_into(address, bytemuck::bytes_of_mut(&mut value))? != mem::size_of::<T>() {
+      return Err(anyhow!("Could not read memory. Partial read occurred."));
+    }
+    Ok(value)
+  }
+
+  /// Returns the first memory region containing `address`, or [`None`]  
+  /// if there is no such region.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  fn region_containing(&self, address: usize) -> Result<Option<&MemoryRegion>> {
+    Ok(self.memory_regions()?.iter().find(|region| region.contains(address)))
+  }
+}
+
+impl MemorySource for Process {
+  fn read_memory_into(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+    Process::read_memory_into(self, address, buffer)
+  }
+
+  fn memory_regions(&self) -> Result<&[MemoryRegion]> {
+    Ok(self.get_memory_regions()?.as_slice())
+  }
+}
@@ -0,0 +1,419 @@
+use anyhow::Result;
+use nix::unistd::Pid;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use super::dump_process::maps_line;
+use super::memory_backend::MemoryBackend;
+use super::Process;
+
+/// Fake memory region of `MockMemory`.
+struct MockRegion {
+  start: usize,
+  permissions: String,
+  path: Option<String>,
+  bytes: Vec<u8>,
+}
+
+impl MockRegion {
+  /// Returns ending address of the region (exclusive).
+  fn end(&self) -> usize {
+    self.start + self.bytes.len()
+  }
+}
+
+/// Memory backend with fake regions and contents defined by the caller,  
+/// so code built on top of this crate (e.g. scanning or pointer chain logic)  
+/// can be unit tested without spawning processes or permission to trace them.  
+/// Requires `testing` feature.
+///
+/// Like **process_vm_readv(2)**, reads fail on regions which are not readable  
+/// and writes on regions which are not writable.
+///
+/// # Examples
+/// ```
+/// extern crate trickster;
+/// use trickster::{MockMemory, Signature};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let ctx = MockMemory::new()
+///     .region(0x400000, "r-xp", Some("/usr/bin/game"), vec![0x90, 0x48, 0x8B, 0x05, 0xC3])
+///     .region(0x601000, "rw-p", None, 1337u32.to_ne_bytes().to_vec())
+///     .into_process("game")?;
+///
+///   assert_eq!(ctx.scan(&Signature::new("48 8B ??")?)?, vec![0x400001]);
+///   assert_eq!(ctx.read::<u32>(0x601000)?, 1337);
+///
+///   ctx.write::<u32>(0x601000, &10)?;
+///   assert_eq!(ctx.read::<u32>(0x601000)?, 10);
+///   assert!(ctx.write::<u8>(0x400000, &0xCC).is_err());
+///   Ok(())
+/// }
+/// ```
+#[derive(Default)]
+pub struct MockMemory {
+  /// Regions sorted by address.
+  regions: Mutex<Vec<MockRegion>>,
+  /// Starting and ending (exclusive) addresses of ranges which can not be read.
+  holes: Vec<(usize, usize)>,
+}
+
+impl MockMemory {
+  /// MockMemory object constructor. Creates memory without any regions.
+  pub fn new() -> MockMemory {
+    MockMemory::default()
+  }
+
+  /// Adds region starting at `start` with contents `bytes`, so it ends  
+  /// at `start + bytes.len()`. `permissions` are in `/proc/\[pid\]/maps`  
+  /// format (e.g. `rw-p`) and `path` is the name of mapped file or special  
+  /// region like `[heap]`.
+  pub fn region(self, start: usize, permissions: &str, path: Option<&str>, bytes: Vec<u8>) -> Self {
+    {
+      let mut regions = self.lock();
+      let index = regions.partition_point(|region| region.start < start);
+      regions.insert(
+        index,
+        MockRegion {
+          start,
+          permissions: permissions.to_string(),
+          path: path.map(str::to_string),
+          bytes,
+        },
+      );
+    }
+    self
+  }
+
+  /// Makes `length` bytes at `start` unreadable, while regions containing them  
+  /// are still listed in maps, like pages of file mapping past the end  
+  /// of the file. Reads stop at the hole, like at unmapped memory.
+  pub fn hole(mut self, start: usize, length: usize) -> Self {
+    self.holes.push((start, start + length));
+    self
+  }
+
+  /// Builds process object named `name` backed by this memory, with memory  
+  /// regions already parsed. If regions overlap or their permissions  
+  /// are malformed, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn into_process(self, name: &str) -> Result<Process> {
+    {
+      let regions = self.lock();
+      if let Some(pair) = regions.windows(2).find(|pair| pair[0].end() > pair[1].start) {
+        return Err(anyhow!(
+          "Could not build mock process (region at {:#x} overlaps region at {:#x}).",
+          pair[1].start,
+          pair[0].start
+        ));
+      }
+    }
+    Process::from_backend(name.to_string(), Arc::new(self))
+  }
+
+  /// Locks regions, ignoring poisoning, as regions are never left half-modified.
+  fn lock(&self) -> std::sync::MutexGuard<'_, Vec<MockRegion>> {
+    self.regions.lock().unwrap_or_else(|error| error.into_inner())
+  }
+
+  /// Returns index of region containing `address` if its permissions  
+  /// have `permission` character at `position`.
+  fn find(
+    regions: &[MockRegion],
+    address: usize,
+    position: usize,
+    permission: u8,
+  ) -> Option<usize> {
+    let index = regions.partition_point(|region| region.start <= address).checked_sub(1)?;
+    let region = &regions[index];
+    if address < region.end() && region.permissions.as_bytes().get(position) == Some(&permission) {
+      Some(index)
+    } else {
+      None
+    }
+  }
+}
+
+impl MemoryBackend for MockMemory {
+  fn read(&self, _pid: Pid, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+    let regions = self.lock();
+    let mut bytes_read = 0;
+
+    while bytes_read < buffer.len() {
+      let current = address.wrapping_add(bytes_read);
+      let region = match MockMemory::find(&regions, current, 0, b'r') {
+        Some(index) => &regions[index],
+        None => break,
+      };
+      if self.holes.iter().any(|hole| hole.0 <= current && current < hole.1) {
+        break;
+      }
+      let offset = current - region.start;
+      // Reading stops at the nearest hole following the current address.
+      let hole_start = self.holes.iter().map(|hole| hole.0).filter(|start| *start > current).min();
+      let length = (region.bytes.len() - offset)
+        .min(buffer.len() - bytes_read)
+        .min(hole_start.map_or(usize::MAX, |start| start - current));
+      buffer[bytes_read..bytes_read + length]
+        .copy_from_slice(&region.bytes[offset..offset + length]);
+      bytes_read += length;
+    }
+
+    if bytes_read == 0 && !buffer.is_empty() {
+      return Err(io::Error::from_raw_os_error(libc::EFAULT));
+    }
+    Ok(bytes_read)
+  }
+
+  fn write(&self, _pid: Pid, address: usize, buffer: &[u8]) -> io::Result<usize> {
+    let mut regions = self.lock();
+    let mut bytes_written = 0;
+
+    while bytes_written < buffer.len() {
+      let current = address.wrapping_add(bytes_written);
+      let region = match MockMemory::find(&regions, current, 1, b'w') {
+        Some(index) => &mut regions[index],
+        None => break,
+      };
+      let offset = current - region.start;
+      let length = (region.bytes.len() - offset).min(buffer.len() - bytes_written);
+      region.bytes[offset..offset + length]
+        .copy_from_slice(&buffer[bytes_written..bytes_written + length]);
+      bytes_written += length;
+    }
+
+    if bytes_written == 0 && !buffer.is_empty() {
+      return Err(io::Error::from_raw_os_error(libc::EFAULT));
+    }
+    Ok(bytes_written)
+  }
+
+  fn maps(&self, _pid: Pid) -> Option<String> {
+    Some(
+      self
+        .lock()
+        .iter()
+        .map(|region| {
+          maps_line(
+            region.start,
+            region.end(),
+            &region.permissions,
+            0,
+            region.path.as_deref(),
+          )
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::scan::SCAN_CHUNK_SIZE;
+  use super::super::{MemoryRegion, PointerMap, PointerScanOptions, Signature, SignatureSet};
+  use super::MockMemory;
+
+  /// Returns `length` zero bytes with `values` written at their offsets.
+  fn bytes_with(length: usize, values: &[(usize, &[u8])]) -> Vec<u8> {
+    let mut bytes = vec![0u8; length];
+    for (offset, value) in values {
+      bytes[*offset..*offset + value.len()].copy_from_slice(value);
+    }
+    bytes
+  }
+
+  #[test]
+  fn scans_readable_regions() {
+    let code = bytes_with(
+      0x1000,
+      &[(0x10, &[0x48, 0x8b, 0x05]), (0x800, &[0x48, 0x8b, 0xc3])],
+    );
+    let process = MockMemory::new()
+      .region(0x400000, "r-xp", Some("/usr/bin/game"), code)
+      .region(
+        0x500000,
+        "---p",
+        None,
+        bytes_with(0x1000, &[(0, &[0x48, 0x8b, 0x00])]),
+      )
+      .region(
+        0x600000,
+        "rw-p",
+        None,
+        bytes_with(0x1000, &[(0xff0, &[0x48, 0x8b, 0x01])]),
+      )
+      .into_process("game")
+      .unwrap();
+    let signature = Signature::new("48 8B ??").unwrap();
+
+    assert_eq!(
+      process.scan(&signature).unwrap(),
+      vec![0x400010, 0x400800, 0x600ff0]
+    );
+    assert_eq!(process.scan_first(&signature).unwrap(), 0x400010);
+    assert_eq!(
+      process.scan_module("game", &signature).unwrap(),
+      vec![0x400010, 0x400800]
+    );
+    assert!(process.scan_module("libc.so.6", &signature).is_err());
+    assert_eq!(
+      process.find_bytes(&[0x48, 0x8b, 0xc3], process.regions().unwrap()).unwrap(),
+      vec![0x400800]
+    );
+  }
+
+  #[test]
+  fn scans_matches_crossing_chunk_boundary() {
+    let heap = bytes_with(
+      2 * SCAN_CHUNK_SIZE,
+      &[(SCAN_CHUNK_SIZE - 2, &[0xde, 0xad, 0xbe, 0xef])],
+    );
+    let process = MockMemory::new()
+      .region(0x10000000, "rw-p", Some("[heap]"), heap)
+      .into_process("game")
+      .unwrap();
+
+    assert_eq!(
+      process.scan(&Signature::new("DE AD BE EF").unwrap()).unwrap(),
+      vec![0x10000000 + SCAN_CHUNK_SIZE - 2]
+    );
+  }
+
+  #[test]
+  fn scans_past_holes() {
+    let process = MockMemory::new()
+      .region(
+        0x400000,
+        "rw-p",
+        None,
+        bytes_with(0x1000, &[(0x10, &[0xca, 0xfe])]),
+      )
+      .region(
+        0x402000,
+        "rw-p",
+        None,
+        bytes_with(0x1000, &[(0x20, &[0xca, 0xfe])]),
+      )
+      .into_process("game")
+      .unwrap();
+    // Region spanning both mock regions, like the stale one after part of it was unmapped.
+    let region = MemoryRegion {
+      end: 0x403000,
+      ..process.get_memory_regions().unwrap()[0].clone()
+    };
+
+    assert_eq!(
+      process.scan_region(&region, &Signature::new("CA FE").unwrap()).unwrap(),
+      vec![0x400010, 0x402020]
+    );
+  }
+
+  #[test]
+  fn resolves_signature_set() {
+    let code = bytes_with(
+      0x1000,
+      &[(0x100, &[0x55, 0x48, 0x89, 0xe5]), (0x200, &[0xe8, 0x01])],
+    );
+    let process = MockMemory::new()
+      .region(0x400000, "r-xp", Some("/usr/bin/game"), code)
+      .region(
+        0x600000,
+        "rw-p",
+        None,
+        bytes_with(0x1000, &[(0x80, &[0xe8, 0x02])]),
+      )
+      .into_process("game")
+      .unwrap();
+
+    let mut signatures = SignatureSet::new();
+    signatures.add_in_module("prologue", "game", Signature::new("55 48 89 E5").unwrap());
+    signatures.add("call", Signature::new("E8 02").unwrap());
+    signatures.add("missing", Signature::new("CC CC").unwrap());
+    signatures.add_in_module("other", "libc.so.6", Signature::new("55").unwrap());
+    let resolved = process.resolve_signatures(&signatures).unwrap();
+
+    assert_eq!(resolved.get("prologue").unwrap(), 0x400100);
+    assert_eq!(resolved.get("call").unwrap(), 0x600080);
+    assert!(resolved.get("missing").is_err());
+    assert!(resolved.get("other").is_err());
+  }
+
+  #[test]
+  fn finds_pointer_paths() {
+    let pointer = |value: usize| value.to_ne_bytes().to_vec();
+    let process = MockMemory::new()
+      .region(0x400000, "r-xp", Some("/usr/bin/game"), vec![0; 0x1000])
+      .region(
+        0x600000,
+        "rw-p",
+        Some("/usr/bin/game"),
+        bytes_with(0x1000, &[(0x10, &pointer(0x10000000))]),
+      )
+      // Anonymous region right after the data segment of a module is its .bss section.
+      .region(
+        0x601000,
+        "rw-p",
+        None,
+        bytes_with(0x1000, &[(0x40, &pointer(0x10000100))]),
+      )
+      // Anonymous region not following any module is not static.
+      .region(
+        0x700000,
+        "rw-p",
+        None,
+        bytes_with(0x1000, &[(0, &pointer(0x10000100))]),
+      )
+      .region(
+        0x10000000,
+        "rw-p",
+        Some("[heap]"),
+        bytes_with(0x1000, &[(0x8, &pointer(0x10000100))]),
+      )
+      .into_process("game")
+      .unwrap();
+    let target = 0x10000120;
+    let pointer_map = PointerMap::new(&process).unwrap();
+
+    let mut paths = pointer_map.find_paths(target, &PointerScanOptions::default());
+    paths.sort_by(|left, right| left.offsets.cmp(&right.offsets));
+    let offsets: Vec<&[usize]> = paths.iter().map(|path| path.offsets.as_slice()).collect();
+    assert_eq!(
+      offsets,
+      vec![
+        &[0x200010, 0x8, 0x20][..],
+        &[0x200010, 0x120],
+        &[0x201040, 0x20]
+      ]
+    );
+    for path in &paths {
+      assert_eq!(path.module, "game");
+      assert_eq!(path.resolve(&process).unwrap(), target);
+    }
+
+    let options = PointerScanOptions {
+      max_depth: 1,
+      ..PointerScanOptions::default()
+    };
+    assert_eq!(pointer_map.find_paths(target, &options).len(), 2);
+  }
+
+  #[test]
+  fn maps_pointers_past_holes() {
+    let mut heap = vec![0; 0x3000];
+    heap[0x100..0x108].copy_from_slice(&0x10000010usize.to_ne_bytes());
+    heap[0x2100..0x2108].copy_from_slice(&0x10000020usize.to_ne_bytes());
+    let process = MockMemory::new()
+      .region(0x10000000, "rw-p", Some("[heap]"), heap)
+      .hole(0x10001000, 0x1000)
+      .into_process("game")
+      .unwrap();
+    let pointer_map = PointerMap::new(&process).unwrap();
+
+    assert_eq!(pointer_map.len(), 2);
+    assert_eq!(
+      pointer_map.pointers_to(0x10000020, 0),
+      vec![(0x10002100, 0)]
+    );
+  }
+}
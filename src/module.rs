@@ -0,0 +1,399 @@
+use anyhow::Result;
+use nix::unistd::Pid;
+#[cfg(target_arch = "x86_64")]
+use std::convert::TryInto;
+use std::fmt;
+use std::fs;
+use std::path;
+
+use super::elf::{ElfFile, PT_LOAD};
+#[cfg(target_arch = "x86_64")]
+use super::elf::PF_X;
+use super::memory_region::format_size;
+use super::{MemoryRegion, Process};
+
+/// Maximum number of bytes read by `Module::read_elf_head()`.
+#[cfg(feature = "serde-support")]
+const ELF_HEAD_SIZE: usize = 0x10000;
+
+/// Module is a file (executable or shared library) mapped into process  
+/// memory, that is all consecutive memory regions mapped from the same file.  
+/// With `serde-support` feature enabled, module can be serialized together  
+/// with its base, end and regions. It can not be deserialized, as it only  
+/// borrows regions of the process, deserialize its regions instead.
+#[derive(Clone, Debug)]
+pub struct Module<'a> {
+  pid: Pid,
+  path: &'a str,
+  regions: Vec<&'a MemoryRegion>,
+}
+
+#[cfg(feature = "serde-support")]
+impl<'a> ::serde::Serialize for Module<'a> {
+  fn serialize<S: ::serde::Serializer>(
+    &self,
+    serializer: S,
+  ) -> std::result::Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+
+    let mut module = serializer.serialize_struct("Module", 4)?;
+    module.serialize_field("path", self.path)?;
+    module.serialize_field("base", &self.base())?;
+    module.serialize_field("end", &self.end())?;
+    module.serialize_field("regions", &self.regions)?;
+    module.end()
+  }
+}
+
+impl<'a> Module<'a> {
+  /// Returns base address of the module, that is start of its first region.
+  pub fn base(&self) -> usize {
+    self.regions[0].start
+  }
+
+  /// Returns address one past the end of the last module region.
+  pub fn end(&self) -> usize {
+    self.regions[self.regions.len() - 1].end
+  }
+
+  /// Returns size of the module in memory, including gaps between its regions.
+  pub fn size(&self) -> usize {
+    self.end() - self.base()
+  }
+
+  /// Returns full path of the file module is mapped from.
+  pub fn path(&self) -> &'a str {
+    self.path
+  }
+
+  /// Returns file name of the module, that is `path` trimmed  
+  /// to contain only part after the last slash.
+  pub fn name(&self) -> &'a str {
+    self.regions[0].file_name()
+  }
+
+  /// Returns memory regions module consists of, sorted by address.
+  pub fn regions(&self) -> &[&'a MemoryRegion] {
+    &self.regions
+  }
+
+  /// Returns **true** if `address` is located in any of module regions.
+  pub fn contains(&self, address: usize) -> bool {
+    self
+      .regions
+      .iter()
+      .any(|region| address >= region.start && address < region.end)
+  }
+
+  /// Returns load bias of the module, that is difference between addresses  
+  /// it is mapped at and virtual addresses in its ELF file. Adding load bias  
+  /// to address from static analysis (e.g. in Ghidra or IDA, with image base  
+  /// matching virtual addresses of the file) gives address in process memory.  
+  /// Load bias of position independent modules equals address of their  
+  /// first segment, while non-PIE executables have load bias of 0.
+  ///
+  /// Bias is computed from program headers of ELF file the module is mapped  
+  /// from, read through `/proc/\[pid\]/root/`, so it is found even if process  
+  /// runs in different mount namespace, e.g. in a container. If the file could  
+  /// not be read, or none of its loadable segments matches the mapping,  
+  /// [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let game = ctx.module("game")?;
+  ///   let health_address = game.load_bias()? + 0x1a2b30;
+  ///   println!("health: {}", ctx.read::<i32>(health_address)?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `health: 100`
+  pub fn load_bias(&self) -> Result<usize> {
+    let elf = self.read_elf()?;
+    self.load_bias_of(&elf)
+  }
+
+  /// Returns GNU build-id of the module as lowercase hexadecimal string,  
+  /// read from `.note.gnu.build-id` of ELF file the module is mapped from  
+  /// (see `load_bias()`). Build-id uniquely identifies the build of a binary,  
+  /// so it can be used to recognize the same module in different processes.  
+  /// If the file could not be read or has no build-id, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   println!("libc build-id: {}", ctx.module("libc.so.6")?.build_id()?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `libc build-id: 4b3f1c0d2a7e8a1f9d2c5b6e0f7a8b9c1d2e3f40`
+  pub fn build_id(&self) -> Result<String> {
+    self
+      .read_elf()?
+      .build_id()
+      .ok_or_else(|| anyhow!("Could not find build-id of module {}.", self.path))
+  }
+
+  /// Returns addresses of instructions in executable segments of the module  
+  /// which refer to `target_address`, e.g. to find all callers of function found  
+  /// with signature scan. Recognized are x86-64 instructions with displacement  
+  /// relative to their end: `call rel32`, `jmp rel32`, conditional `jcc rel32`,  
+  /// `call [rip+disp32]`, `jmp [rip+disp32]` and 64-bit `lea reg, [rip+disp32]`.  
+  /// Short jumps (`rel8`) are not recognized.
+  ///
+  /// Code is read from ELF file the module is mapped from (see `load_bias()`)  
+  /// and searched byte by byte instead of being disassembled, so in rare cases  
+  /// bytes in the middle of other instruction may be reported as reference.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Signature};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let signature = Signature::new("55 48 89 E5 89 7D FC 8B 05")?;
+  ///   let update_health = ctx.scan_module_first("game", &signature)?;
+  ///   for caller in ctx.module("game")?.find_xrefs(update_health)? {
+  ///     println!("{}", ctx.symbolicate(caller)?);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `game!on_hit+0x2f`  
+  /// `game!tick+0x1c4`
+  #[cfg(target_arch = "x86_64")]
+  pub fn find_xrefs(&self, target_address: usize) -> Result<Vec<usize>> {
+    let elf = self.read_elf()?;
+    let load_bias = self.load_bias_of(&elf)?;
+    let mut references: Vec<usize> = Vec::new();
+
+    for header in elf
+      .program_headers()
+      .iter()
+      .filter(|header| header.p_type == PT_LOAD && header.flags & PF_X != 0)
+    {
+      let code = elf.segment_data(header);
+      let segment_address = load_bias.wrapping_add(header.vaddr);
+
+      for index in 0..code.len() {
+        let (displacement_offset, instruction_length) = match relative_instruction(&code[index..]) {
+          Some(layout) => layout,
+          None => continue,
+        };
+        if index + instruction_length > code.len() {
+          continue;
+        }
+
+        let displacement_start = index + displacement_offset;
+        let displacement =
+          i32::from_ne_bytes(code[displacement_start..displacement_start + 4].try_into().unwrap());
+        let address = segment_address.wrapping_add(index);
+        let referenced = address
+          .wrapping_add(instruction_length)
+          .wrapping_add(displacement as isize as usize);
+        if referenced == target_address {
+          references.push(address);
+        }
+      }
+    }
+
+    references.sort_unstable();
+    Ok(references)
+  }
+
+  /// Reads and parses ELF file the module is mapped from, see `load_bias()`.
+  pub(crate) fn read_elf(&self) -> Result<ElfFile> {
+    let root_path = path::Path::new("/proc/")
+      .join(self.pid.to_string())
+      .join("root")
+      .join(self.path.trim_start_matches('/'));
+
+    let data = fs::read(root_path)
+      .or_else(|_| fs::read(self.path))
+      .map_err(|error| anyhow!("Could not read ELF file {} ({}).", self.path, error))?;
+
+    ElfFile::parse(data, self.path)
+  }
+
+  /// Reads beginning of ELF file the module is mapped from, that is ELF header,  
+  /// program headers and usually notes, from memory of `process`. It is much  
+  /// faster than `read_elf()`, but section headers are not available.
+  #[cfg(feature = "serde-support")]
+  pub(crate) fn read_elf_head(&self, process: &Process) -> Result<ElfFile> {
+    let region = self.regions[0];
+    if region.offset != 0 {
+      return Err(anyhow!(
+        "Could not read ELF header of module {} (it is not mapped).",
+        self.path
+      ));
+    }
+
+    let length = region.size().min(ELF_HEAD_SIZE);
+    ElfFile::parse(process.read_memory_bytes(region.start, length)?, self.path)
+  }
+
+  /// Computes load bias from loadable segment of `elf`  
+  /// which contains the first region of the module.
+  pub(crate) fn load_bias_of(&self, elf: &ElfFile) -> Result<usize> {
+    let region = self.regions[0];
+
+    elf
+      .program_headers()
+      .into_iter()
+      .filter(|header| header.p_type == PT_LOAD)
+      .find(|header| {
+        header.offset < region.offset + region.size()
+          && region.offset <= header.offset + header.file_size
+      })
+      .map(|header| {
+        region
+          .start
+          .wrapping_add(header.offset)
+          .wrapping_sub(region.offset)
+          .wrapping_sub(header.vaddr)
+      })
+      .ok_or_else(|| {
+        anyhow!(
+          "Could not compute load bias of module {} (no segment matches its mapping).",
+          self.path
+        )
+      })
+  }
+}
+
+impl Process {
+  /// Returns all modules mapped into process memory, in order of  
+  /// their base addresses. Only regions mapped from files are taken  
+  /// into account, special regions like `[heap]` are skipped.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `modules();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for module in ctx.modules()? {
+  ///     println!("{:#x} {:#x} {}", module.base(), module.size(), module.path());
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x7f0c8e3a1000 0x1c2000 /usr/lib/libc.so.6`
+  pub fn modules(&self) -> Result<Vec<Module<'_>>> {
+    let mut modules: Vec<Module> = Vec::new();
+
+    for region in self.get_memory_regions()? {
+      let path = match &region.path {
+        Some(path) if path.starts_with('/') => path.as_str(),
+        _ => continue,
+      };
+
+      match modules.last_mut() {
+        Some(module) if module.path == path && module.end() <= region.start => {
+          module.regions.push(region);
+        }
+        _ => modules.push(Module {
+          pid: self.get_pid(),
+          path,
+          regions: vec![region],
+        }),
+      }
+    }
+
+    Ok(modules)
+  }
+
+  /// Returns the first module which file name or full path equals `name`.  
+  /// If there is no such module, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `module();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let libc = ctx.module("libc.so.6")?;
+  ///   println!("libc base: {:#x}", libc.base());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn module(&self, name: &str) -> Result<Module<'_>> {
+    self
+      .modules()?
+      .into_iter()
+      .find(|module| module.name() == name || module.path() == name)
+      .ok_or_else(|| anyhow!("Could not find module {}.", name))
+  }
+}
+
+impl<'a> fmt::Display for Module<'a> {
+  /// Formats the module like `MemoryRegion` is formatted, without permissions  
+  /// and with number of its regions after the path, e.g.  
+  /// `7f1c2a400000-7f1c2a5d5000         1.8 MiB  /usr/lib/libc.so.6 (5 regions)`.
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      formatter,
+      "{:>12x}-{:<12x}      {:>10}  {} ({} region{})",
+      self.base(),
+      self.end(),
+      format_size(self.size()),
+      self.path,
+      self.regions.len(),
+      if self.regions.len() == 1 { "" } else { "s" }
+    )
+  }
+}
+
+/// Returns offset of 32-bit displacement and length of instruction at start of `code`  
+/// if it is one of instructions recognized by `Module::find_xrefs()`.
+#[cfg(target_arch = "x86_64")]
+fn relative_instruction(code: &[u8]) -> Option<(usize, usize)> {
+  match code {
+    // call rel32, jmp rel32
+    [0xe8, ..] | [0xe9, ..] => Some((1, 5)),
+    // jcc rel32
+    [0x0f, 0x80..=0x8f, ..] => Some((2, 6)),
+    // call [rip+disp32], jmp [rip+disp32]
+    [0xff, 0x15, ..] | [0xff, 0x25, ..] => Some((2, 6)),
+    // lea r64, [rip+disp32]
+    [0x48, 0x8d, modrm, ..] | [0x4c, 0x8d, modrm, ..] if modrm & 0xc7 == 0x05 => Some((3, 7)),
+    _ => None,
+  }
+}
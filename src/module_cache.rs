@@ -0,0 +1,195 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::{ffi, fs, io, path};
+
+use super::{Process, Signature};
+
+/// Cached results for single module build, stored as one JSON file.  
+/// Symbols are stored as ELF virtual addresses and signature matches  
+/// as offsets from module base, so they are valid regardless of ASLR.
+#[derive(Default, ::serde::Serialize, ::serde::Deserialize)]
+struct CacheEntry {
+  symbols: BTreeMap<String, usize>,
+  signatures: BTreeMap<String, Vec<usize>>,
+}
+
+/// On-disk cache of resolved symbols and signature scan results, keyed by  
+/// build-id of modules (see `Module::build_id()`). Results are computed  
+/// once per build of a binary, so repeated attaches to processes running  
+/// the same binary do not parse ELF symbol tables or scan memory again.
+///
+/// Each module build is stored as JSON file named after its build-id  
+/// in cache directory. Build-id is read from process memory, so ELF file  
+/// of the module is read only on cache miss. Modules without build-id  
+/// are not cached.
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{ModuleCache, Process, Signature};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.parse_maps()?;
+///
+///   let cache = ModuleCache::new("/tmp/trickster-cache")?;
+///   let malloc_address = cache.resolve_symbol(&ctx, "libc.so.6", "malloc")?;
+///   let signature = Signature::new("48 8B ?? ?? E8 ? ? ? ?")?;
+///   let matches = cache.scan_module(&ctx, "game", &signature)?;
+///   println!("malloc: {:#x}, matches: {}", malloc_address, matches.len());
+///   Ok(())
+/// }
+/// ```
+/// This prints output like:  
+/// `malloc: 0x7f0c8e41e0a0, matches: 1`
+pub struct ModuleCache {
+  directory: path::PathBuf,
+}
+
+impl ModuleCache {
+  /// ModuleCache object constructor. Creates cache `directory`  
+  /// (with missing parent directories) if it does not exist.
+  pub fn new<P: AsRef<path::Path>>(directory: P) -> Result<ModuleCache> {
+    let directory = directory.as_ref().to_path_buf();
+    fs::create_dir_all(&directory).map_err(|error| {
+      anyhow!(
+        "Could not create cache directory {} ({}).",
+        directory.display(),
+        error
+      )
+    })?;
+
+    Ok(ModuleCache { directory })
+  }
+
+  /// Returns address of `symbol` defined in module `module_name` of `process`,  
+  /// like `Process::resolve_symbol()`, using cached result if there is one.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `resolve_symbol();`.
+  pub fn resolve_symbol(
+    &self,
+    process: &Process,
+    module_name: &str,
+    symbol: &str,
+  ) -> Result<usize> {
+    let module = process.module(module_name)?;
+    let head = module.read_elf_head(process)?;
+    let build_id = match head.build_id() {
+      Some(build_id) => build_id,
+      None => return process.resolve_symbol(module_name, symbol),
+    };
+    let load_bias = module.load_bias_of(&head)?;
+
+    let mut entry = self.load(&build_id)?;
+    let value = match entry.symbols.get(symbol) {
+      Some(value) => *value,
+      None => {
+        let value = module.read_elf()?.find_symbol(symbol).ok_or_else(|| {
+          anyhow!(
+            "Could not find symbol {} in module {}.",
+            symbol,
+            module_name
+          )
+        })?;
+        entry.symbols.insert(symbol.to_string(), value);
+        self.save(&build_id, &entry)?;
+        value
+      }
+    };
+
+    Ok(load_bias.wrapping_add(value))
+  }
+
+  /// Returns addresses of all `signature` matches in module `module_name`  
+  /// of `process`, like `Process::scan_module()`, using cached result if there  
+  /// is one. Cached matches are not verified, so they are valid only for code  
+  /// and constant data, which do not change at runtime.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `scan_module();`.
+  pub fn scan_module(
+    &self,
+    process: &Process,
+    module_name: &str,
+    signature: &Signature,
+  ) -> Result<Vec<usize>> {
+    let module = process.module(module_name)?;
+    let build_id = match module.read_elf_head(process)?.build_id() {
+      Some(build_id) => build_id,
+      None => return process.scan_module(module_name, signature),
+    };
+
+    let key = signature_key(signature);
+    let mut entry = self.load(&build_id)?;
+    if let Some(offsets) = entry.signatures.get(&key) {
+      return Ok(offsets.iter().map(|offset| module.base().wrapping_add(*offset)).collect());
+    }
+
+    let addresses = process.scan_module(module_name, signature)?;
+    entry.signatures.insert(
+      key,
+      addresses.iter().map(|address| address.wrapping_sub(module.base())).collect(),
+    );
+    self.save(&build_id, &entry)?;
+
+    Ok(addresses)
+  }
+
+  /// Removes all cached results.
+  pub fn clear(&self) -> Result<()> {
+    let entries = fs::read_dir(&self.directory).map_err(|error| {
+      anyhow!(
+        "Could not read cache directory {} ({}).",
+        self.directory.display(),
+        error
+      )
+    })?;
+
+    for entry in entries.flatten() {
+      let path = entry.path();
+      if path.extension() == Some(ffi::OsStr::new("json")) {
+        fs::remove_file(&path)
+          .map_err(|error| anyhow!("Could not remove {} ({}).", path.display(), error))?;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn entry_path(&self, build_id: &str) -> path::PathBuf {
+    self.directory.join(format!("{}.json", build_id))
+  }
+
+  /// Loads cached results of module build, empty if there are none yet.
+  fn load(&self, build_id: &str) -> Result<CacheEntry> {
+    let file = match fs::File::open(self.entry_path(build_id)) {
+      Ok(file) => file,
+      Err(ref error) if error.kind() == io::ErrorKind::NotFound => return Ok(CacheEntry::default()),
+      Err(error) => return Err(anyhow!("Could not load cache of {} ({}).", build_id, error)),
+    };
+
+    serde_json::from_reader(io::BufReader::new(file))
+      .map_err(|error| anyhow!("Could not load cache of {} ({}).", build_id, error))
+  }
+
+  fn save(&self, build_id: &str, entry: &CacheEntry) -> Result<()> {
+    let file = fs::File::create(self.entry_path(build_id))
+      .map_err(|error| anyhow!("Could not save cache of {} ({}).", build_id, error))?;
+    serde_json::to_writer(io::BufWriter::new(file), entry)
+      .map_err(|error| anyhow!("Could not save cache of {} ({}).", build_id, error))
+  }
+}
+
+/// Returns IDA-style pattern of `signature`, used as cache key.
+fn signature_key(signature: &Signature) -> String {
+  signature
+    .bytes()
+    .iter()
+    .map(|byte| match byte {
+      Some(byte) => format!("{:02X}", byte),
+      None => "??".to_string(),
+    })
+    .collect::<Vec<String>>()
+    .join(" ")
+}
@@ -0,0 +1,339 @@
+use anyhow::Result;
+use nix::unistd::{sysconf, SysconfVar};
+use std::fs;
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::sync::OnceLock;
+
+use super::error::backend_error;
+use super::{MemoryRegion, Process};
+
+/// Size of single entry of `/proc/\[pid\]/pagemap` file, describing one page.
+const PAGEMAP_ENTRY_SIZE: usize = 8;
+
+/// Bit of pagemap entry set if the page is present in RAM.
+const PAGEMAP_PRESENT: u64 = 1 << 63;
+
+/// Bit of pagemap entry set if the page is swapped out.
+const PAGEMAP_SWAPPED: u64 = 1 << 62;
+
+/// Bit of pagemap entry set if the page was written since soft-dirty bits  
+/// were cleared, see `Process::clear_soft_dirty()`.
+const PAGEMAP_SOFT_DIRTY: u64 = 1 << 55;
+
+/// Value written to `/proc/\[pid\]/clear_refs` file to clear soft-dirty bits.
+const CLEAR_REFS_SOFT_DIRTY: &str = "4";
+
+/// Number of pagemap entries read at once.
+const PAGEMAP_CHUNK_ENTRIES: usize = 0x1000;
+
+impl Process {
+  /// Returns **true** if every page of `length` bytes of memory at `address`  
+  /// is resident in RAM, that is reading it will not force the kernel  
+  /// to swap it in or fault it in from file. Residency is read from  
+  /// `/proc/\[pid\]/pagemap` file, which requires the same permissions  
+  /// as reading memory of the process. Pages which were never touched  
+  /// are not resident either.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   if ctx.is_resident(0x601040, 4)? {
+  ///     println!("value: {}", ctx.read::<u32>(0x601040)?);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn is_resident(&self, address: usize, length: usize) -> Result<bool> {
+    let mut resident = true;
+    self.for_each_page(address, length, |entry| {
+      resident = entry & PAGEMAP_PRESENT != 0;
+      resident
+    })?;
+    Ok(resident)
+  }
+
+  /// Returns ratio (from `0.0` to `1.0`) of pages of `region` which are  
+  /// resident in RAM, see `is_resident()`. Scanners can use it to skip  
+  /// regions which are mostly swapped out, instead of forcing them back in.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for region in ctx.regions()?.readable() {
+  ///     println!("{:#x} resident: {:.0}%", region.start, ctx.region_residency(region)? * 100.0);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x55d0c0ffe000 resident: 75%`
+  pub fn region_residency(&self, region: &MemoryRegion) -> Result<f64> {
+    let mut pages = 0usize;
+    let mut resident_pages = 0usize;
+    self.for_each_page(region.start, region.size(), |entry| {
+      pages += 1;
+      if entry & PAGEMAP_PRESENT != 0 {
+        resident_pages += 1;
+      }
+      true
+    })?;
+
+    if pages == 0 {
+      return Ok(0.0);
+    }
+    Ok(resident_pages as f64 / pages as f64)
+  }
+
+  /// Returns residency of every page of `length` bytes of memory at `address`,  
+  /// **true** for pages resident in RAM (see `is_resident()`), in order  
+  /// of their addresses. The first page is the one containing `address`.
+  pub fn resident_pages(&self, address: usize, length: usize) -> Result<Vec<bool>> {
+    let mut pages = Vec::new();
+    self.for_each_page(address, length, |entry| {
+      pages.push(entry & PAGEMAP_PRESENT != 0);
+      true
+    })?;
+    Ok(pages)
+  }
+
+  /// Clears soft-dirty bits of all pages of the process by writing `4`  
+  /// to `/proc/\[pid\]/clear_refs` file, so `dirty_pages_since_clear()`  
+  /// reports only pages written from now on. Bits are shared by everyone  
+  /// tracking the process, so clearing them affects other tools too.
+  ///
+  /// Requires kernel built with **CONFIG_MEM_SOFT_DIRTY**, otherwise [`Err`]  
+  /// is returned, as the kernel accepts the request, but never marks pages.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///   let heap = ctx.region_find_first_by_name("[heap]", None)?;
+  ///   let (start, end) = (heap.start, heap.end);
+  ///
+  ///   ctx.clear_soft_dirty()?;
+  ///   // ... let the process run for a while ...
+  ///   for page in ctx.dirty_pages_since_clear(start, end - start)? {
+  ///     println!("{:#x} was written", page);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x55d0c1000000 was written`
+  pub fn clear_soft_dirty(&self) -> Result<()> {
+    check_soft_dirty_support()?;
+    self.verify_identity()?;
+
+    fs::write(self.proc_path("clear_refs"), CLEAR_REFS_SOFT_DIRTY).map_err(|error| {
+      backend_error(
+        self.get_pid(),
+        error,
+        String::from("Could not clear soft-dirty bits"),
+      )
+    })
+  }
+
+  /// Returns starting addresses of pages of `length` bytes of memory  
+  /// at `address` which were written since the last `clear_soft_dirty()`  
+  /// (or since they were mapped), sorted by address. Pages which were  
+  /// swapped out keep their soft-dirty bit, so they are reported correctly.
+  ///
+  /// Requires kernel built with **CONFIG_MEM_SOFT_DIRTY**, otherwise [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn dirty_pages_since_clear(&self, address: usize, length: usize) -> Result<Vec<usize>> {
+    check_soft_dirty_support()?;
+    self.pages_matching(address, length, |entry| entry & PAGEMAP_SOFT_DIRTY != 0)
+  }
+
+  /// Returns starting addresses of pages of `length` bytes of memory at `address`  
+  /// which may have changed since the last `clear_soft_dirty()`: soft-dirty pages,  
+  /// and pages which are neither present nor swapped, e.g. unmapped ones.
+  pub(crate) fn possibly_changed_pages(&self, address: usize, length: usize) -> Result<Vec<usize>> {
+    check_soft_dirty_support()?;
+    self.pages_matching(address, length, |entry| {
+      entry & PAGEMAP_SOFT_DIRTY != 0 || entry & (PAGEMAP_PRESENT | PAGEMAP_SWAPPED) == 0
+    })
+  }
+
+  /// Returns starting addresses of pages of `length` bytes of memory at `address`  
+  /// which pagemap entries satisfy `predicate`.
+  fn pages_matching<F: Fn(u64) -> bool>(
+    &self,
+    address: usize,
+    length: usize,
+    predicate: F,
+  ) -> Result<Vec<usize>> {
+    let page_size = page_size();
+    let mut page = address - address % page_size;
+    let mut pages = Vec::new();
+
+    self.for_each_page(address, length, |entry| {
+      if predicate(entry) {
+        pages.push(page);
+      }
+      page += page_size;
+      true
+    })?;
+    Ok(pages)
+  }
+
+  /// Reads pagemap entries of pages of `length` bytes of memory at `address`  
+  /// and calls `callback` with entry of every page, until it returns **false**.  
+  /// Entries of pages past the end of user address space are zero.
+  fn for_each_page<F: FnMut(u64) -> bool>(
+    &self,
+    address: usize,
+    length: usize,
+    mut callback: F,
+  ) -> Result<()> {
+    if length == 0 {
+      return Ok(());
+    }
+    self.verify_identity()?;
+
+    let page_size = page_size();
+    let first_page = address / page_size;
+    let page_count = (address + length - 1) / page_size + 1 - first_page;
+
+    let file = fs::File::open(self.proc_path("pagemap")).map_err(|error| {
+      backend_error(
+        self.get_pid(),
+        error,
+        String::from("Could not open pagemap file"),
+      )
+    })?;
+    let mut buffer = vec![0u8; PAGEMAP_CHUNK_ENTRIES.min(page_count) * PAGEMAP_ENTRY_SIZE];
+    let mut page = 0;
+
+    while page < page_count {
+      let entries = PAGEMAP_CHUNK_ENTRIES.min(page_count - page);
+      let chunk = &mut buffer[..entries * PAGEMAP_ENTRY_SIZE];
+      let offset = ((first_page + page) * PAGEMAP_ENTRY_SIZE) as u64;
+      let bytes_read = read_at_most(&file, chunk, offset).map_err(|error| {
+        backend_error(
+          self.get_pid(),
+          error,
+          format!(
+            "Could not read pagemap file at {:#x}",
+            (first_page + page) * page_size
+          ),
+        )
+      })?;
+
+      for (index, entry) in chunk.chunks_exact(PAGEMAP_ENTRY_SIZE).enumerate() {
+        let mut bytes = [0u8; PAGEMAP_ENTRY_SIZE];
+        bytes.copy_from_slice(entry);
+        let entry = if (index + 1) * PAGEMAP_ENTRY_SIZE <= bytes_read {
+          u64::from_ne_bytes(bytes)
+        } else {
+          0
+        };
+        if !callback(entry) {
+          return Ok(());
+        }
+      }
+      page += entries;
+    }
+
+    Ok(())
+  }
+}
+
+/// Reads from `file` at `offset` until `buffer` is full or end of file  
+/// is reached, and returns number of bytes read.
+fn read_at_most(file: &fs::File, buffer: &mut [u8], offset: u64) -> io::Result<usize> {
+  let mut bytes_read = 0;
+  while bytes_read < buffer.len() {
+    match file.read_at(&mut buffer[bytes_read..], offset + bytes_read as u64) {
+      Ok(0) => break,
+      Ok(count) => bytes_read += count,
+      Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {}
+      Err(error) => return Err(error),
+    }
+  }
+  Ok(bytes_read)
+}
+
+/// Returns [`Err`] if the kernel does not track soft-dirty pages. Checked once,  
+/// by writing to a freshly mapped page of the calling process and looking  
+/// at its soft-dirty bit, as there is no other reliable way to tell.
+///
+/// [`Err`]: https://doc.rust-lang.org/std/result/
+fn check_soft_dirty_support() -> Result<()> {
+  static SUPPORTED: OnceLock<bool> = OnceLock::new();
+
+  if *SUPPORTED.get_or_init(probe_soft_dirty) {
+    Ok(())
+  } else {
+    Err(anyhow!(
+      "Could not track soft-dirty pages (kernel is built without CONFIG_MEM_SOFT_DIRTY)."
+    ))
+  }
+}
+
+/// Returns **true** if page of the calling process written right after  
+/// being mapped is marked as soft-dirty in `/proc/self/pagemap` file.
+fn probe_soft_dirty() -> bool {
+  let page_size = page_size();
+  // New mappings are always soft-dirty when the kernel supports it.
+  let page = unsafe {
+    libc::mmap(
+      std::ptr::null_mut(),
+      page_size,
+      libc::PROT_READ | libc::PROT_WRITE,
+      libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+      -1,
+      0,
+    )
+  };
+  if page == libc::MAP_FAILED {
+    return false;
+  }
+
+  let address = page as usize;
+  unsafe {
+    std::ptr::write_volatile(page as *mut u8, 1);
+  }
+  let mut entry = [0u8; PAGEMAP_ENTRY_SIZE];
+  let supported = fs::File::open("/proc/self/pagemap")
+    .and_then(|file| {
+      file.read_exact_at(
+        &mut entry,
+        (address / page_size * PAGEMAP_ENTRY_SIZE) as u64,
+      )
+    })
+    .is_ok_and(|_| u64::from_ne_bytes(entry) & PAGEMAP_SOFT_DIRTY != 0);
+
+  unsafe {
+    libc::munmap(page, page_size);
+  }
+  supported
+}
+
+/// Returns size of memory page in bytes.
+pub(crate) fn page_size() -> usize {
+  match sysconf(SysconfVar::PAGE_SIZE) {
+    Ok(Some(page_size)) => page_size as usize,
+    _ => 0x1000,
+  }
+}
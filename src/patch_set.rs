@@ -0,0 +1,229 @@
+use anyhow::Result;
+
+use super::{Process, RegionQuery};
+
+/// Machine code of single `nop` instruction.
+#[cfg(target_arch = "x86_64")]
+const NOP_INSTRUCTION: &[u8] = &[0x90];
+#[cfg(target_arch = "aarch64")]
+const NOP_INSTRUCTION: &[u8] = &[0x1f, 0x20, 0x03, 0xd5];
+
+/// Bytes written by `PatchSet`, together with bytes they replaced.
+struct Patch {
+  id: usize,
+  address: usize,
+  original: Vec<u8>,
+}
+
+/// Set of memory patches applied to a process, which remembers original bytes  
+/// of every patch, so patches can be reverted one by one or all at once.  
+/// Patches still applied are reverted when `PatchSet` is dropped, also when  
+/// the program panics, unless `keep()` is called.
+///
+/// Patches are written through `/proc/\[pid\]/mem`, which ignores memory  
+/// protection, so code can be patched without changing protection of its pages.
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{PatchSet, Process};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.parse_maps()?;
+///
+///   let mut patches = PatchSet::new(&ctx);
+///   let health_decrease = patches.nop(0x401a2b, 6)?;
+///   patches.patch(0x401c00, &[0xb0, 0x01, 0xc3])?;
+///
+///   // ... later ...
+///   patches.revert(health_decrease)?;
+///   patches.revert_all()?;
+///   Ok(())
+/// }
+/// ```
+pub struct PatchSet<'a> {
+  process: &'a Process,
+  patches: Vec<Patch>,
+  next_id: usize,
+}
+
+impl<'a> PatchSet<'a> {
+  /// PatchSet object constructor. Creates empty set of patches of `process`.
+  pub fn new(process: &'a Process) -> PatchSet<'a> {
+    PatchSet {
+      process,
+      patches: Vec::new(),
+      next_id: 0,
+    }
+  }
+
+  /// Writes `bytes` at `address` and returns id of the patch,  
+  /// which can be passed to `revert()`.
+  pub fn patch(&mut self, address: usize, bytes: &[u8]) -> Result<usize> {
+    let original = self.process.read_memory_bytes(address, bytes.len())?;
+    self.process.write_memory_forced(address, bytes)?;
+
+    let id = self.next_id;
+    self.next_id += 1;
+    self.patches.push(Patch {
+      id,
+      address,
+      original,
+    });
+    Ok(id)
+  }
+
+  /// Overwrites `length` bytes at `address` with `nop` instructions  
+  /// and returns id of the patch, see `patch()`. On architectures with  
+  /// fixed instruction size, `length` has to be multiple of it.
+  #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+  pub fn nop(&mut self, address: usize, length: usize) -> Result<usize> {
+    if !length.is_multiple_of(NOP_INSTRUCTION.len()) {
+      return Err(anyhow!(
+        "Could not write nop instructions at {:#x} (length has to be multiple of {}).",
+        address,
+        NOP_INSTRUCTION.len()
+      ));
+    }
+
+    let bytes: Vec<u8> = NOP_INSTRUCTION.iter().cycle().take(length).cloned().collect();
+    self.patch(address, &bytes)
+  }
+
+  /// Replaces occurrences of `needle` in writable regions yielded by `regions`  
+  /// query with `replacement`, which has to be as long as `needle`. Every  
+  /// occurrence becomes separate patch, so it is reverted together with  
+  /// the others. At most `limit` occurrences are replaced, all of them if  
+  /// `limit` is [`None`]. Returns addresses of replaced occurrences,  
+  /// see `Process::find_bytes()`.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `replace_bytes();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{PatchSet, Process};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let mut patches = PatchSet::new(&ctx);
+  ///   let replaced = patches.replace_bytes(b"Game Over", b"You Won! ", ctx.regions()?, None)?;
+  ///   println!("replaced {} strings", replaced.len());
+  ///
+  ///   // ... later ...
+  ///   patches.revert_all()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn replace_bytes(
+    &mut self,
+    needle: &[u8],
+    replacement: &[u8],
+    regions: RegionQuery<'_>,
+    limit: Option<usize>,
+  ) -> Result<Vec<usize>> {
+    if needle.len() != replacement.len() {
+      return Err(anyhow!(
+        "Could not replace bytes (replacement has to be as long as needle)."
+      ));
+    }
+
+    let mut replaced: Vec<usize> = Vec::new();
+    for address in self.process.find_bytes(needle, regions.writable())? {
+      if limit.is_some_and(|limit| replaced.len() >= limit) {
+        break;
+      }
+      // Overlapping occurrences would be corrupted by replacing the previous one.
+      if replaced.last().is_some_and(|last| address < last + needle.len()) {
+        continue;
+      }
+
+      self.patch(address, replacement)?;
+      replaced.push(address);
+    }
+
+    Ok(replaced)
+  }
+
+  /// Restores original bytes of patch with `id`. Bytes overwritten again  
+  /// by patches applied later are left as they are, until these are reverted.  
+  /// If there is no patch with `id` (e.g. it was already reverted), [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn revert(&mut self, id: usize) -> Result<()> {
+    let index = self
+      .patches
+      .iter()
+      .position(|patch| patch.id == id)
+      .ok_or_else(|| anyhow!("Could not find patch {}.", id))?;
+
+    // Original bytes under later patches are handed over to the first of them,
+    // so they are restored once all patches covering them are reverted.
+    let mut restored: Vec<Option<u8>> =
+      self.patches[index].original.iter().cloned().map(Some).collect();
+    let (patch, later) = self.patches.split_at_mut(index + 1);
+    let patch = &patch[index];
+    for later in later.iter_mut() {
+      let covered = later.address..later.address + later.original.len();
+      for (offset, byte) in restored.iter_mut().enumerate() {
+        let address = patch.address + offset;
+        if let Some(value) = *byte {
+          if covered.contains(&address) {
+            later.original[address - later.address] = value;
+            *byte = None;
+          }
+        }
+      }
+    }
+
+    for (offset, byte) in restored.iter().enumerate() {
+      if let Some(byte) = byte {
+        self.process.write_memory_forced(patch.address + offset, &[*byte])?;
+      }
+    }
+
+    self.patches.remove(index);
+    Ok(())
+  }
+
+  /// Reverts all patches in reverse order of applying them.
+  pub fn revert_all(&mut self) -> Result<()> {
+    while let Some(patch) = self.patches.last() {
+      self.process.write_memory_forced(patch.address, &patch.original)?;
+      self.patches.pop();
+    }
+    Ok(())
+  }
+
+  /// Returns ids of patches which are applied, in order of applying them.
+  pub fn ids(&self) -> Vec<usize> {
+    self.patches.iter().map(|patch| patch.id).collect()
+  }
+
+  /// Returns number of patches which are applied.
+  pub fn len(&self) -> usize {
+    self.patches.len()
+  }
+
+  /// Returns **true** if no patch is applied.
+  pub fn is_empty(&self) -> bool {
+    self.patches.is_empty()
+  }
+
+  /// Leaves all patches applied, so they are not reverted when the set is dropped.
+  pub fn keep(mut self) {
+    self.patches.clear();
+  }
+}
+
+impl<'a> Drop for PatchSet<'a> {
+  fn drop(&mut self) {
+    let _ = self.revert_all();
+  }
+}
@@ -0,0 +1,312 @@
+use anyhow::Result;
+use nix::unistd::{getpid, getuid, Pid};
+use std::fmt;
+use std::fs;
+use std::path;
+
+use super::stat::read_stat;
+use super::{Process, RegionKind};
+
+/// Bit of **CAP_SYS_PTRACE** capability in `CapEff` field of `/proc/\[pid\]/status` file.
+const CAP_SYS_PTRACE: u64 = 1 << 19;
+
+/// Reason why access to memory of the process was denied, diagnosed  
+/// when system call fails with **EPERM** or **EACCES** (see `ProcessError::PermissionDenied`).  
+/// It is displayed together with a way to fix it.
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{PermissionDenialReason, Process, ProcessError};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let ctx = Process::new("current_process_name")?;
+///
+///   if let Err(error) = ctx.read::<u32>(0x601040) {
+///     match error.downcast_ref::<ProcessError>() {
+///       Some(ProcessError::PermissionDenied {
+///         reason: PermissionDenialReason::YamaDescendantsOnly,
+///         ..
+///       }) => println!("restart the target from this tool"),
+///       _ => return Err(error.into()),
+///     }
+///   }
+///   Ok(())
+/// }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum PermissionDenialReason {
+  /// Yama `ptrace_scope` is 3, access to other processes is disabled until reboot.
+  YamaDisabled,
+  /// Yama `ptrace_scope` is 2, only callers with **CAP_SYS_PTRACE** can access other processes.
+  YamaAdminOnly,
+  /// Yama `ptrace_scope` is 1, only descendants of the caller can be accessed  
+  /// (unless the process allowed it with **PR_SET_PTRACER**).
+  YamaDescendantsOnly,
+  /// Process runs as other user than the caller.
+  DifferentUser { caller_uid: u32, target_uid: u32 },
+  /// Process is already traced by process with this id.
+  AlreadyTraced(Pid),
+  /// None of the above applies, e.g. the process is not dumpable  
+  /// or access was denied by security module like SELinux.
+  Unknown,
+}
+
+impl fmt::Display for PermissionDenialReason {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PermissionDenialReason::YamaDisabled => write!(
+        formatter,
+        "Yama ptrace_scope is 3, which disables access to other processes until reboot; \
+         boot with kernel.yama.ptrace_scope set to lower value"
+      ),
+      PermissionDenialReason::YamaAdminOnly => write!(
+        formatter,
+        "Yama ptrace_scope is 2, which allows access only with CAP_SYS_PTRACE; \
+         run as root or grant the capability with `setcap cap_sys_ptrace=eip <binary>`"
+      ),
+      PermissionDenialReason::YamaDescendantsOnly => write!(
+        formatter,
+        "Yama ptrace_scope is 1, which allows access only to descendants of the caller; \
+         run as root, grant CAP_SYS_PTRACE with `setcap cap_sys_ptrace=eip <binary>`, \
+         start the target from this process or set kernel.yama.ptrace_scope to 0"
+      ),
+      PermissionDenialReason::DifferentUser {
+        caller_uid,
+        target_uid,
+      } => write!(
+        formatter,
+        "process runs as user {} while caller runs as user {}; run as the same user or as root",
+        target_uid, caller_uid
+      ),
+      PermissionDenialReason::AlreadyTraced(tracer) => write!(
+        formatter,
+        "process is already traced by process {}; detach it (e.g. close the debugger) first",
+        tracer
+      ),
+      PermissionDenialReason::Unknown => write!(
+        formatter,
+        "process may be not dumpable or access was denied by security module; run as root, \
+         or if the process is attached already, use /proc/[pid]/mem or ptrace memory backend"
+      ),
+    }
+  }
+}
+
+/// Result of pre-flight check of access to the process, see `Process::check_access()`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct AccessReport {
+  /// Value of Yama `ptrace_scope`, or [`None`] if Yama is not enabled.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  pub ptrace_scope: Option<u32>,
+  /// Whether the caller has **CAP_SYS_PTRACE** capability.
+  pub cap_sys_ptrace: bool,
+  /// Whether real, effective and saved user ids of the process match the caller's one.
+  pub same_user: bool,
+  /// Id of process tracing the process, if any.
+  pub tracer: Option<Pid>,
+  /// Whether attaching to the process (see `Process::attach()`) is expected  
+  /// to be allowed. It is predicted from the fields above, as attaching  
+  /// to check it would have side effects.
+  pub ptrace_allowed: bool,
+  /// Whether `/proc/\[pid\]/maps` file could be read.
+  pub maps_readable: bool,
+  /// Whether memory of the process could be read with its memory backend.
+  pub memory_readable: bool,
+  /// Whether `/proc/\[pid\]/mem` file could be opened.
+  pub proc_mem_accessible: bool,
+  /// Why access to memory is denied, if it is.
+  pub denial: Option<PermissionDenialReason>,
+}
+
+impl AccessReport {
+  /// Returns **true** if both memory regions and memory of the process can be read.
+  pub fn is_accessible(&self) -> bool {
+    self.maps_readable && self.memory_readable
+  }
+}
+
+impl Process {
+  /// Checks whether memory of the process can be accessed, without side effects:  
+  /// inspects Yama `ptrace_scope`, capabilities and users of the caller and  
+  /// the process, reads `/proc/\[pid\]/maps` file and single byte of memory,  
+  /// and opens `/proc/\[pid\]/mem` file. Nothing is written and the process  
+  /// is not attached to. If the process is backed by captured memory  
+  /// (see `DumpProcess`), [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let report = ctx.check_access()?;
+  ///   match report.denial {
+  ///     None => println!("ready to scan"),
+  ///     Some(reason) => println!("can not scan: {}", reason),
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `can not scan: Yama ptrace_scope is 1, which allows access only to descendants of the caller; ...`
+  pub fn check_access(&self) -> Result<AccessReport> {
+    self.verify_identity()?;
+    let pid = self.get_pid();
+
+    let ptrace_scope = ptrace_scope();
+    let cap_sys_ptrace = has_cap_sys_ptrace();
+    let same_user = different_uid(pid).is_none();
+    let tracer = tracer(pid);
+    let scope_allows = match ptrace_scope.unwrap_or(0) {
+      0 => true,
+      1 => is_descendant(pid),
+      _ => false,
+    };
+    let ptrace_allowed = ptrace_scope.unwrap_or(0) < 3
+      && tracer.is_none()
+      && (cap_sys_ptrace || (same_user && scope_allows));
+
+    // Special regions like [vvar] are readable according to maps,
+    // but can not be accessed by other processes.
+    let mut maps_readable = false;
+    let mut readable_address = None;
+    if let Ok(maps) = self.iter_maps() {
+      for region in maps {
+        let region = match region {
+          Ok(region) => region,
+          Err(_) => break,
+        };
+        maps_readable = true;
+        let special = matches!(region.kind(), RegionKind::Vdso | RegionKind::Vvar);
+        if region.permissions.readable && !special {
+          readable_address = Some(region.start);
+          break;
+        }
+      }
+    }
+
+    let memory_readable = readable_address
+      .is_some_and(|address| self.read_memory_into(address, &mut [0u8; 1]).ok() == Some(1));
+    let proc_mem_accessible = fs::File::open(self.proc_path("mem")).is_ok();
+
+    let denial = if maps_readable && memory_readable {
+      None
+    } else {
+      Some(diagnose(pid))
+    };
+
+    Ok(AccessReport {
+      ptrace_scope,
+      cap_sys_ptrace,
+      same_user,
+      tracer,
+      ptrace_allowed,
+      maps_readable,
+      memory_readable,
+      proc_mem_accessible,
+      denial,
+    })
+  }
+}
+
+/// Diagnoses why access to process `pid` was denied, by inspecting Yama  
+/// `ptrace_scope`, capabilities and user of the caller and the process.
+pub(crate) fn diagnose(pid: Pid) -> PermissionDenialReason {
+  let ptrace_scope = ptrace_scope().unwrap_or(0);
+  if ptrace_scope >= 3 {
+    return PermissionDenialReason::YamaDisabled;
+  }
+
+  if !has_cap_sys_ptrace() {
+    if ptrace_scope == 2 {
+      return PermissionDenialReason::YamaAdminOnly;
+    }
+    if let Some((caller_uid, target_uid)) = different_uid(pid) {
+      return PermissionDenialReason::DifferentUser {
+        caller_uid,
+        target_uid,
+      };
+    }
+    if ptrace_scope == 1 && !is_descendant(pid) {
+      return PermissionDenialReason::YamaDescendantsOnly;
+    }
+  }
+
+  match tracer(pid) {
+    Some(tracer) => PermissionDenialReason::AlreadyTraced(tracer),
+    None => PermissionDenialReason::Unknown,
+  }
+}
+
+/// Returns value of Yama `ptrace_scope`, or [`None`] if Yama is not enabled.
+///
+/// [`None`]: https://doc.rust-lang.org/std/option/
+fn ptrace_scope() -> Option<u32> {
+  fs::read_to_string("/proc/sys/kernel/yama/ptrace_scope")
+    .ok()
+    .and_then(|scope| scope.trim().parse::<u32>().ok())
+}
+
+/// Returns **true** if the caller has **CAP_SYS_PTRACE** in its effective capabilities.
+fn has_cap_sys_ptrace() -> bool {
+  fs::read_to_string("/proc/self/status")
+    .ok()
+    .and_then(|status| {
+      status_field(&status, "CapEff:")
+        .and_then(|capabilities| u64::from_str_radix(capabilities, 16).ok())
+    })
+    .is_some_and(|capabilities| capabilities & CAP_SYS_PTRACE != 0)
+}
+
+/// Returns real user id of the caller and user id of process `pid` if they differ.  
+/// Caller's real user id has to match real, effective and saved user id of the process.
+fn different_uid(pid: Pid) -> Option<(u32, u32)> {
+  let caller_uid = getuid().as_raw();
+  let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+  status_field(&status, "Uid:")?
+    .split_whitespace()
+    .take(3)
+    .filter_map(|uid| uid.parse::<u32>().ok())
+    .find(|uid| *uid != caller_uid)
+    .map(|target_uid| (caller_uid, target_uid))
+}
+
+/// Returns id of process tracing process `pid`, unless it is the caller.
+fn tracer(pid: Pid) -> Option<Pid> {
+  let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+  let tracer = status_field(&status, "TracerPid:")?.parse::<i32>().ok()?;
+  if tracer == 0 || tracer == getpid().as_raw() {
+    return None;
+  }
+  Some(Pid::from_raw(tracer))
+}
+
+/// Returns trimmed value of field `name` of `/proc/\[pid\]/status` file content.
+fn status_field<'a>(status: &'a str, name: &str) -> Option<&'a str> {
+  status.lines().find_map(|line| line.strip_prefix(name)).map(str::trim)
+}
+
+/// Returns **true** if process `pid` is descendant of the calling process,  
+/// following parent process ids up to `init`.
+fn is_descendant(pid: Pid) -> bool {
+  let caller = getpid();
+  let mut current = pid;
+
+  while current.as_raw() > 1 {
+    let stat_path = path::Path::new("/proc/").join(current.to_string()).join("stat");
+    current = match read_stat(&stat_path) {
+      Ok(stat) => stat.ppid,
+      Err(_) => return false,
+    };
+    if current == caller {
+      return true;
+    }
+  }
+  false
+}
@@ -0,0 +1,312 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+
+use super::{MemoryRegion, Process};
+
+/// Options limiting pointer path search done by `PointerMap::find_paths()`.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct PointerScanOptions {
+  /// Maximum number of pointers dereferenced by single path.
+  pub max_depth: usize,
+  /// Maximum offset added to dereferenced pointer at every level.
+  pub max_offset: usize,
+  /// Search stops after this many paths were found.
+  pub max_results: usize,
+}
+
+impl Default for PointerScanOptions {
+  fn default() -> PointerScanOptions {
+    PointerScanOptions {
+      max_depth: 4,
+      max_offset: 0x1000,
+      max_results: 10000,
+    }
+  }
+}
+
+/// Pointer path leading from static address inside a module (mapped file)  
+/// to some target address. Since it is relative to module base address,  
+/// it stays valid after target process restarts, even with ASLR enabled.
+///
+/// `offsets` follow the same convention as `Process::resolve_pointer_chain()`,  
+/// with module base address as `base`, so the first offset is the offset  
+/// of static pointer from module base address.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct PointerPath {
+  /// File name of the module containing static pointer.
+  pub module: String,
+  pub offsets: Vec<usize>,
+}
+
+impl PointerPath {
+  /// Returns address this path currently leads to in `process`.  
+  /// Module base address is the start of the first mapping of the module.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `resolve();`.
+  pub fn resolve(&self, process: &Process) -> Result<usize> {
+    let module_base = process.region_find_first_by_name(&self.module, None)?.start;
+    process.resolve_pointer_chain(module_base, &self.offsets)
+  }
+}
+
+impl fmt::Display for PointerPath {
+  /// Formats path like `"libfoo.so"+0x1234 -> 0x10 -> 0x8`.
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(formatter, "\"{}\"", self.module)?;
+    for (level, offset) in self.offsets.iter().enumerate() {
+      if level == 0 {
+        write!(formatter, "+{:#x}", offset)?;
+      } else {
+        write!(formatter, " -> {:#x}", offset)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Static memory range, that is writeable mapping of a file  
+/// or anonymous mapping following it (.bss section).
+#[derive(Clone, Debug)]
+struct StaticRange {
+  start: usize,
+  end: usize,
+  module: String,
+  module_base: usize,
+}
+
+/// Map of every pointer-sized, aligned value stored in readable and writeable  
+/// memory of a process which points into any of its readable regions.  
+/// It is a snapshot, so it has to be built again after target memory changes.
+///
+/// Pointer map is used to find pointer paths from static addresses  
+/// inside modules to some target address (see `PointerMap::find_paths()`).
+#[derive(Clone, Debug)]
+pub struct PointerMap {
+  /// `(value, address)` pairs sorted by value, so pointers pointing  
+  /// to some range of addresses can be found with binary search.
+  pointers: Vec<(usize, usize)>,
+  /// Static ranges sorted by start address.
+  static_ranges: Vec<StaticRange>,
+}
+
+impl PointerMap {
+  /// PointerMap object constructor. Reads all readable and writeable memory  
+  /// regions of `process` and records every aligned pointer-sized value  
+  /// pointing into any readable region.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `PointerMap::new();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{PointerMap, PointerScanOptions, Process};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let pointer_map = PointerMap::new(&ctx)?;
+  ///   for path in pointer_map.find_paths(0x55d0c0ffee00, &PointerScanOptions::default()) {
+  ///     println!("{}", path);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `"game"+0x2f1a8 -> 0x10 -> 0x38`
+  pub fn new(process: &Process) -> Result<PointerMap> {
+    let regions = process.get_memory_regions()?;
+    let pointer_size = mem::size_of::<usize>();
+
+    let readable_ranges: Vec<(usize, usize)> = regions
+      .iter()
+      .filter(|region| region.permissions.readable)
+      .map(|region| (region.start, region.end))
+      .collect();
+
+    let mut module_bases: HashMap<&str, usize> = HashMap::new();
+    for region in regions {
+      if let Some(path) = &region.path {
+        if path.starts_with('/') {
+          let module_base = module_bases.entry(region.file_name()).or_insert(region.start);
+          *module_base = (*module_base).min(region.start);
+        }
+      }
+    }
+
+    let mut pointers: Vec<(usize, usize)> = Vec::new();
+    let mut static_ranges: Vec<StaticRange> = Vec::new();
+
+    let mut previous: Option<&MemoryRegion> = None;
+    for region in regions {
+      // Anonymous region directly following mapping of a module is its .bss section.
+      let module = match previous.replace(region) {
+        Some(previous) if region.path.is_none() && previous.end == region.start => {
+          previous.file_name()
+        }
+        _ => region.file_name(),
+      };
+
+      if !region.permissions.readable || !region.permissions.writeable {
+        continue;
+      }
+
+      if let Some(module_base) = module_bases.get(module) {
+        static_ranges.push(StaticRange {
+          start: region.start,
+          end: region.end,
+          module: module.to_string(),
+          module_base: *module_base,
+        });
+      }
+
+      // Memory which could not be read is skipped page by page, so chunks
+      // always start at addresses aligned to pointer size.
+      process.walk_chunks(region.start, region.end, 0, |chunk_start, chunk| {
+        for (index, value_bytes) in chunk.chunks_exact(pointer_size).enumerate() {
+          let value: usize = bytemuck::pod_read_unaligned(value_bytes);
+          if points_into(&readable_ranges, value) {
+            pointers.push((value, chunk_start + index * pointer_size));
+          }
+        }
+        true
+      })?;
+    }
+
+    pointers.sort_unstable();
+
+    Ok(PointerMap {
+      pointers,
+      static_ranges,
+    })
+  }
+
+  /// Returns number of pointers in the map.
+  pub fn len(&self) -> usize {
+    self.pointers.len()
+  }
+
+  /// Returns **true** if there are no pointers in the map.
+  pub fn is_empty(&self) -> bool {
+    self.pointers.is_empty()
+  }
+
+  /// Returns addresses of all pointers which value is in range  
+  /// `[target - max_offset, target]`, together with offsets that  
+  /// have to be added to their values to reach `target`.
+  pub fn pointers_to(&self, target: usize, max_offset: usize) -> Vec<(usize, usize)> {
+    let lowest_value = target.saturating_sub(max_offset);
+    let first = self.pointers.partition_point(|(value, _)| *value < lowest_value);
+
+    self.pointers[first..]
+      .iter()
+      .take_while(|(value, _)| *value <= target)
+      .map(|(value, address)| (*address, target - value))
+      .collect()
+  }
+
+  /// Returns pointer paths leading from static addresses inside modules  
+  /// to `target` address, limited by `options`. Paths are searched  
+  /// backwards, starting at `target` and going through pointers  
+  /// pointing at most `max_offset` bytes before the current address.
+  pub fn find_paths(&self, target: usize, options: &PointerScanOptions) -> Vec<PointerPath> {
+    let mut paths: Vec<PointerPath> = Vec::new();
+    let mut offsets: Vec<usize> = Vec::new();
+    let mut visited: Vec<usize> = Vec::new();
+    let mut dead_ends: HashMap<usize, usize> = HashMap::new();
+
+    self.find_paths_from(
+      target,
+      options,
+      &mut offsets,
+      &mut visited,
+      &mut dead_ends,
+      &mut paths,
+    );
+    paths
+  }
+
+  /// Walks pointer map backwards from `address`. `offsets` contains offsets  
+  /// of the path walked so far, in reversed order. `dead_ends` maps addresses  
+  /// from which no path was found to the depth they were searched to, so that  
+  /// addresses reachable through many pointers are not searched again.  
+  /// Returns **false** if addresses of the path walked so far were skipped,  
+  /// so that result depends on the path and can not be remembered.
+  fn find_paths_from(
+    &self,
+    address: usize,
+    options: &PointerScanOptions,
+    offsets: &mut Vec<usize>,
+    visited: &mut Vec<usize>,
+    dead_ends: &mut HashMap<usize, usize>,
+    paths: &mut Vec<PointerPath>,
+  ) -> bool {
+    if offsets.len() >= options.max_depth {
+      return true;
+    }
+    let remaining_depth = options.max_depth - offsets.len();
+    if dead_ends.get(&address).is_some_and(|depth| *depth >= remaining_depth) {
+      return true;
+    }
+
+    let paths_found = paths.len();
+    let mut independent = true;
+    visited.push(address);
+
+    for (pointer_address, offset) in self.pointers_to(address, options.max_offset) {
+      if paths.len() >= options.max_results {
+        break;
+      }
+
+      if visited.contains(&pointer_address) {
+        independent = false;
+        continue;
+      }
+
+      offsets.push(offset);
+
+      if let Some(static_range) = self.static_range(pointer_address) {
+        let mut path_offsets = vec![pointer_address - static_range.module_base];
+        path_offsets.extend(offsets.iter().rev());
+        paths.push(PointerPath {
+          module: static_range.module.clone(),
+          offsets: path_offsets,
+        });
+      } else {
+        independent &=
+          self.find_paths_from(pointer_address, options, offsets, visited, dead_ends, paths);
+      }
+
+      offsets.pop();
+    }
+
+    visited.pop();
+    if independent && paths.len() == paths_found {
+      dead_ends.insert(address, remaining_depth);
+    }
+    independent
+  }
+
+  /// Returns static range containing `address`.
+  fn static_range(&self, address: usize) -> Option<&StaticRange> {
+    let index = self.static_ranges.partition_point(|range| range.start <= address);
+    match index {
+      0 => None,
+      _ => Some(&self.static_ranges[index - 1]).filter(|range| address < range.end),
+    }
+  }
+}
+
+/// Returns **true** if `value` is located in any of sorted `ranges`.
+fn points_into(ranges: &[(usize, usize)], value: usize) -> bool {
+  let index = ranges.partition_point(|(start, _)| *start <= value);
+  index > 0 && value < ranges[index - 1].1
+}
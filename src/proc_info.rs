@@ -0,0 +1,175 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path;
+
+use super::Process;
+
+impl Process {
+  /// Returns path of `file_name` file inside `/proc/\[pid\]/` directory.
+  pub(crate) fn proc_path(&self, file_name: &str) -> path::PathBuf {
+    path::Path::new("/proc/").join(self.get_pid().to_string()).join(file_name)
+  }
+
+  /// Reads `/proc/\[pid\]/cmdline` file and returns command line arguments  
+  /// of the process, the first one usually being program name.  
+  /// Arguments in the file are separated with NUL bytes,  
+  /// invalid UTF-8 sequences are replaced with `U+FFFD`.
+  ///
+  /// **NOTE**: Zombie processes and kernel threads have empty command line.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   for ctx in Process::find_by_regex("^worker$")? {
+  ///     println!("{}: {:?}", ctx.get_pid(), ctx.cmdline()?);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `2686: ["worker", "--queue", "images"]`
+  pub fn cmdline(&self) -> Result<Vec<String>> {
+    let bytes = fs::read(self.proc_path("cmdline")).map_err(|error| {
+      anyhow!(
+        "Could not read command line of process {} ({}).",
+        self.get_pid(),
+        error
+      )
+    })?;
+
+    Ok(split_nul_separated(&bytes))
+  }
+
+  /// Reads `/proc/\[pid\]/environ` file and returns environment variables  
+  /// the process was started with. Changes made later by the process itself  
+  /// (e.g. with **setenv(3)**) are usually not visible there.  
+  /// Entries without `=` character are skipped.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   for (name, value) in ctx.environ()? {
+  ///     println!("{}={}", name, value);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn environ(&self) -> Result<HashMap<String, String>> {
+    let bytes = fs::read(self.proc_path("environ")).map_err(|error| {
+      anyhow!(
+        "Could not read environment of process {} ({}).",
+        self.get_pid(),
+        error
+      )
+    })?;
+
+    Ok(
+      split_nul_separated(&bytes)
+        .into_iter()
+        .filter_map(|entry| {
+          let separator = entry.find('=')?;
+          Some((
+            entry[..separator].to_string(),
+            entry[separator + 1..].to_string(),
+          ))
+        })
+        .collect(),
+    )
+  }
+
+  /// Returns value of environment variable `name` of the process  
+  /// (see `environ()`), or [`None`] if it is not set.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///   println!("target display: {:?}", ctx.env_var("DISPLAY")?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `target display: Some(":0")`
+  pub fn env_var(&self, name: &str) -> Result<Option<String>> {
+    Ok(self.environ()?.remove(name))
+  }
+
+  /// Returns path of the executable file of the process, resolved from  
+  /// `/proc/\[pid\]/exe` symbolic link. If executable was deleted or replaced  
+  /// after the process started, kernel appends ` (deleted)` to the path.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///   println!("executable: {}", ctx.exe_path()?.display());
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `executable: /usr/bin/current_process_name`
+  pub fn exe_path(&self) -> Result<path::PathBuf> {
+    self.read_proc_link("exe")
+  }
+
+  /// Returns current working directory of the process, resolved  
+  /// from `/proc/\[pid\]/cwd` symbolic link.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///   println!("working directory: {}", ctx.cwd()?.display());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn cwd(&self) -> Result<path::PathBuf> {
+    self.read_proc_link("cwd")
+  }
+
+  /// Returns target of `link_name` symbolic link inside `/proc/\[pid\]/` directory.  
+  /// Reading these links requires the same permissions as reading process memory.
+  fn read_proc_link(&self, link_name: &str) -> Result<path::PathBuf> {
+    fs::read_link(self.proc_path(link_name)).map_err(|error| {
+      anyhow!(
+        "Could not resolve {} link of process {} ({}).",
+        link_name,
+        self.get_pid(),
+        error
+      )
+    })
+  }
+}
+
+/// Splits NUL separated (and possibly NUL terminated) `bytes` into strings.
+pub(crate) fn split_nul_separated(bytes: &[u8]) -> Vec<String> {
+  let bytes = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+  if bytes.is_empty() {
+    return Vec::new();
+  }
+
+  bytes
+    .split(|byte| *byte == 0)
+    .map(|argument| String::from_utf8_lossy(argument).into_owned())
+    .collect()
+}
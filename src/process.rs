@@ -1,12 +1,47 @@
 use anyhow::Result;
-use nix::sys::uio::{process_vm_readv, process_vm_writev, IoVec, RemoteIoVec};
+use bytemuck::Pod;
 use nix::unistd::Pid;
+use regex::Regex;
 use std::fs;
-use std::io::{self, BufRead};
+use std::io;
 use std::mem;
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
 use std::path;
+use std::sync::Arc;
+use std::thread;
+use std::time;
 
-use super::{MemoryRegion, RegionPermissions};
+use super::chunked_read::{HolePolicy, DEFAULT_READ_CHUNK_SIZE};
+use super::dump_process::DumpMemory;
+use super::memory_backend::{MemoryBackend, ProcessVmBackend};
+use super::memory_region::RegionNamePattern;
+use super::error::backend_error;
+use super::stat::read_stat;
+use super::{MemoryRegion, ProcessError, ProcessState, RegionPermissions};
+
+/// Size of chunks used when reading data of unknown length (e.g. strings).  
+/// Chunks are aligned to it, so they never cross page boundaries.
+const CHUNK_SIZE: usize = 0x1000;
+
+/// Fails to compile if `Process` stops being shareable between threads.
+const _: fn() = || {
+  fn assert_send_sync<T: Send + Sync>() {}
+  assert_send_sync::<Process>();
+};
+
+/// Interval between checks of `/proc/` directory in `Process::wait_for`.
+const WAIT_FOR_INTERVAL: time::Duration = time::Duration::from_millis(100);
+
+/// Describes how command line passed to `Process::new_by_cmdline`  
+/// is compared with one located in `/proc/\[pid\]/cmdline` file.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum CmdlineMatch {
+  /// Whole command line has to be equal to the provided one.
+  Exact,
+  /// Command line has to contain the provided one.
+  Contains,
+}
 
 // TODO: Document rest of fields
 /// Process is an object implementation of existing   
@@ -16,12 +51,64 @@ use super::{MemoryRegion, RegionPermissions};
 /// if memory regions were not mapped. (`parse_maps()` was not called).
 ///   
 /// [`None`]: https://doc.rust-lang.org/std/option/
+///
+/// Process is `Send + Sync` and cheap to clone, as parsed memory regions  
+/// are shared between clones, so one handle can be cloned into every  
+/// worker thread instead of wrapping it in `Arc<Mutex<Process>>`. Reads  
+/// and writes take `&self` and run concurrently. Calling `parse_maps()`  
+/// on a clone replaces its regions only, other clones keep the old ones.
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use std::thread;
+/// use trickster::Process;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.parse_maps()?;
+///
+///   let workers: Vec<_> = (0..4)
+///     .map(|index| {
+///       let ctx = ctx.clone();
+///       thread::spawn(move || ctx.read::<u32>(0x601040 + index * 4))
+///     })
+///     .collect();
+///   for worker in workers {
+///     println!("value: {}", worker.join().unwrap()?);
+///   }
+///   Ok(())
+/// }
+/// ```
+#[derive(Clone)]
 pub struct Process {
   /// A Pid (i.e., process identification number) is an auto   
   /// generated identification number for each process.
   pid: Pid,
   name: String,
-  memory_regions: Option<Vec<MemoryRegion>>,
+  /// Parsed memory regions, shared between clones of the process.
+  memory_regions: Option<Arc<Vec<MemoryRegion>>>,
+  /// Start time of the process (see `ProcessStat::start_time`), read  
+  /// at construction. Together with `pid` it identifies the process,  
+  /// since pids are reused after processes exit.
+  start_time: u64,
+  /// Pidfd of the process (see **pidfd_open(2)**), which becomes readable  
+  /// once the process exits, so its identity is verified without reading  
+  /// `/proc/\[pid\]/stat` file. [`None`] on kernels older than 5.3.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  pidfd: Option<Arc<OwnedFd>>,
+  /// Whether identity of the process is verified before every read and write.
+  pid_reuse_check: bool,
+  /// Whether written memory is read back and compared after every write.
+  write_verification: bool,
+  /// Size of chunks large reads are split into, see `set_read_chunk_size()`.
+  read_chunk_size: usize,
+  /// Whether the process is captured memory (see `DumpProcess`) read  
+  /// through its memory backend, instead of live process.
+  offline: bool,
+  /// Method of accessing memory of the process, see `set_memory_backend()`.
+  memory_backend: Arc<dyn MemoryBackend>,
 }
 
 impl Process {
@@ -33,157 +120,1112 @@ impl Process {
   /// If you want to do so, use `parse_maps()`.
   ///
   /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///   println!("example process id: {}", ctx.get_pid());
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `example process id: 2686`
+  ///
+  /// # Note about /proc/\[pid\]/comm
+  /// This file exposes the process's comm value — that is, the com‐  
+  /// mand name associated with the process.  Different threads in  
+  /// the same process may have different comm values, accessible  
+  /// via `/proc/[pid]/task/\[tid\]/comm`.  A thread may modify its comm  
+  /// value, or that of any of other thread in the same thread group  
+  /// (see the discussion of **CLONE_THREAD** in
+  /// [**clone(2)**](http://man7.org/linux/man-pages/man2/clone.2.html)), by writing  
+  /// to the file `/proc/self/task/\[tid\]/comm`. Strings longer than  
+  /// **TASK_COMM_LEN** (16) characters are silently truncated.  
+  ///
+  /// This file provides a superset of the
+  /// [**prctl(2)**](http://man7.org/linux/man-pages/man2/prctl.2.html) **PR_SET_NAME** and  
+  /// **PR_GET_NAME** operations, and is employed by  
+  /// [**pthread_setname_np(3)**](http://man7.org/linux/man-pages/man3/pthread_setname_np.3.html) when used to rename  
+  /// threads other than the caller.  
+  pub fn new(process_name: &str) -> Result<Process> {
+    let process_list = fs::read_dir("/proc/")?;
+
+    for process in process_list.filter_map(|process| process.ok()) {
+      let filename_string = process
+        .file_name()
+        .into_string()
+        .map_err(|_| anyhow!("Could not convert file_name() of process directory to String."))?;
+
+      if !filename_string.chars().all(char::is_numeric) {
+        continue;
+      }
+
+      let comm_path = process.path().join("comm");
+      let true_name = fs::read_to_string(comm_path)?;
+
+      if true_name.trim_end() == process_name {
+        let pid = Pid::from_raw(
+          filename_string
+            .parse::<i32>()
+            .expect("Could not parse i32 value from filename_string."),
+        );
+        return Process::with_pid_and_name(pid, true_name.trim_end().to_string());
+      }
+    }
+
+    Err(anyhow!("Could not get process id of {}.", process_name))
+  }
+
+  /// Process object constructor. Waits until process with name `process_name`  
+  /// exists and builds process object for it (see `Process::new`). `/proc/`  
+  /// directory is checked every 100 milliseconds, use `Process::wait_for_with_interval`  
+  /// to change it. If process does not appear within `timeout`, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **WARNING**: This method __does not__ initialize `memory_regions` field.  
+  /// If you want to do so, use `parse_maps()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use std::time::Duration;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   println!("waiting for the game to start...");
+  ///   let ctx = Process::wait_for("current_process_name", Duration::from_secs(60))?;
+  ///   println!("example process id: {}", ctx.get_pid());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn wait_for(process_name: &str, timeout: time::Duration) -> Result<Process> {
+    Process::wait_for_with_interval(process_name, timeout, WAIT_FOR_INTERVAL)
+  }
+
+  /// Process object constructor. Works like `Process::wait_for`, but checks  
+  /// `/proc/` directory every `interval` instead of every 100 milliseconds.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use std::time::Duration;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::wait_for_with_interval(
+  ///     "current_process_name",
+  ///     Duration::from_secs(60),
+  ///     Duration::from_millis(10),
+  ///   )?;
+  ///   println!("example process id: {}", ctx.get_pid());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn wait_for_with_interval(
+    process_name: &str,
+    timeout: time::Duration,
+    interval: time::Duration,
+  ) -> Result<Process> {
+    let start = time::Instant::now();
+
+    loop {
+      // Errors are expected too, e.g. when process exits while `/proc/` is listed.
+      if let Ok(process) = Process::new(process_name) {
+        return Ok(process);
+      }
+
+      let elapsed = start.elapsed();
+      if elapsed >= timeout {
+        return Err(anyhow!(
+          "Could not get process id of {} within {:?}.",
+          process_name,
+          timeout
+        ));
+      }
+      thread::sleep(interval.min(timeout - elapsed));
+    }
+  }
+
+  /// Process object constructor. Builds process object directly from  
+  /// already known process id, reading its name from `/proc/\[pid\]/comm` file.  
+  /// If `/proc/\[pid\]/` directory does not exist, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **WARNING**: This method __does not__ initialize `memory_regions` field.  
+  /// If you want to do so, use `parse_maps()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Pid, Process};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::from_pid(Pid::from_raw(2686))?;
+  ///   println!("example process name: {}", ctx.get_name());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn from_pid(pid: Pid) -> Result<Process> {
+    let process_path = path::Path::new("/proc/").join(pid.to_string());
+
+    if !process_path.is_dir() {
+      return Err(anyhow!("Process with id {} does not exist.", pid));
+    }
+
+    let true_name = fs::read_to_string(process_path.join("comm"))
+      .map_err(|error| anyhow!("Could not read name of process {} ({}).", pid, error))?;
+
+    Process::with_pid_and_name(pid, true_name.trim_end().to_string())
+  }
+
+  /// Process object constructor. Finds process id by command line by iterating  
+  /// over numeric directories in `/proc/` and comparing `cmdline` provided  
+  /// in method parameter with one located in `/proc/\[pid\]/cmdline` file.  
+  /// Arguments in `/proc/\[pid\]/cmdline` are separated with NUL bytes,  
+  /// before comparison they are joined with single spaces.
+  ///
+  /// Unlike `/proc/\[pid\]/comm` (see `Process::new`), command line is not  
+  /// truncated to **TASK_COMM_LEN** (16) characters, so this is the way  
+  /// to go when looking for processes with long binary names.
+  ///
+  /// `match_mode` decides whether whole command line has to be equal  
+  /// to `cmdline` ([`CmdlineMatch::Exact`]) or only contain it ([`CmdlineMatch::Contains`]).
+  ///
+  /// [`CmdlineMatch::Exact`]: enum.CmdlineMatch.html
+  /// [`CmdlineMatch::Contains`]: enum.CmdlineMatch.html
+  ///
+  /// **WARNING**: This method __does not__ initialize `memory_regions` field.  
+  /// If you want to do so, use `parse_maps()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{CmdlineMatch, Process};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new_by_cmdline("my_very_long_process_name", CmdlineMatch::Contains)?;
+  ///   println!("example process id: {}", ctx.get_pid());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn new_by_cmdline(cmdline: &str, match_mode: CmdlineMatch) -> Result<Process> {
+    for (pid, process_path) in Self::list_process_directories()? {
+      // Processes can exit while iterating and kernel threads have
+      // empty command line, so unreadable entries are just skipped.
+      let true_cmdline = match fs::read(process_path.join("cmdline")) {
+        Ok(bytes) => Self::join_cmdline(&bytes),
+        Err(_) => continue,
+      };
+
+      let matches = match match_mode {
+        CmdlineMatch::Exact => true_cmdline == cmdline,
+        CmdlineMatch::Contains => true_cmdline.contains(cmdline),
+      };
+
+      if matches {
+        return Process::from_pid(pid);
+      }
+    }
+
+    Err(anyhow!("Could not get process id of {}.", cmdline))
+  }
+
+  /// Finds all processes which name located in `/proc/\[pid\]/comm` file  
+  /// or command line located in `/proc/\[pid\]/cmdline` file matches  
+  /// regular expression `pattern`. Command line arguments are joined  
+  /// with single spaces before matching (see `Process::new_by_cmdline`).  
+  /// If `pattern` is not a valid regular expression, [`Err`] is returned.
+  ///
+  /// Processes that exit while iterating over `/proc/` are skipped.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **WARNING**: This method __does not__ initialize `memory_regions` field  
+  /// of returned processes. If you want to do so, use `parse_maps()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   for ctx in Process::find_by_regex(r"^game_server-v\d+\.\d+$")? {
+  ///     println!("{}: {}", ctx.get_pid(), ctx.get_name());
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn find_by_regex(pattern: &str) -> Result<Vec<Process>> {
+    let regex = Regex::new(pattern)
+      .map_err(|error| anyhow!("Could not compile regular expression {} ({}).", pattern, error))?;
+    let mut processes: Vec<Process> = Vec::new();
+
+    for (pid, process_path) in Self::list_process_directories()? {
+      let true_name = match fs::read_to_string(process_path.join("comm")) {
+        Ok(true_name) => true_name,
+        Err(_) => continue,
+      };
+
+      let true_cmdline = match fs::read(process_path.join("cmdline")) {
+        Ok(bytes) => Self::join_cmdline(&bytes),
+        Err(_) => continue,
+      };
+
+      if regex.is_match(true_name.trim_end()) || regex.is_match(&true_cmdline) {
+        if let Ok(process) = Process::with_pid_and_name(pid, true_name.trim_end().to_string()) {
+          processes.push(process);
+        }
+      }
+    }
+
+    Ok(processes)
+  }
+
+  /// Builds process object, reading start time of the process  
+  /// from `/proc/\[pid\]/stat` file.
+  fn with_pid_and_name(pid: Pid, name: String) -> Result<Process> {
+    // Pidfd is opened before reading start time, so both refer to the same process.
+    let pidfd = open_pidfd(pid);
+    let stat_path = path::Path::new("/proc/").join(pid.to_string()).join("stat");
+    let start_time = read_stat(&stat_path)?.start_time;
+
+    Ok(Process {
+      pid,
+      name,
+      memory_regions: None,
+      start_time,
+      pidfd,
+      pid_reuse_check: true,
+      write_verification: false,
+      read_chunk_size: DEFAULT_READ_CHUNK_SIZE,
+      offline: false,
+      memory_backend: Arc::new(ProcessVmBackend),
+    })
+  }
+
+  /// Builds process object named `name` backed by captured memory `dump`  
+  /// instead of live process, and parses its memory regions.
+  pub(crate) fn from_dump(name: String, dump: DumpMemory) -> Result<Process> {
+    let mut process = Process {
+      pid: Pid::from_raw(0),
+      name,
+      memory_regions: None,
+      start_time: 0,
+      pidfd: None,
+      pid_reuse_check: false,
+      write_verification: false,
+      read_chunk_size: DEFAULT_READ_CHUNK_SIZE,
+      offline: true,
+      memory_backend: Arc::new(dump),
+    };
+    process.parse_maps()?;
+    Ok(process)
+  }
+
+  /// Builds process object named `name` backed entirely by `backend`, which  
+  /// provides memory regions too (see `MemoryBackend::maps()`), and parses them.
+  #[cfg(feature = "testing")]
+  pub(crate) fn from_backend(name: String, backend: Arc<dyn MemoryBackend>) -> Result<Process> {
+    let mut process = Process {
+      pid: Pid::from_raw(0),
+      name,
+      memory_regions: None,
+      start_time: 0,
+      pidfd: None,
+      pid_reuse_check: false,
+      write_verification: false,
+      read_chunk_size: DEFAULT_READ_CHUNK_SIZE,
+      offline: false,
+      memory_backend: backend,
+    };
+    process.parse_maps()?;
+    Ok(process)
+  }
+
+  /// Returns method of accessing memory of the process, see `set_memory_backend()`.
+  pub(crate) fn memory_backend(&self) -> &dyn MemoryBackend {
+    self.memory_backend.as_ref()
+  }
+
+  /// Refreshes name of the process and forgets its memory regions,  
+  /// as both are replaced when process executes new program.  
+  /// Start time, and so process identity, does not change.
+  pub(crate) fn refresh_after_exec(&mut self) {
+    if let Ok(stat) = read_stat(&self.proc_path("stat")) {
+      self.name = stat.comm;
+    }
+    self.memory_regions = None;
+  }
+
+  /// Returns **true** if process this object was built for is still running  
+  /// under the same pid. Pids are reused after processes exit, so process  
+  /// with the same pid but different start time (see `ProcessStat::start_time`)  
+  /// is a different process. If `/proc/\[pid\]/stat` can not be read, **false** is returned.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///   // ...
+  ///   if !ctx.is_same_process() {
+  ///     println!("target exited, its pid may belong to other process now");
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn is_same_process(&self) -> bool {
+    self.stat().is_ok_and(|stat| stat.start_time == self.start_time)
+  }
+
+  /// Returns **true** if the process is still running, that is it did not  
+  /// exit (or become a zombie) and its pid was not reused by other process.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use std::{thread, time::Duration};
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///   while ctx.is_alive() {
+  ///     thread::sleep(Duration::from_secs(1));
+  ///   }
+  ///   println!("target closed");
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn is_alive(&self) -> bool {
+    self.stat().is_ok_and(|stat| {
+      stat.start_time == self.start_time
+        && stat.state != ProcessState::Zombie
+        && stat.state != ProcessState::Dead
+    })
+  }
+
+  /// Returns start time of the process read at construction, in clock ticks  
+  /// after system boot (see `ProcessStat::start_time`).
+  pub fn get_start_time(&self) -> u64 {
+    self.start_time
+  }
+
+  /// Enables or disables verification of process identity (see `is_same_process()`)  
+  /// before every memory read and write. It is enabled by default, so that memory  
+  /// of unrelated process which got the same pid after target exited is never  
+  /// touched. Verification polls pidfd of the process, which costs single system  
+  /// call, or reads `/proc/\[pid\]/stat` file on kernels without **pidfd_open(2)**,  
+  /// so it can be disabled in hot loops where the target is known to be alive.
+  pub fn set_pid_reuse_check(&mut self, enabled: bool) {
+    self.pid_reuse_check = enabled;
+  }
+
+  /// Returns [`Err`] if PID reuse check is enabled and the process  
+  /// is not the one this object was built for, or if the process is  
+  /// backed by captured memory (see `DumpProcess`), which can only be read.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub(crate) fn verify_identity(&self) -> Result<()> {
+    if self.offline {
+      return Err(anyhow!(
+        "Could not access process {} (it is an offline dump).",
+        self.name
+      ));
+    }
+    self.verify_pid_reuse()
+  }
+
+  /// Returns [`Err`] if PID reuse check is enabled and the process  
+  /// is not the one this object was built for. Unlike `verify_identity()`,  
+  /// it passes for captured memory, which is read through memory backend.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  fn verify_pid_reuse(&self) -> Result<()> {
+    if !self.pid_reuse_check {
+      return Ok(());
+    }
+
+    // Pid can be reused only after the process exits, which pidfd reports.
+    let is_same_process = match &self.pidfd {
+      Some(pidfd) => !has_exited(pidfd),
+      None => self.is_same_process(),
+    };
+    if !is_same_process {
+      return Err(ProcessError::ProcessExited(self.pid).into());
+    }
+    Ok(())
+  }
+
+  /// Enables or disables verification of memory writes. When enabled, every write  
+  /// (`write_memory_bytes()`, `write()`, `write_memory_batch()` and patches written  
+  /// by this crate) reads written memory back immediately and returns  
+  /// `ProcessError::WriteVerificationFailed` with differing bytes if it does not  
+  /// match, e.g. because the target overwrote patch in the meantime. It is disabled  
+  /// by default, since it doubles number of system calls per write.
+  ///
+  /// **NOTE**: Verification detects only changes made before memory is read back,  
+  /// so it narrows the window of races with the target, but does not close it.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, ProcessError};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.set_write_verification(true);
+  ///
+  ///   if let Err(error) = ctx.write::<u32>(0x601040, &100) {
+  ///     match error.downcast_ref::<ProcessError>() {
+  ///       Some(ProcessError::WriteVerificationFailed { differences, .. }) => {
+  ///         println!("{} bytes were overwritten", differences.len())
+  ///       }
+  ///       _ => return Err(error.into()),
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `4 bytes were overwritten`
+  pub fn set_write_verification(&mut self, enabled: bool) {
+    self.write_verification = enabled;
+  }
+
+  /// Sets size of chunks reads longer than it are split into (2 MiB by default).  
+  /// Single huge read may fail unpredictably when range touches unmapped  
+  /// pages, while chunks let `read_memory_chunked()` report exactly which  
+  /// parts of the range could not be read. Zero is treated as one byte.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.set_read_chunk_size(0x10000);
+  ///
+  ///   let bytes = ctx.read_memory_bytes(0x7f0000000000, 0x1000000)?;
+  ///   println!("read {} bytes", bytes.len());
+  ///   Ok(())
+  /// }
   /// ```
+  pub fn set_read_chunk_size(&mut self, chunk_size: usize) {
+    self.read_chunk_size = chunk_size.max(1);
+  }
+
+  /// Returns size of chunks large reads are split into, see `set_read_chunk_size()`.
+  pub(crate) fn read_chunk_size(&self) -> usize {
+    self.read_chunk_size
+  }
+
+  /// Sets method of accessing memory of the process used by every read  
+  /// and write (see [`MemoryBackend`]). By default [`ProcessVmBackend`] is used,  
+  /// which may be blocked on hardened kernels or by Yama. [`ProcMemBackend`]  
+  /// and [`PtraceBackend`] work there for callers attached as a tracer,  
+  /// and [`FallbackBackend`] picks the first one which works automatically.
+  ///
+  /// [`MemoryBackend`]: trait.MemoryBackend.html
+  /// [`ProcessVmBackend`]: struct.ProcessVmBackend.html
+  /// [`ProcMemBackend`]: struct.ProcMemBackend.html
+  /// [`PtraceBackend`]: struct.PtraceBackend.html
+  /// [`FallbackBackend`]: struct.FallbackBackend.html
+  ///
+  /// # Examples
+  /// ```no_run
   /// extern crate trickster;
-  /// use trickster::external::Process;
+  /// use trickster::{ProcMemBackend, Process};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let mut traced = ctx.attach()?;
+  ///   traced.set_memory_backend(ProcMemBackend::new());
+  ///   println!("value: {}", traced.read::<u32>(0x601040)?);
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn set_memory_backend<B: MemoryBackend + 'static>(&mut self, backend: B) {
+    self.memory_backend = Arc::new(backend);
+  }
+
+  /// Reads `buffer.len()` bytes at `address` back if write verification is enabled,  
+  /// returning `ProcessError::WriteVerificationFailed` if they differ from `buffer`.
+  pub(crate) fn verify_write(&self, address: usize, buffer: &[u8]) -> Result<()> {
+    if !self.write_verification {
+      return Ok(());
+    }
+
+    let actual = self.read_memory_bytes(address, buffer.len())?;
+    let differences: Vec<(usize, u8, u8)> = buffer
+      .iter()
+      .zip(actual.iter())
+      .enumerate()
+      .filter(|(_, (expected, actual))| expected != actual)
+      .map(|(offset, (expected, actual))| (address + offset, *expected, *actual))
+      .collect();
+
+    if differences.is_empty() {
+      return Ok(());
+    }
+    Err(
+      ProcessError::WriteVerificationFailed {
+        pid: self.pid,
+        address,
+        differences,
+      }
+      .into(),
+    )
+  }
+
+  /// Returns process ids and paths of all numeric directories in `/proc/`.
+  fn list_process_directories() -> Result<Vec<(Pid, path::PathBuf)>> {
+    let process_list = fs::read_dir("/proc/")?;
+    let mut directories: Vec<(Pid, path::PathBuf)> = Vec::new();
+
+    for process in process_list.filter_map(|process| process.ok()) {
+      let pid = match process.file_name().to_str().map(str::parse::<i32>) {
+        Some(Ok(pid)) => Pid::from_raw(pid),
+        _ => continue,
+      };
+
+      directories.push((pid, process.path()));
+    }
+
+    Ok(directories)
+  }
+
+  /// Joins NUL separated `/proc/\[pid\]/cmdline` content with single spaces.
+  fn join_cmdline(bytes: &[u8]) -> String {
+    bytes
+      .split(|byte| *byte == 0)
+      .filter(|argument| !argument.is_empty())
+      .map(|argument| String::from_utf8_lossy(argument).into_owned())
+      .collect::<Vec<String>>()
+      .join(" ")
+  }
+
+  /// Returns [`Cursor`] wrapping around byte buffer containing memory read at `address`  
+  /// in remote process. Size of returned byte buffer is equivalent to size of generic type (`T`).  
+  /// Reading is done using [**process_vm_readv(2)**](http://man7.org/linux/man-pages/man2/process_vm_readv.2.html)
+  /// system call by default, see `set_memory_backend()`.
+  ///
+  /// This requires the same permissions as debugging the process using
+  /// [**ptrace(2)**](http://man7.org/linux/man-pages/man2/ptrace.2.html): you must either be  
+  /// a privileged process (with **CAP_SYS_PTRACE**), or you must be running as the same user as the target  
+  /// process and the OS must have unprivileged debugging enabled.
+  ///
+  /// [`Cursor`]: https://doc.rust-lang.org/std/io/struct.Cursor.html
+  ///
+  /// # Examples
+  /// NOTE: In this scenario, process running this code is treated as remote process.  
+  /// Just for example purposes.
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
   ///
   /// fn main() -> Result<(), Box<dyn std::error::Error>> {
   ///   let ctx = Process::new("current_process_name")?;
   ///   println!("example process id: {}", ctx.get_pid());
+  ///
+  ///   let kind_of_remote_var: i32 = 1337;
+  ///   let read_byte_buffer = ctx.read_memory::<i32>(&kind_of_remote_var as *const i32 as usize)?;
+  ///
+  ///   for (index, byte) in read_byte_buffer.into_inner().iter().enumerate() {
+  ///     println!("read_byte_buffer[{}]: {}", index, byte);
+  ///   }
+  ///
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `example process id: 25805`  
+  /// `read_byte_buffer[0]: 57`  
+  /// `read_byte_buffer[1]: 5`  
+  /// `read_byte_buffer[2]: 0`  
+  /// `read_byte_buffer[3]: 0`  
+  ///
+  ///
+  /// If you want to construct 'real' value from byte buffer, using [`byteorder`] crate  
+  /// would be the easiest way because this action is kinda difficult to standardize  
+  /// because of [endianness](https://en.wikipedia.org/wiki/Endianness).
+  ///
+  /// [`byteorder`]: https://crates.io/crates/byteorder
+  ///
+  /// For primitive numeric types, there are also endianness-aware helpers  
+  /// like `read_u32_le()` or `read_f32_be()`, which need neither of them.
+  ///
+  /// Changing (a little) code above would look like:
+  /// ```no_run
+  /// extern crate byteorder;
+  /// # extern crate trickster;
+  /// use byteorder::{LittleEndian, ReadBytesExt};
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// # let ctx = trickster::Process::new("current_process_name")?;
+  /// # let kind_of_remote_var: i32 = 1337;
+  /// // ...
+  /// let mut read_byte_buffer = ctx.read_memory::<i32>(&kind_of_remote_var as *const i32 as usize)?;
+  /// println!("kind_of_remote_var from byte buffer: {}", read_byte_buffer.read_i32::<LittleEndian>()?);
+  /// // ...
+  /// # Ok(())
+  /// # }
+  /// ```
+  /// ...and this prints output like:  
+  /// `example process id: 26444`  
+  /// `kind_of_remote_var from byte buffer: 1337`
+  pub fn read_memory<T>(&self, address: usize) -> Result<io::Cursor<Vec<u8>>> {
+    Ok(io::Cursor::new(self.read_memory_bytes(address, mem::size_of::<T>())?))
+  }
+
+  /// Returns byte buffer containing `length` bytes of memory read at `address`  
+  /// in remote process. Unlike `read_memory()`, size of returned byte buffer  
+  /// is not bound to any type, so this can be used to read strings, arrays  
+  /// and whole structures of size known only at runtime.  
+  /// Reading is done using [**process_vm_readv(2)**](http://man7.org/linux/man-pages/man2/process_vm_readv.2.html)  
+  /// system call by default, see `set_memory_backend()`.
+  ///
+  /// Ranges longer than chunk size (see `set_read_chunk_size()`) are read  
+  /// in chunks. If less than `length` bytes were read, [`Err`] is returned,  
+  /// use `read_memory_chunked()` to read around unreadable parts instead.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// This requires the same permissions as debugging the process using  
+  /// [**ptrace(2)**](http://man7.org/linux/man-pages/man2/ptrace.2.html), see `read_memory()`.
+  ///
+  /// # Examples
+  /// NOTE: In this scenario, process running this code is treated as remote process.  
+  /// Just for example purposes.
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let kind_of_remote_string = "trickster";
+  ///   let read_byte_buffer =
+  ///     ctx.read_memory_bytes(kind_of_remote_string.as_ptr() as usize, kind_of_remote_string.len())?;
+  ///
+  ///   println!("read string: {}", String::from_utf8(read_byte_buffer)?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `read string: trickster`
+  pub fn read_memory_bytes(&self, address: usize, length: usize) -> Result<Vec<u8>> {
+    if length > self.read_chunk_size {
+      return Ok(self.read_memory_chunked(address, length, HolePolicy::Fail)?.bytes);
+    }
+
+    let mut buffer = vec![0u8; length];
+
+    if self.read_memory_into(address, &mut buffer)? != length {
+      return Err(anyhow!("Could not read memory. Partial read occurred."));
+    }
+
+    Ok(buffer)
+  }
+
+  /// Fills `buffer` with memory read at `address` in remote process  
+  /// and returns number of bytes read. Unlike `read_memory_bytes()`,  
+  /// this does not allocate, so it is well suited for hot loops  
+  /// reusing the same buffer over and over again.  
+  /// Reading is done using [**process_vm_readv(2)**](http://man7.org/linux/man-pages/man2/process_vm_readv.2.html)  
+  /// system call by default, see `set_memory_backend()`.
+  ///
+  /// **NOTE**: Partial read is not treated as an error. If returned number  
+  /// of bytes is lower than `buffer.len()`, rest of `buffer` is left untouched.
+  ///
+  /// This requires the same permissions as debugging the process using  
+  /// [**ptrace(2)**](http://man7.org/linux/man-pages/man2/ptrace.2.html), see `read_memory()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///   let mut buffer = [0u8; 0x1000];
+  ///
+  ///   for address in (0x400000..0x500000).step_by(buffer.len()) {
+  ///     if ctx.read_memory_into(address, &mut buffer)? == buffer.len() {
+  ///       // ...
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn read_memory_into(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+    self.verify_pid_reuse()?;
+
+    self
+      .memory_backend
+      .read(self.pid, address, buffer)
+      .map_err(|error| {
+        backend_error(
+          self.pid,
+          error,
+          format!("Could not read memory at {:#x}", address),
+        )
+      })
+  }
+
+  /// Returns byte buffer of `length` bytes containing memory read at `address`  
+  /// in remote process, together with number of bytes that were actually read.  
+  /// Unlike `read_memory_bytes()`, partial read is not treated as an error,  
+  /// bytes which could not be read (e.g. because range straddles an unmapped page)  
+  /// are left zeroed, so dumping tools can make forward progress across holes.  
+  /// Reading is done using [**process_vm_readv(2)**](http://man7.org/linux/man-pages/man2/process_vm_readv.2.html)  
+  /// system call by default, see `set_memory_backend()`.
+  ///
+  /// **NOTE**: If first byte at `address` is not readable, returned count is `0`  
+  /// instead of [`Err`]. [`Err`] is returned only if reading failed for other reasons.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let (buffer, bytes_read) = ctx.read_memory_partial(0x7ffff7dd0000, 0x4000)?;
+  ///   println!("read {} of {} bytes", bytes_read, buffer.len());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn read_memory_partial(&self, address: usize, length: usize) -> Result<(Vec<u8>, usize)> {
+    let mut buffer = vec![0u8; length];
+    let bytes_read = self.read_memory_into_partial(address, &mut buffer)?;
+    Ok((buffer, bytes_read))
+  }
+
+  /// Fills `buffer` with memory read at `address` like `read_memory_into()`,  
+  /// but returns zero instead of error if memory at `address` is not readable.
+  pub(crate) fn read_memory_into_partial(&self, address: usize, buffer: &mut [u8]) -> Result<usize> {
+    self.verify_pid_reuse()?;
+
+    match self.memory_backend.read(self.pid, address, buffer) {
+      Ok(bytes_read) => Ok(bytes_read),
+      Err(ref error) if error.raw_os_error() == Some(libc::EFAULT) => Ok(0),
+      Err(error) => Err(backend_error(
+        self.pid,
+        error,
+        format!("Could not read memory at {:#x}", address),
+      )),
+    }
+  }
+
+  /// Returns byte buffers containing memory read at every `(address, length)`  
+  /// pair of `ranges` in remote process, in the same order as `ranges`.  
+  /// All ranges are packed into single [**process_vm_readv(2)**](http://man7.org/linux/man-pages/man2/process_vm_readv.2.html)  
+  /// system call (or a few of them, if there are more ranges than **IOV_MAX**),  
+  /// which is much cheaper than calling `read_memory_bytes()` for every range.  
+  /// Other memory backends (see `set_memory_backend()`) may read ranges one by one.
+  ///
+  /// If any of the ranges could not be read completely, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let buffers = ctx.read_memory_batch(&[(0x601040, 4), (0x601080, 16), (0x6010f0, 8)])?;
+  ///   println!("second range: {:?}", buffers[1]);
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn read_memory_batch(&self, ranges: &[(usize, usize)]) -> Result<Vec<Vec<u8>>> {
+    self.verify_pid_reuse()?;
+    let mut buffers: Vec<Vec<u8>> = ranges.iter().map(|&(_, length)| vec![0u8; length]).collect();
+
+    let bytes_requested: usize = ranges.iter().map(|&(_, length)| length).sum();
+    let mut backend_ranges: Vec<(usize, &mut [u8])> = ranges
+      .iter()
+      .zip(buffers.iter_mut())
+      .map(|(&(address, _), buffer)| (address, buffer.as_mut_slice()))
+      .collect();
+
+    let bytes_read = self
+      .memory_backend
+      .read_batch(self.pid, &mut backend_ranges)
+      .map_err(|error| {
+        backend_error(
+          self.pid,
+          error,
+          format!("Could not read memory at {:#x}", ranges.first().map_or(0, |range| range.0)),
+        )
+      })?;
+
+    if bytes_read != bytes_requested {
+      return Err(anyhow!("Could not read memory. Partial read occurred."));
+    }
+
+    Ok(buffers)
+  }
+
+  /// Returns string read at `address` in remote process. Memory is read  
+  /// in chunks until NUL terminator is found or `max_length` bytes were read.  
+  /// Chunks never cross page boundaries, so string located right  
+  /// before unmapped page can still be read.
+  ///
+  /// If no bytes could be read at all or read bytes are not valid UTF-8, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// NOTE: In this scenario, process running this code is treated as remote process.  
+  /// Just for example purposes.
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let kind_of_remote_string = b"trickster\0";
+  ///   let string = ctx.read_cstring(kind_of_remote_string.as_ptr() as usize, 256)?;
+  ///
+  ///   println!("read string: {}", string);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `read string: trickster`
+  pub fn read_cstring(&self, address: usize, max_length: usize) -> Result<String> {
+    let mut string_bytes: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; CHUNK_SIZE];
+
+    while string_bytes.len() < max_length {
+      let chunk_address = address + string_bytes.len();
+      let chunk_length = (CHUNK_SIZE - chunk_address % CHUNK_SIZE).min(max_length - string_bytes.len());
+
+      let bytes_read = self.read_memory_into(chunk_address, &mut chunk[..chunk_length])?;
+      if bytes_read == 0 {
+        return Err(anyhow!("Could not read string at {:#x}.", address));
+      }
+
+      match chunk[..bytes_read].iter().position(|byte| *byte == 0) {
+        Some(terminator) => {
+          string_bytes.extend_from_slice(&chunk[..terminator]);
+          break;
+        }
+        None => string_bytes.extend_from_slice(&chunk[..bytes_read]),
+      }
+    }
+
+    String::from_utf8(string_bytes)
+      .map_err(|error| anyhow!("Could not read string at {:#x} ({}).", address, error))
+  }
+
+  /// Returns string decoded from UTF-16LE read at `address` in remote process.  
+  /// Memory is read in chunks until NUL terminator (two zero bytes) is found  
+  /// or `max_chars` UTF-16 code units were read. Wide strings like this are used  
+  /// by Windows programs running under Wine and by Mono/Unity runtimes.
+  ///
+  /// If no bytes could be read at all or read code units are not valid UTF-16, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let player_name = ctx.read_utf16_string(0x7f0012345678, 64)?;
+  ///   println!("player name: {}", player_name);
   ///   Ok(())
   /// }
   /// ```
-  /// This prints output like:  
-  /// `example process id: 2686`
-  ///
-  /// # Note about /proc/\[pid\]/comm
-  /// This file exposes the process's comm value — that is, the com‐  
-  /// mand name associated with the process.  Different threads in  
-  /// the same process may have different comm values, accessible  
-  /// via `/proc/[pid]/task/\[tid\]/comm`.  A thread may modify its comm  
-  /// value, or that of any of other thread in the same thread group  
-  /// (see the discussion of **CLONE_THREAD** in
-  /// [**clone(2)**](http://man7.org/linux/man-pages/man2/clone.2.html)), by writing  
-  /// to the file `/proc/self/task/\[tid\]/comm`. Strings longer than  
-  /// **TASK_COMM_LEN** (16) characters are silently truncated.  
-  ///
-  /// This file provides a superset of the
-  /// [**prctl(2)**](http://man7.org/linux/man-pages/man2/prctl.2.html) **PR_SET_NAME** and  
-  /// **PR_GET_NAME** operations, and is employed by  
-  /// [**pthread_setname_np(3)**](http://man7.org/linux/man-pages/man3/pthread_setname_np.3.html) when used to rename  
-  /// threads other than the caller.  
-  pub fn new(process_name: &str) -> Result<Process> {
-    let process_list = fs::read_dir("/proc/")?;
+  pub fn read_utf16_string(&self, address: usize, max_chars: usize) -> Result<String> {
+    let mut code_units: Vec<u16> = Vec::new();
+    let mut chunk = [0u8; CHUNK_SIZE];
 
-    for process in process_list.filter_map(|process| process.ok()) {
-      let filename_string = process
-        .file_name()
-        .into_string()
-        .map_err(|_| anyhow!("Could not convert file_name() of process directory to String."))?;
+    'reading: while code_units.len() < max_chars {
+      let chunk_address = address + code_units.len() * 2;
+      let chunk_length = ((CHUNK_SIZE - chunk_address % CHUNK_SIZE) & !1)
+        .max(2)
+        .min((max_chars - code_units.len()) * 2);
 
-      if !filename_string.chars().all(char::is_numeric) {
-        continue;
+      let bytes_read = self.read_memory_into(chunk_address, &mut chunk[..chunk_length])?;
+      if bytes_read < 2 {
+        return Err(anyhow!("Could not read string at {:#x}.", address));
       }
 
-      let comm_path = process.path().join("comm");
-      let true_name = fs::read_to_string(comm_path)?;
-
-      if true_name.trim_end() == process_name.to_string() {
-        return Ok(Process {
-          pid: Pid::from_raw(
-            filename_string
-              .parse::<i32>()
-              .expect("Could not parse i32 value from filename_string."),
-          ),
-          name: true_name,
-          memory_regions: None,
-        });
+      for code_unit_bytes in chunk[..bytes_read].chunks_exact(2) {
+        let code_unit = u16::from_le_bytes([code_unit_bytes[0], code_unit_bytes[1]]);
+        if code_unit == 0 {
+          break 'reading;
+        }
+        code_units.push(code_unit);
       }
     }
 
-    Err(anyhow!("Could not get process id of {}.", process_name))
+    String::from_utf16(&code_units)
+      .map_err(|error| anyhow!("Could not read string at {:#x} ({}).", address, error))
   }
-  /// Returns [`Cursor`] wrapping around byte buffer containing memory read at `address`  
-  /// in remote process. Size of returned byte buffer is equivalent to size of generic type (`T`).  
-  /// Reading is done using [**process_vm_readv(2)**](http://man7.org/linux/man-pages/man2/process_vm_readv.2.html)
-  /// system call.
+
+  /// Returns value of generic type (`T`) read at `address` in remote process.  
+  /// Read bytes are reinterpreted as `T` using local machine's endianness,  
+  /// so there is no need to go through [`Cursor`] and [`byteorder`] crate.  
+  /// Reading is done using `read_memory_into()`, so no allocation is made.
   ///
-  /// This requires the same permissions as debugging the process using
-  /// [**ptrace(2)**](http://man7.org/linux/man-pages/man2/ptrace.2.html): you must either be  
-  /// a privileged process (with **CAP_SYS_PTRACE**), or you must be running as the same user as the target  
-  /// process and the OS must have unprivileged debugging enabled.
+  /// `T` has to implement [`Pod`] trait (plain old data), which guarantees  
+  /// that every bit pattern is a valid value of `T`. It is implemented for  
+  /// all primitive numeric types and arrays of them, and can be derived  
+  /// for your own `#[repr(C)]` structures (see [`bytemuck`] crate documentation).
   ///
   /// [`Cursor`]: https://doc.rust-lang.org/std/io/struct.Cursor.html
+  /// [`byteorder`]: https://crates.io/crates/byteorder
+  /// [`Pod`]: https://docs.rs/bytemuck/1/bytemuck/trait.Pod.html
+  /// [`bytemuck`]: https://crates.io/crates/bytemuck
   ///
   /// # Examples
   /// NOTE: In this scenario, process running this code is treated as remote process.  
   /// Just for example purposes.
-  /// ```
+  /// ```no_run
   /// extern crate trickster;
-  /// use trickster::external::Process;
+  /// use trickster::Process;
   ///
   /// fn main() -> Result<(), Box<dyn std::error::Error>> {
   ///   let ctx = Process::new("current_process_name")?;
-  ///   println!("example process id: {}", ctx.get_pid());
   ///
   ///   let kind_of_remote_var: i32 = 1337;
-  ///   let read_byte_buffer = ctx.read_memory::<i32>(&kind_of_remote_var as *const i32 as usize)?;
-  ///
-  ///   for (index, byte) in read_byte_buffer.into_inner().iter().enumerate() {
-  ///     println!("read_byte_buffer[{}]: {}", index, byte);
-  ///   }
+  ///   let value = ctx.read::<i32>(&kind_of_remote_var as *const i32 as usize)?;
   ///
+  ///   println!("kind_of_remote_var: {}", value);
   ///   Ok(())
   /// }
   /// ```
   /// This prints output like:  
-  /// `example process id: 25805`  
-  /// `read_byte_buffer[0]: 57`  
-  /// `read_byte_buffer[1]: 5`  
-  /// `read_byte_buffer[2]: 0`  
-  /// `read_byte_buffer[3]: 0`  
+  /// `kind_of_remote_var: 1337`
+  pub fn read<T: Pod>(&self, address: usize) -> Result<T> {
+    let mut value = T::zeroed();
+
+    if self.read_memory_into(address, bytemuck::bytes_of_mut(&mut value))? != mem::size_of::<T>() {
+      return Err(anyhow!("Could not read memory. Partial read occurred."));
+    }
+
+    Ok(value)
+  }
+
+  /// Reads `count` consecutive values of generic type (`T`) starting at `address`  
+  /// in remote process with single system call, e.g. array of entity structures.  
+  /// If less than `count` values could be read, [`Err`] is returned.
   ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
   ///
-  /// If you want to construct 'real' value from byte buffer, using [`byteorder`] crate  
-  /// would be the easiest way because this action is kinda difficult to standardize  
-  /// because of [endianness](https://en.wikipedia.org/wiki/Endianness).
+  /// `T` has to implement [`Pod`] trait (plain old data), see `read()`.
   ///
-  /// [`byteorder`]: https://crates.io/crates/byteorder
+  /// [`Pod`]: https://docs.rs/bytemuck/1/bytemuck/trait.Pod.html
   ///
-  /// Changing (a little) code above would look like:
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let entity_positions = ctx.read_array::<[f32; 3]>(0x601040, 512)?;
+  ///   println!("first entity at {:?}", entity_positions[0]);
+  ///   Ok(())
+  /// }
   /// ```
-  /// extern crate byteorder;
-  /// use byteorder::{LittleEndian, ReadBytesExt};
-  /// // ...
-  /// let mut read_byte_buffer = ctx.read_memory::<i32>(&kind_of_remote_var as *const i32 as usize)?;
-  /// println!("kind_of_remote_var from byte buffer: {}", read_byte_buffer.read_i32::<LittleEndian>()?);
-  /// // ...
+  /// This prints output like:  
+  /// `first entity at [12.5, 0.0, -3.25]`
+  pub fn read_array<T: Pod>(&self, address: usize, count: usize) -> Result<Vec<T>> {
+    let mut values = vec![T::zeroed(); count];
+    let buffer: &mut [u8] = bytemuck::cast_slice_mut(&mut values);
+    let bytes_requested = buffer.len();
+
+    if self.read_memory_into(address, buffer)? != bytes_requested {
+      return Err(anyhow!("Could not read memory. Partial read occurred."));
+    }
+
+    Ok(values)
+  }
+
+  /// Returns address resolved by dereferencing multi-level pointer path  
+  /// starting at `base`. Every offset except the last one is added to  
+  /// the current address which is then dereferenced, the last offset  
+  /// is just added to the result, so `offsets` equal to `[0x10, 0x8, 0x30]`  
+  /// resolve `[[base + 0x10] + 0x8] + 0x30`. If `offsets` is empty, `base` is returned.  
+  /// Pointers are read as `usize`, so their size matches local machine's one.
+  ///
+  /// If any pointer could not be read or is null, [`Err`] telling at which  
+  /// level (starting from 0) and address the chain broke is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let health_address = ctx.resolve_pointer_chain(0x601040, &[0x10, 0x8, 0x30])?;
+  ///   println!("health: {}", ctx.read::<i32>(health_address)?);
+  ///   Ok(())
+  /// }
   /// ```
-  /// ...and this prints output like:  
-  /// `example process id: 26444`  
-  /// `kind_of_remote_var from byte buffer: 1337`
-  pub fn read_memory<T>(&self, address: usize) -> Result<io::Cursor<Vec<u8>>> {
-    let bytes_requested = mem::size_of::<T>();
-    let mut buffer = vec![0u8; bytes_requested];
+  pub fn resolve_pointer_chain(&self, base: usize, offsets: &[usize]) -> Result<usize> {
+    let mut address = base;
 
-    let remote = RemoteIoVec {
-      base: address,
-      len: bytes_requested,
-    };
+    for (level, offset) in offsets.iter().enumerate() {
+      let pointer_address = address.wrapping_add(*offset);
 
-    let bytes_read =
-      match process_vm_readv(self.pid, &[IoVec::from_mut_slice(&mut buffer)], &[remote]) {
-        Ok(bytes_read) => bytes_read,
-        Err(error) => {
-          return Err(anyhow!(
-            "Could not read memory at {:#x} ({}).",
-            address,
-            error
-          ));
-        }
-      };
+      if level == offsets.len() - 1 {
+        return Ok(pointer_address);
+      }
 
-    if bytes_read != bytes_requested {
-      return Err(anyhow!("Could not read memory. Partial read occurred."));
+      address = self.read::<usize>(pointer_address).map_err(|error| {
+        anyhow!(
+          "Pointer chain broke at level {} (could not dereference {:#x}: {}).",
+          level,
+          pointer_address,
+          error
+        )
+      })?;
+
+      if address == 0 {
+        return Err(anyhow!(
+          "Pointer chain broke at level {} (null pointer at {:#x}).",
+          level,
+          pointer_address
+        ));
+      }
     }
 
-    Ok(io::Cursor::new(buffer))
+    Ok(address)
   }
 
   /// Writes `buffer` at `address` in remote process. Size of `buffer`  
   /// is (or should be, if specified) equivalent to size of generic type (`T`).  
   /// Writing is done using [**process_vm_writev(2)**](http://man7.org/linux/man-pages/man2/process_vm_writev.2.html)
-  /// system call.
+  /// system call by default, see `set_memory_backend()`.
   ///
   /// This requires the same permissions as debugging the process using
   /// [**ptrace(2)**](http://man7.org/linux/man-pages/man2/ptrace.2.html): you must either be  
@@ -193,9 +1235,9 @@ impl Process {
   /// # Examples
   /// NOTE: In this scenario, process running this code is treated as remote process.  
   /// Just for example purposes.
-  /// ```
+  /// ```no_run
   /// extern crate trickster;
-  /// use trickster::external::Process;
+  /// use trickster::Process;
   ///   
   /// fn main() -> Result<(), Box<dyn std::error::Error>> {
   ///   let ctx = Process::new("current_process_name")?;
@@ -223,96 +1265,177 @@ impl Process {
   ///
   /// [`byteorder`]: https://crates.io/crates/byteorder
   ///
+  /// For primitive numeric types, there are also endianness-aware helpers  
+  /// like `write_u32_le()` or `write_u64_be()`, which need neither of them.
+  ///
   /// Changing (a little) code above to would look like:
-  /// ```
+  /// ```no_run
   /// extern crate byteorder;
+  /// # extern crate trickster;
   /// use byteorder::{LittleEndian, WriteBytesExt};
+  /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+  /// # let ctx = trickster::Process::new("current_process_name")?;
+  /// # let kind_of_remote_var: i32 = 1337;
   /// // ...
   /// let mut write_buffer = Vec::new();
   /// write_buffer.write_i32::<LittleEndian>(10)?;
   /// ctx.write_memory::<i32>(&kind_of_remote_var as *const i32 as usize, write_buffer)?;
   /// // ...
+  /// # Ok(())
+  /// # }
   /// ```
   pub fn write_memory<T>(&self, address: usize, buffer: Vec<u8>) -> Result<()> {
     let bytes_requested = mem::size_of::<T>();
 
-    let remote = RemoteIoVec {
-      base: address,
-      len: bytes_requested,
-    };
+    if buffer.len() < bytes_requested {
+      return Err(anyhow!("Could not write memory. Partial write occurred."));
+    }
 
-    let bytes_written = match process_vm_writev(self.pid, &[IoVec::from_slice(&buffer)], &[remote])
-    {
-      Ok(bytes_written) => bytes_written,
-      Err(error) => {
-        return Err(anyhow!(
-          "Could not write memory at {:#x} ({}).",
-          address,
-          error
-        ));
-      }
-    };
+    self.write_memory_bytes(address, &buffer[..bytes_requested])
+  }
+
+  /// Writes whole `buffer` at `address` in remote process. Unlike `write_memory()`,  
+  /// amount of written bytes is not bound to any type.  
+  /// Writing is done using [**process_vm_writev(2)**](http://man7.org/linux/man-pages/man2/process_vm_writev.2.html)  
+  /// system call by default, see `set_memory_backend()`.
+  ///
+  /// If less than `buffer.len()` bytes were written, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// This requires the same permissions as debugging the process using  
+  /// [**ptrace(2)**](http://man7.org/linux/man-pages/man2/ptrace.2.html), see `write_memory()`.
+  pub fn write_memory_bytes(&self, address: usize, buffer: &[u8]) -> Result<()> {
+    self.verify_identity()?;
+
+    let bytes_written = self
+      .memory_backend
+      .write(self.pid, address, buffer)
+      .map_err(|error| {
+        backend_error(
+          self.pid,
+          error,
+          format!("Could not write memory at {:#x}", address),
+        )
+      })?;
+
+    if bytes_written != buffer.len() {
+      return Err(anyhow!("Could not write memory. Partial write occurred."));
+    }
+
+    self.verify_write(address, buffer)
+  }
+
+  /// Writes whole `buffer` at `address` in remote process through `/proc/\[pid\]/mem`,  
+  /// which ignores memory protection, unlike `write_memory_bytes()`. It is used  
+  /// for patching read-only memory, e.g. code or GOT protected by RELRO.
+  pub(crate) fn write_memory_forced(&self, address: usize, buffer: &[u8]) -> Result<()> {
+    self.verify_identity()?;
+
+    fs::OpenOptions::new()
+      .write(true)
+      .open(self.proc_path("mem"))
+      .and_then(|file| file.write_all_at(buffer, address as u64))
+      .map_err(|error| anyhow!("Could not write memory at {:#x} ({}).", address, error))?;
+
+    self.verify_write(address, buffer)
+  }
+
+  /// Writes every `(address, buffer)` pair of `writes` in remote process.  
+  /// All writes are packed into single [**process_vm_writev(2)**](http://man7.org/linux/man-pages/man2/process_vm_writev.2.html)  
+  /// system call (or a few of them, if there are more writes than **IOV_MAX**),  
+  /// so patches spanning multiple fields land as close to atomically as the kernel allows.  
+  /// Other memory backends (see `set_memory_backend()`) may write buffers one by one.
+  ///
+  /// If any of the buffers could not be written completely, [`Err`] is returned.  
+  /// Writes preceding the failed one are **not** reverted.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   ctx.write_memory_batch(&[(0x601040, &[0x90, 0x90]), (0x601080, &100u32.to_ne_bytes())])?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn write_memory_batch(&self, writes: &[(usize, &[u8])]) -> Result<()> {
+    self.verify_identity()?;
+
+    let bytes_requested: usize = writes.iter().map(|(_, buffer)| buffer.len()).sum();
+    let bytes_written = self
+      .memory_backend
+      .write_batch(self.pid, writes)
+      .map_err(|error| {
+        backend_error(
+          self.pid,
+          error,
+          format!("Could not write memory at {:#x}", writes.first().map_or(0, |write| write.0)),
+        )
+      })?;
 
     if bytes_written != bytes_requested {
       return Err(anyhow!("Could not write memory. Partial write occurred."));
     }
 
+    for (address, buffer) in writes {
+      self.verify_write(*address, buffer)?;
+    }
     Ok(())
   }
 
+  /// Writes `value` of generic type (`T`) at `address` in remote process.  
+  /// Value is written using local machine's endianness, so there is no need  
+  /// to serialize it into byte buffer first. Writing is done using `write_memory_bytes()`.
+  ///
+  /// `T` has to implement [`Pod`] trait (plain old data), see `read()`.
+  ///
+  /// [`Pod`]: https://docs.rs/bytemuck/1/bytemuck/trait.Pod.html
+  ///
+  /// # Examples
+  /// NOTE: In this scenario, process running this code is treated as remote process.  
+  /// Just for example purposes.
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let kind_of_remote_var: f32 = 13.37;
+  ///   ctx.write::<f32>(&kind_of_remote_var as *const f32 as usize, &100.0)?;
+  ///
+  ///   println!("kind_of_remote_var after write: {}", kind_of_remote_var);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `kind_of_remote_var after write: 100`
+  pub fn write<T: Pod>(&self, address: usize, value: &T) -> Result<()> {
+    self.write_memory_bytes(address, bytemuck::bytes_of(value))
+  }
+
+  /// Writes all `values` of generic type (`T`) consecutively starting at `address`  
+  /// in remote process with single system call, see `read_array()` and `write()`.
+  pub fn write_array<T: Pod>(&self, address: usize, values: &[T]) -> Result<()> {
+    self.write_memory_bytes(address, bytemuck::cast_slice(values))
+  }
+
   /// Reads `/proc/\[pid\]/maps` file line by line and parses  
   /// every value to the corresponding value in `MemoryRegion` struct  
   /// in `self.memory_regions`.
   pub fn parse_maps(&mut self) -> Result<()> {
-    let maps_path = path::Path::new("/proc/")
-      .join(self.pid.to_string())
-      .join("maps");
-
-    let mut reader = io::BufReader::new(fs::File::open(maps_path)?);
-    let mut buffer = Vec::<u8>::new();
-    let mut memory_regions: Vec<MemoryRegion> = Vec::new();
-
-    while reader.read_until(b'\n', &mut buffer)? != 0 {
-      let line = String::from_utf8(buffer).unwrap();
-      let mut permissions: RegionPermissions = RegionPermissions {
-        readable: false,
-        writeable: false,
-        executable: false,
-        shared: false,
-      };
-
-      let (start, end, permissions_string, offset, dev_major, dev_minor, inode, path) = scan_fmt_some!(
-        line.as_str(),
-        "{x}-{x} {} {x} {}:{} {} {}",
-        [hex usize], [hex usize], String, [hex usize], u8, u8, usize, String
-      );
-
-      for character in permissions_string.unwrap().chars() {
-        match character {
-          'r' => permissions.readable = true,
-          'w' => permissions.writeable = true,
-          'x' => permissions.executable = true,
-          's' => permissions.shared = true,
-          _ => continue,
-        }
-      }
+    let mut memory_regions = self.iter_maps()?.collect::<Result<Vec<MemoryRegion>>>()?;
 
-      memory_regions.push(MemoryRegion {
-        start: start.unwrap(),
-        end: end.unwrap(),
-        permissions,
-        offset: offset.unwrap(),
-        dev_major: dev_major.unwrap(),
-        dev_minor: dev_minor.unwrap(),
-        inode: inode.unwrap(),
-        path,
-      });
-
-      buffer = line.into_bytes();
-      buffer.clear();
-    }
-
-    self.memory_regions = Some(memory_regions);
+    // Kernel lists regions in ascending address order already, but lookups
+    // like `get_address_region()` rely on it, so make sure it holds.
+    memory_regions.sort_by_key(|region| region.start);
+    self.memory_regions = Some(Arc::new(memory_regions));
 
     Ok(())
   }
@@ -336,61 +1459,107 @@ impl Process {
   /// **NOTE**: `parse_maps();` should be called minimum once  
   /// before calling `get_memory_regions();`.
   pub fn get_memory_regions(&self) -> Result<&Vec<MemoryRegion>> {
-    return match &self.memory_regions {
-      Some(memory_regions) => Ok(memory_regions),
+    match &self.memory_regions {
+      Some(memory_regions) => Ok(memory_regions.as_ref()),
       None => Err(anyhow!("Memory regions not mapped.")),
-    };
+    }
   }
 
-  /// Returns immutable reference to memory region with  
+  /// Returns immutable reference to the first memory region with  
   /// `path` field in `MemoryRegion` struct trimmed to  
   /// contain only file name equals `region_name` and  
   /// region permissions equals `permissions_eq` if not [`None`].  
+  /// Regions which name matches but permissions do not are skipped.  
   ///   
   /// [`None`]: https://doc.rust-lang.org/std/option/
   ///  
   /// **NOTES**:
   /// - `parse_maps();` should be called minimum once  
-  /// before calling `region_find_first_by_name();`.
+  ///   before calling `region_find_first_by_name();`.
   /// - `region_name` can be equal to `[anonymous_region]` if  
-  /// region was not mapped from a file or its not special.
+  ///   region was not mapped from a file or its not special.
+  /// - `region_name` can be a glob pattern (e.g. `libssl*.so*`).
   pub fn region_find_first_by_name(
     &self,
     region_name: &str,
     permissions_eq: Option<RegionPermissions>,
   ) -> Result<&MemoryRegion> {
     let regions = self.get_memory_regions()?;
+    let name_pattern = RegionNamePattern::new(region_name);
+    let mut name_matched = false;
     for region in regions {
-      let index_to_split = region
-        .path
-        .clone()
-        .unwrap_or("[anonymous_region]".to_string())
-        .rfind('/')
-        .unwrap_or(0 as usize);
-
-      let split_file_name = region
-        .path
-        .clone()
-        .unwrap_or("[anonymous_region]".to_string())
-        .split_off(index_to_split + if index_to_split > 0 { 1 } else { 0 });
-
-      if split_file_name == region_name {
-        return match permissions_eq {
-          Some(permissions) => {
-            if permissions == region.permissions {
-              Ok(region)
-            } else {
-              Err(anyhow!("Could not get region with specific permissions."))
-            }
-          }
-          None => Ok(region),
-        };
+      if !name_pattern.matches(region) {
+        continue;
+      }
+      name_matched = true;
+
+      match &permissions_eq {
+        Some(permissions) if *permissions != region.permissions => continue,
+        _ => return Ok(region),
       }
     }
+
+    if name_matched {
+      return Err(anyhow!("Could not get {} region with specific permissions.", region_name));
+    }
     Err(anyhow!("Could not find {}.", region_name))
   }
 
+  /// Returns immutable references to all memory regions with  
+  /// `path` field in `MemoryRegion` struct trimmed to  
+  /// contain only file name equals `region_name` and  
+  /// region permissions equals `permissions_eq` if not [`None`].  
+  /// Regions are returned in the same order as in `/proc/[pid]/maps`.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  ///
+  /// **NOTES**:
+  /// - `parse_maps();` should be called minimum once  
+  ///   before calling `region_find_all_by_name();`.
+  /// - `region_name` can be equal to `[anonymous_region]` if  
+  ///   region was not mapped from a file or its not special.
+  /// - `region_name` can be a glob pattern (e.g. `libssl*.so*`).
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let text_segment = ctx
+  ///     .region_find_all_by_name("libc.so.6", None)?
+  ///     .into_iter()
+  ///     .find(|region| region.permissions.executable);
+  ///   println!("{:?}", text_segment);
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn region_find_all_by_name(
+    &self,
+    region_name: &str,
+    permissions_eq: Option<RegionPermissions>,
+  ) -> Result<Vec<&MemoryRegion>> {
+    let regions = self.get_memory_regions()?;
+    let name_pattern = RegionNamePattern::new(region_name);
+    Ok(
+      regions
+        .iter()
+        .filter(|region| name_pattern.matches(region))
+        .filter(|region| {
+          permissions_eq
+            .as_ref()
+            .is_none_or(|permissions| *permissions == region.permissions)
+        })
+        .collect(),
+    )
+  }
+
   /// Returns the region in which's range `address` is located.  
+  /// Region end address is exclusive, so `region.end` itself belongs  
+  /// to the next region (if any). Lookup is done with binary search.  
   /// If `self.memory_regions` is [`None`], [`Err`] is returned.  
   ///
   /// [`None`]: https://doc.rust-lang.org/std/option/
@@ -399,42 +1568,118 @@ impl Process {
   /// **NOTE**: `parse_maps();` should be called minimum once  
   /// before calling `get_memory_regions();`.
   pub fn get_address_region(&self, address: usize) -> Result<&MemoryRegion> {
-    match &self.memory_regions {
-      Some(regions) => {
-        for region in regions {
-          if address >= region.start && address <= region.end {
-            return Ok(region);
-          }
-        }
-      }
-      None => return Err(anyhow!("Memory regions not mapped.")),
-    }
-    Err(anyhow!("Could not get {:x}'s region.", address))
-  }
-
-  // TODO: document this
-  #[cfg(feature = "byteorder-utils")]
-  #[cfg(target_endian = "little")]
-  /// Returns the absolute address calculated from function parameters.
-  pub fn abs_addr(&self, address: usize, offset: usize, size: usize) -> Result<usize> {
-    use byteorder::{NativeEndian, ReadBytesExt};
-    if let Ok(mut buffer) = self.read_memory::<u32>(address + offset) {
-      let value = buffer.read_u32::<NativeEndian>()?;
-      return Ok(value as usize + address + size);
+    let regions = self.get_memory_regions()?;
+    // Regions are sorted by start address and do not overlap,
+    // so the only candidate is the last region starting at or before `address`.
+    let index = regions.partition_point(|region| region.start <= address);
+    match index {
+      0 => Err(anyhow!("Could not get {:x}'s region.", address)),
+      _ if address < regions[index - 1].end => Ok(&regions[index - 1]),
+      _ => Err(anyhow!("Could not get {:x}'s region.", address)),
     }
-    Err(anyhow!("Could not get absolute address."))
-  }
-
-  // TODO: document this
-  #[cfg(feature = "byteorder-utils")]
-  #[cfg(target_endian = "little")]
-  /// Returns the call address.
-  pub fn call_addr(&self, address: usize) -> Result<usize> {
-    use byteorder::{NativeEndian, ReadBytesExt};
-    if let Ok(mut buffer) = self.read_memory::<u32>(address + 0x1) {
-      let value = buffer.read_u32::<NativeEndian>()?;
-      return Ok(value as usize + address + 0x5);
+  }
+
+  /// Returns address referenced by instruction at `address`, which is `instruction_length`  
+  /// bytes long and holds signed 32-bit displacement `displacement_offset` bytes after  
+  /// its start. Displacement is relative to the end of the instruction, like in x86-64  
+  /// RIP-relative addressing (e.g. `lea rax, [rip+disp32]` is `48 8D 05 disp32`,  
+  /// so `displacement_offset` is 3 and `instruction_length` is 7) and in `call rel32`  
+  /// or `jmp rel32`. Instruction itself is not validated, see `resolve_call()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Signature};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   // mov rax, [rip+disp32] loading pointer to player object.
+  ///   let signature = Signature::new("48 8B 05 ?? ?? ?? ?? 48 85 C0 74")?;
+  ///   let instruction = ctx.scan_module("game", &signature)?[0];
+  ///   let player_pointer = ctx.resolve_rip_relative(instruction, 3, 7)?;
+  ///   println!("player pointer at {:#x}", player_pointer);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `player pointer at 0x55d4c0e41a28`
+  pub fn resolve_rip_relative(
+    &self,
+    address: usize,
+    displacement_offset: usize,
+    instruction_length: usize,
+  ) -> Result<usize> {
+    let displacement = self.read::<i32>(address.wrapping_add(displacement_offset))?;
+    Ok(
+      address
+        .wrapping_add(instruction_length)
+        .wrapping_add(displacement as isize as usize),
+    )
+  }
+
+  /// Returns address of function called by x86-64 call instruction at `address`.  
+  /// Both `call rel32` (`E8 rel32`) and `call [rip+disp32]` (`FF 15 disp32`, used for  
+  /// imported functions when PLT is not used) are supported, in the latter case  
+  /// function address is read from memory the instruction refers to. If there is  
+  /// no such instruction at `address`, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Signature};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let signature = Signature::new("E8 ?? ?? ?? ?? 89 83 ?? ?? ?? ?? 85 C0")?;
+  ///   let call = ctx.scan_module("game", &signature)?[0];
+  ///   println!("calls {:#x}", ctx.resolve_call(call)?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `calls 0x55d4c0e3a6f0`
+  #[cfg(target_arch = "x86_64")]
+  pub fn resolve_call(&self, address: usize) -> Result<usize> {
+    let opcode = self.read::<[u8; 2]>(address)?;
+    match opcode {
+      [0xe8, _] => self.resolve_rip_relative(address, 1, 5),
+      [0xff, 0x15] => self.read::<usize>(self.resolve_rip_relative(address, 2, 6)?),
+      _ => Err(anyhow!(
+        "Could not resolve call at {:#x} (unexpected opcode {:02x} {:02x}).",
+        address,
+        opcode[0],
+        opcode[1]
+      )),
     }
-    Err(anyhow!("Could not get call address."))
   }
 }
+
+/// Opens pidfd referring to process `pid`. Returns [`None`] if it could not  
+/// be opened, e.g. because kernel does not support **pidfd_open(2)**.
+///
+/// [`None`]: https://doc.rust-lang.org/std/option/
+fn open_pidfd(pid: Pid) -> Option<Arc<OwnedFd>> {
+  let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid.as_raw(), 0) };
+  if fd < 0 {
+    return None;
+  }
+  Some(Arc::new(unsafe { OwnedFd::from_raw_fd(fd as i32) }))
+}
+
+/// Returns **true** if process referred to by `pidfd` exited,  
+/// which is reported by pidfd becoming readable.
+fn has_exited(pidfd: &OwnedFd) -> bool {
+  let mut poll_fd = libc::pollfd {
+    fd: pidfd.as_raw_fd(),
+    events: libc::POLLIN,
+    revents: 0,
+  };
+  let ready = unsafe { libc::poll(&mut poll_fd, 1, 0) };
+  ready > 0 && poll_fd.revents & libc::POLLIN != 0
+}
@@ -0,0 +1,183 @@
+use anyhow::Result;
+use std::slice;
+
+use super::memory_region::RegionNamePattern;
+use super::{MemoryRegion, Process, RegionKind};
+
+/// Iterator over memory regions of a process, filtered with conditions  
+/// added by builder methods. Created by `Process::regions()`.
+#[derive(Clone, Debug)]
+pub struct RegionQuery<'a> {
+  regions: slice::Iter<'a, MemoryRegion>,
+  readable: Option<bool>,
+  writeable: Option<bool>,
+  executable: Option<bool>,
+  shared: Option<bool>,
+  file_backed: Option<bool>,
+  kind: Option<RegionKind>,
+  name: Option<RegionNamePattern>,
+  path_contains: Vec<String>,
+  address_range: Option<(usize, usize)>,
+}
+
+impl<'a> RegionQuery<'a> {
+  /// Keeps only readable regions.
+  pub fn readable(mut self) -> Self {
+    self.readable = Some(true);
+    self
+  }
+
+  /// Keeps only regions which are not readable.
+  pub fn not_readable(mut self) -> Self {
+    self.readable = Some(false);
+    self
+  }
+
+  /// Keeps only writeable regions.
+  pub fn writable(mut self) -> Self {
+    self.writeable = Some(true);
+    self
+  }
+
+  /// Keeps only regions which are not writeable.
+  pub fn not_writable(mut self) -> Self {
+    self.writeable = Some(false);
+    self
+  }
+
+  /// Keeps only executable regions.
+  pub fn executable(mut self) -> Self {
+    self.executable = Some(true);
+    self
+  }
+
+  /// Keeps only regions which are not executable.
+  pub fn not_executable(mut self) -> Self {
+    self.executable = Some(false);
+    self
+  }
+
+  /// Keeps only shared regions.
+  pub fn shared(mut self) -> Self {
+    self.shared = Some(true);
+    self
+  }
+
+  /// Keeps only private (copy on write) regions.
+  pub fn private(mut self) -> Self {
+    self.shared = Some(false);
+    self
+  }
+
+  /// Keeps only regions mapped from a file.
+  pub fn file_backed(mut self) -> Self {
+    self.file_backed = Some(true);
+    self
+  }
+
+  /// Keeps only regions which are not mapped from a file,  
+  /// that is anonymous and special (like `[heap]`) regions.
+  pub fn not_file_backed(mut self) -> Self {
+    self.file_backed = Some(false);
+    self
+  }
+
+  /// Keeps only regions of category `kind`, see `MemoryRegion::kind()`.
+  pub fn kind(mut self, kind: RegionKind) -> Self {
+    self.kind = Some(kind);
+    self
+  }
+
+  /// Keeps only regions which file name equals `name`, which can  
+  /// also be a glob pattern (see `Process::region_find_first_by_name()`).
+  pub fn name(mut self, name: &str) -> Self {
+    self.name = Some(RegionNamePattern::new(name));
+    self
+  }
+
+  /// Keeps only regions which path contains `pattern`. Calling this method  
+  /// multiple times requires path to contain all of given patterns.
+  pub fn path_contains(mut self, pattern: &str) -> Self {
+    self.path_contains.push(pattern.to_string());
+    self
+  }
+
+  /// Keeps only regions overlapping address range `[start, end)`.
+  pub fn overlapping(mut self, start: usize, end: usize) -> Self {
+    self.address_range = Some((start, end));
+    self
+  }
+
+  /// Returns **true** if `region` satisfies all conditions of the query.
+  fn matches(&self, region: &MemoryRegion) -> bool {
+    let permissions = &region.permissions;
+    let file_backed = region.path.as_ref().is_some_and(|path| path.starts_with('/'));
+
+    self.readable.is_none_or(|readable| permissions.readable == readable)
+      && self.writeable.is_none_or(|writeable| permissions.writeable == writeable)
+      && self.executable.is_none_or(|executable| permissions.executable == executable)
+      && self.shared.is_none_or(|shared| permissions.shared == shared)
+      && self.file_backed.is_none_or(|expected| file_backed == expected)
+      && self.kind.is_none_or(|kind| region.kind() == kind)
+      && self.name.as_ref().is_none_or(|name| name.matches(region))
+      && self
+        .path_contains
+        .iter()
+        .all(|pattern| region.path.as_ref().is_some_and(|path| path.contains(pattern.as_str())))
+      && self.address_range.is_none_or(|(start, end)| region.overlaps(&(start..end)))
+  }
+}
+
+impl<'a> Iterator for RegionQuery<'a> {
+  type Item = &'a MemoryRegion;
+
+  fn next(&mut self) -> Option<&'a MemoryRegion> {
+    while let Some(region) = self.regions.next() {
+      if self.matches(region) {
+        return Some(region);
+      }
+    }
+    None
+  }
+}
+
+impl Process {
+  /// Returns query over memory regions of the process. Conditions are added  
+  /// with builder methods of [`RegionQuery`], which is an iterator  
+  /// yielding only regions satisfying all of them.
+  ///
+  /// [`RegionQuery`]: struct.RegionQuery.html
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `regions();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for region in ctx.regions()?.readable().writable().not_executable().path_contains("heap") {
+  ///     println!("{:#x}-{:#x}", region.start, region.end);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn regions(&self) -> Result<RegionQuery<'_>> {
+    Ok(RegionQuery {
+      regions: self.get_memory_regions()?.iter(),
+      readable: None,
+      writeable: None,
+      executable: None,
+      shared: None,
+      file_backed: None,
+      kind: None,
+      name: None,
+      path_contains: Vec::new(),
+      address_range: None,
+    })
+  }
+}
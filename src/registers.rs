@@ -0,0 +1,242 @@
+use anyhow::Result;
+use nix::errno::Errno;
+use nix::unistd::Pid;
+use std::mem;
+
+use super::error::system_call_error;
+use super::TracedProcess;
+
+/// General purpose registers of a thread on x86_64, in the same layout  
+/// as `struct user_regs_struct` from `<sys/user.h>`.
+#[cfg(target_arch = "x86_64")]
+#[repr(C)]
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct Registers {
+  pub r15: u64,
+  pub r14: u64,
+  pub r13: u64,
+  pub r12: u64,
+  pub rbp: u64,
+  pub rbx: u64,
+  pub r11: u64,
+  pub r10: u64,
+  pub r9: u64,
+  pub r8: u64,
+  pub rax: u64,
+  pub rcx: u64,
+  pub rdx: u64,
+  pub rsi: u64,
+  pub rdi: u64,
+  /// Number of the system call thread is stopped in, **rax** is  
+  /// already overwritten with its return value at syscall exit.
+  pub orig_rax: u64,
+  pub rip: u64,
+  pub cs: u64,
+  pub eflags: u64,
+  pub rsp: u64,
+  pub ss: u64,
+  pub fs_base: u64,
+  pub gs_base: u64,
+  pub ds: u64,
+  pub es: u64,
+  pub fs: u64,
+  pub gs: u64,
+}
+
+/// General purpose registers of a thread on aarch64, in the same layout  
+/// as `struct user_pt_regs` from `<asm/ptrace.h>`.
+#[cfg(target_arch = "aarch64")]
+#[repr(C)]
+#[derive(Clone, Copy, Default, Eq, PartialEq, Debug)]
+pub struct Registers {
+  /// Registers `x0` to `x30`, `x30` being the link register.
+  pub regs: [u64; 31],
+  pub sp: u64,
+  pub pc: u64,
+  pub pstate: u64,
+}
+
+// Registers consist of `u64` fields only, so there is no padding
+// and every bit pattern is a valid value.
+unsafe impl bytemuck::Zeroable for Registers {}
+unsafe impl bytemuck::Pod for Registers {}
+
+#[cfg(target_arch = "x86_64")]
+impl Registers {
+  /// Returns instruction pointer (**rip**).
+  pub fn instruction_pointer(&self) -> usize {
+    self.rip as usize
+  }
+
+  /// Sets instruction pointer (**rip**).
+  pub fn set_instruction_pointer(&mut self, address: usize) {
+    self.rip = address as u64;
+  }
+
+  /// Returns stack pointer (**rsp**).
+  pub fn stack_pointer(&self) -> usize {
+    self.rsp as usize
+  }
+
+  /// Sets stack pointer (**rsp**).
+  pub fn set_stack_pointer(&mut self, address: usize) {
+    self.rsp = address as u64;
+  }
+
+  /// Returns number of the system call thread is stopped in (**orig_rax**).
+  pub fn syscall_number(&self) -> i64 {
+    self.orig_rax as i64
+  }
+
+  /// Returns system call arguments (**rdi**, **rsi**, **rdx**, **r10**, **r8**, **r9**).
+  pub fn syscall_args(&self) -> [u64; 6] {
+    [self.rdi, self.rsi, self.rdx, self.r10, self.r8, self.r9]
+  }
+
+  /// Returns system call return value (**rax**), negative error number on failure.  
+  /// It is valid only at system call exit.
+  pub fn syscall_result(&self) -> i64 {
+    self.rax as i64
+  }
+}
+
+#[cfg(target_arch = "aarch64")]
+impl Registers {
+  /// Returns instruction pointer (**pc**).
+  pub fn instruction_pointer(&self) -> usize {
+    self.pc as usize
+  }
+
+  /// Sets instruction pointer (**pc**).
+  pub fn set_instruction_pointer(&mut self, address: usize) {
+    self.pc = address as u64;
+  }
+
+  /// Returns stack pointer (**sp**).
+  pub fn stack_pointer(&self) -> usize {
+    self.sp as usize
+  }
+
+  /// Sets stack pointer (**sp**).
+  pub fn set_stack_pointer(&mut self, address: usize) {
+    self.sp = address as u64;
+  }
+
+  /// Returns number of the system call thread is stopped in (**x8**).
+  pub fn syscall_number(&self) -> i64 {
+    self.regs[8] as i64
+  }
+
+  /// Returns system call arguments (**x0** to **x5**). At system call exit  
+  /// **x0** is already overwritten with return value.
+  pub fn syscall_args(&self) -> [u64; 6] {
+    let mut args = [0; 6];
+    args.copy_from_slice(&self.regs[..6]);
+    args
+  }
+
+  /// Returns system call return value (**x0**), negative error number on failure.  
+  /// It is valid only at system call exit.
+  pub fn syscall_result(&self) -> i64 {
+    self.regs[0] as i64
+  }
+}
+
+impl TracedProcess {
+  /// Returns general purpose registers of stopped traced thread `tid`.  
+  /// Registers are read with **PTRACE_GETREGSET**, which works the same  
+  /// way on all supported architectures.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Registers};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   for tid in traced.traced_threads() {
+  ///     let registers: Registers = traced.get_regs(tid)?;
+  ///     println!("{}: ip {:#x} sp {:#x}", tid, registers.instruction_pointer(), registers.stack_pointer());
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `2686: ip 0x7f0c8e4b1d3e sp 0x7ffd1c2a3b40`
+  pub fn get_regs(&self, tid: Pid) -> Result<Registers> {
+    self.ensure_stopped(tid)?;
+
+    let mut registers = Registers::default();
+    ptrace_regset(libc::PTRACE_GETREGSET, tid, libc::NT_PRSTATUS, &mut registers).map_err(|error| {
+      system_call_error(
+        tid,
+        error,
+        format!("Could not get registers of thread {}", tid),
+      )
+    })?;
+
+    Ok(registers)
+  }
+
+  /// Sets general purpose registers of stopped traced thread `tid`,  
+  /// using **PTRACE_SETREGSET**. Registers usually should be read with  
+  /// `get_regs()` first and modified, rather than built from scratch.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let traced = Process::new("current_process_name")?.attach()?;
+  ///   let tid = traced.get_pid();
+  ///
+  ///   let mut registers = traced.get_regs(tid)?;
+  ///   registers.set_instruction_pointer(0x401000);
+  ///   traced.set_regs(tid, &registers)?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn set_regs(&self, tid: Pid, registers: &Registers) -> Result<()> {
+    self.ensure_stopped(tid)?;
+
+    ptrace_regset(
+      libc::PTRACE_SETREGSET,
+      tid,
+      libc::NT_PRSTATUS,
+      &mut { *registers },
+    ).map_err(|error| {
+      system_call_error(
+        tid,
+        error,
+        format!("Could not set registers of thread {}", tid),
+      )
+    })
+  }
+}
+
+/// Issues **PTRACE_GETREGSET** or **PTRACE_SETREGSET** `request` for `note_type` register set  
+/// (e.g. **NT_PRSTATUS** for general purpose registers) of thread `tid`, using `buffer` as buffer.
+pub(crate) fn ptrace_regset<T>(
+  request: libc::c_uint,
+  tid: Pid,
+  note_type: libc::c_int,
+  buffer: &mut T,
+) -> nix::Result<()> {
+  let mut iovec = libc::iovec {
+    iov_base: buffer as *mut T as *mut libc::c_void,
+    iov_len: mem::size_of::<T>(),
+  };
+
+  let result = unsafe {
+    libc::ptrace(
+      request,
+      tid.as_raw(),
+      note_type as usize as *mut libc::c_void,
+      &mut iovec as *mut libc::iovec as *mut libc::c_void,
+    )
+  };
+
+  Errno::result(result).map(drop)
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use bytemuck::Pod;
+use std::marker::PhantomData;
+use std::mem;
+
+use super::Process;
+
+/// Number of bytes `RemoteSliceIter` reads at once.
+const PREFETCH_SIZE: usize = 0x1000;
+
+/// View of `len` consecutive values of type `T` at `address` in remote process,  
+/// e.g. entity list of a game. Values are read only when they are accessed,  
+/// every access reads current value from the process.
+///
+/// `T` has to implement [`Pod`] trait (plain old data), see `Process::read()`.
+///
+/// [`Pod`]: https://docs.rs/bytemuck/1/bytemuck/trait.Pod.html
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{Process, RemoteSlice};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let ctx = Process::new("current_process_name")?;
+///
+///   let entity_count = ctx.read::<u32>(0x601038)? as usize;
+///   let health = RemoteSlice::<i32>::new(&ctx, 0x601040, entity_count);
+///   println!("first entity health: {}", health.get(0)?);
+///
+///   for (index, value) in health.iter().enumerate() {
+///     if value? <= 0 {
+///       println!("entity {} is dead", index);
+///     }
+///   }
+///   Ok(())
+/// }
+/// ```
+/// This prints output like:  
+/// `first entity health: 100`  
+/// `entity 3 is dead`
+pub struct RemoteSlice<'a, T: Pod> {
+  process: &'a Process,
+  address: usize,
+  len: usize,
+  value_type: PhantomData<T>,
+}
+
+impl<'a, T: Pod> RemoteSlice<'a, T> {
+  /// RemoteSlice object constructor. Memory is not accessed until values are read.
+  pub fn new(process: &'a Process, address: usize, len: usize) -> RemoteSlice<'a, T> {
+    RemoteSlice {
+      process,
+      address,
+      len,
+      value_type: PhantomData,
+    }
+  }
+
+  /// Returns address of the first value in remote process.
+  pub fn address(&self) -> usize {
+    self.address
+  }
+
+  /// Returns number of values in the slice.
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Returns **true** if the slice has no values.
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Returns address of value at `index`, or [`None`] if it is out of bounds.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  pub fn address_of(&self, index: usize) -> Option<usize> {
+    if index < self.len {
+      Some(self.address.wrapping_add(index * mem::size_of::<T>()))
+    } else {
+      None
+    }
+  }
+
+  /// Reads value at `index`. If `index` is out of bounds or the value  
+  /// could not be read, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn get(&self, index: usize) -> Result<T> {
+    self.process.read::<T>(self.checked_address(index)?)
+  }
+
+  /// Writes `value` at `index`. If `index` is out of bounds or the value  
+  /// could not be written, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn set(&self, index: usize, value: &T) -> Result<()> {
+    self.process.write::<T>(self.checked_address(index)?, value)
+  }
+
+  /// Reads all values of the slice at once, see `Process::read_array()`.
+  pub fn to_vec(&self) -> Result<Vec<T>> {
+    self.process.read_array::<T>(self.address, self.len)
+  }
+
+  /// Returns iterator over values of the slice, which reads them  
+  /// in chunks of 4 KB, instead of one value at a time.
+  pub fn iter(&self) -> RemoteSliceIter<'a, T> {
+    RemoteSliceIter {
+      slice: RemoteSlice::new(self.process, self.address, self.len),
+      index: 0,
+      buffer: Vec::new(),
+      buffer_start: 0,
+      failed: false,
+    }
+  }
+
+  fn checked_address(&self, index: usize) -> Result<usize> {
+    self.address_of(index).ok_or_else(|| {
+      anyhow!(
+        "Index {} is out of bounds of remote slice of length {}.",
+        index,
+        self.len
+      )
+    })
+  }
+}
+
+/// Iterator over values of [`RemoteSlice`], created by `RemoteSlice::iter()`.  
+/// If a chunk could not be read, [`Err`] is yielded and iteration ends.
+///
+/// [`RemoteSlice`]: struct.RemoteSlice.html
+/// [`Err`]: https://doc.rust-lang.org/std/result/
+pub struct RemoteSliceIter<'a, T: Pod> {
+  slice: RemoteSlice<'a, T>,
+  index: usize,
+  /// Values prefetched starting at `buffer_start` index.
+  buffer: Vec<T>,
+  buffer_start: usize,
+  failed: bool,
+}
+
+impl<'a, T: Pod> Iterator for RemoteSliceIter<'a, T> {
+  type Item = Result<T>;
+
+  fn next(&mut self) -> Option<Result<T>> {
+    if self.failed || self.index >= self.slice.len {
+      return None;
+    }
+
+    if self.index >= self.buffer_start + self.buffer.len() {
+      let chunk_length = (PREFETCH_SIZE / mem::size_of::<T>().max(1))
+        .max(1)
+        .min(self.slice.len - self.index);
+      let chunk_address = self.slice.address.wrapping_add(self.index * mem::size_of::<T>());
+
+      match self.slice.process.read_array::<T>(chunk_address, chunk_length) {
+        Ok(buffer) => {
+          self.buffer = buffer;
+          self.buffer_start = self.index;
+        }
+        Err(error) => {
+          self.failed = true;
+          return Some(Err(error));
+        }
+      }
+    }
+
+    let value = self.buffer[self.index - self.buffer_start];
+    self.index += 1;
+    Some(Ok(value))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    // Iteration may end early if reading fails, so there is no lower bound.
+    if self.failed {
+      (0, Some(0))
+    } else {
+      (0, Some(self.slice.len.saturating_sub(self.index)))
+    }
+  }
+}
+
+impl<'a, T: Pod> IntoIterator for &RemoteSlice<'a, T> {
+  type Item = Result<T>;
+  type IntoIter = RemoteSliceIter<'a, T>;
+
+  fn into_iter(self) -> RemoteSliceIter<'a, T> {
+    self.iter()
+  }
+}
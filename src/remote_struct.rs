@@ -0,0 +1,90 @@
+use anyhow::Result;
+
+use super::Process;
+
+/// Structure read from and written to remote process memory as a whole,  
+/// implemented with `#[derive(RemoteStruct)]` for structs which fields  
+/// implement [`Pod`] trait. Requires `derive` feature.
+///
+/// Fields are laid out like in C structure unless their offset in remote  
+/// structure is given with `#[remote(offset = ...)]` attribute, so remote  
+/// structures with padding or unknown fields do not need placeholder fields.
+///
+/// [`Pod`]: https://docs.rs/bytemuck/1/bytemuck/trait.Pod.html
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{Process, RemoteStruct};
+///
+/// #[derive(RemoteStruct)]
+/// struct Player {
+///   health: i32,
+///   armor: i32,
+///   #[remote(offset = 0x40)]
+///   position: [f32; 3],
+///   team: u8,
+/// }
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let ctx = Process::new("current_process_name")?;
+///
+///   let mut player = Player::read_from(&ctx, 0x601040)?;
+///   println!("health: {}, position: {:?}", player.health, player.position);
+///   player.health = 1000;
+///   player.write_to(&ctx, 0x601040)?;
+///   Ok(())
+/// }
+/// ```
+/// This prints output like:  
+/// `health: 100, position: [12.5, 0.0, -3.25]`
+pub trait RemoteStruct: Sized {
+  /// Returns offsets of fields in remote structure, in order of declaration.
+  fn field_offsets() -> Vec<usize>;
+
+  /// Returns number of bytes spanned by fields of remote structure.
+  fn remote_size() -> usize;
+
+  /// Builds structure from `bytes` of remote structure, which length  
+  /// is minimum `remote_size()`.
+  fn from_remote_bytes(bytes: &[u8]) -> Self;
+
+  /// Returns bytes of every field together with its offset in remote structure.
+  fn remote_fields(&self) -> Vec<(usize, &[u8])>;
+
+  /// Reads structure at `address` in `process` with a single read.
+  fn read_from(process: &Process, address: usize) -> Result<Self> {
+    let bytes = process.read_memory_bytes(address, Self::remote_size())?;
+    Ok(Self::from_remote_bytes(&bytes))
+  }
+
+  /// Writes fields of structure at `address` in `process`. Bytes between fields  
+  /// are left untouched, see `Process::write_memory_batch()`.
+  fn write_to(&self, process: &Process, address: usize) -> Result<()> {
+    let writes: Vec<(usize, &[u8])> = self
+      .remote_fields()
+      .into_iter()
+      .map(|(offset, bytes)| (address.wrapping_add(offset), bytes))
+      .collect();
+    process.write_memory_batch(&writes)
+  }
+}
+
+/// Helpers used by code generated with `#[derive(RemoteStruct)]`.
+#[doc(hidden)]
+pub mod private {
+  use bytemuck::Pod;
+  use std::mem;
+
+  pub fn align_up(offset: usize, align: usize) -> usize {
+    offset.next_multiple_of(align)
+  }
+
+  pub fn read_field<T: Pod>(bytes: &[u8], offset: usize) -> T {
+    bytemuck::pod_read_unaligned(&bytes[offset..offset + mem::size_of::<T>()])
+  }
+
+  pub fn field_bytes<T: Pod>(value: &T) -> &[u8] {
+    bytemuck::bytes_of(value)
+  }
+}
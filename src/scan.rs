@@ -0,0 +1,569 @@
+use anyhow::Result;
+use memchr::memmem::Finder;
+use std::str::FromStr;
+
+use super::pagemap::page_size;
+use super::{MemoryRegion, Process, RegionQuery};
+
+/// Size of chunks in which memory is read while scanning.
+pub(crate) const SCAN_CHUNK_SIZE: usize = 0x100000;
+
+/// Byte signature (pattern) with optional wildcards, used to find  
+/// code or data in remote process memory without knowing its address.
+///
+/// Signatures are written in the same format IDA uses, that is  
+/// hexadecimal bytes separated with spaces, where `?` or `??`  
+/// stands for wildcard that matches any byte, e.g. `48 8B ?? ?? E8 ? ? ? ?`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Signature {
+  bytes: Vec<Option<u8>>,
+}
+
+impl Signature {
+  /// Signature object constructor. Parses IDA-style `pattern`.  
+  /// If `pattern` is empty, contains only wildcards or any of its  
+  /// tokens is neither a hexadecimal byte nor wildcard, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```
+  /// extern crate trickster;
+  /// use trickster::Signature;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let signature = Signature::new("48 8B ?? ?? E8 ? ? ? ?")?;
+  ///   assert_eq!(signature.len(), 9);
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn new(pattern: &str) -> Result<Signature> {
+    let mut bytes: Vec<Option<u8>> = Vec::new();
+
+    for token in pattern.split_whitespace() {
+      if token == "?" || token == "??" {
+        bytes.push(None);
+        continue;
+      }
+
+      if token.len() != 2 {
+        return Err(anyhow!("Invalid signature token {}.", token));
+      }
+
+      bytes.push(Some(
+        u8::from_str_radix(token, 16).map_err(|_| anyhow!("Invalid signature token {}.", token))?,
+      ));
+    }
+
+    if bytes.iter().all(Option::is_none) {
+      return Err(anyhow!(
+        "Signature has to contain at least one non-wildcard byte."
+      ));
+    }
+
+    Ok(Signature { bytes })
+  }
+
+  /// Returns signature length in bytes (wildcards included).
+  pub fn len(&self) -> usize {
+    self.bytes.len()
+  }
+
+  /// Returns **true** if signature has no bytes.  
+  /// This can never be the case for signature created with `Signature::new()`.
+  pub fn is_empty(&self) -> bool {
+    self.bytes.is_empty()
+  }
+
+  /// Returns signature bytes, wildcards are represented by [`None`].
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  pub fn bytes(&self) -> &[Option<u8>] {
+    &self.bytes
+  }
+
+  /// Returns **true** if signature matches `haystack` at its very beginning.
+  pub fn matches(&self, haystack: &[u8]) -> bool {
+    haystack.len() >= self.bytes.len()
+      && self
+        .bytes
+        .iter()
+        .zip(haystack)
+        .all(|(byte, haystack_byte)| byte.is_none() || *byte == Some(*haystack_byte))
+  }
+
+  /// Returns offsets of all signature matches in `haystack`.  
+  /// If `first_only` is **true**, search stops after the first match.
+  pub fn find_in(&self, haystack: &[u8], first_only: bool) -> Vec<usize> {
+    let mut offsets: Vec<usize> = Vec::new();
+
+    if haystack.len() < self.bytes.len() {
+      return offsets;
+    }
+
+    // Search is anchored on the first non-wildcard byte to skip
+    // verifying positions which can't possibly match.
+    let (anchor_index, anchor_byte) = self
+      .bytes
+      .iter()
+      .enumerate()
+      .find_map(|(index, byte)| byte.map(|byte| (index, byte)))
+      .unwrap_or((0, 0));
+
+    let last_offset = haystack.len() - self.bytes.len();
+    let mut offset = 0;
+
+    while offset <= last_offset {
+      let anchor_search = &haystack[offset + anchor_index..=last_offset + anchor_index];
+      match anchor_search.iter().position(|byte| *byte == anchor_byte) {
+        Some(position) => offset += position,
+        None => break,
+      }
+
+      if self.matches(&haystack[offset..]) {
+        offsets.push(offset);
+        if first_only {
+          break;
+        }
+      }
+
+      offset += 1;
+    }
+
+    offsets
+  }
+}
+
+impl FromStr for Signature {
+  type Err = anyhow::Error;
+
+  fn from_str(pattern: &str) -> Result<Signature> {
+    Signature::new(pattern)
+  }
+}
+
+impl Process {
+  /// Returns addresses of all `signature` matches in `region`.  
+  /// Region is read in chunks, pages which could not be read are skipped.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Signature};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let region = ctx.region_find_first_by_name("current_process_name", None)?;
+  ///   let signature = Signature::new("48 8B ?? ?? E8 ? ? ? ?")?;
+  ///
+  ///   for address in ctx.scan_region(region, &signature)? {
+  ///     println!("match at {:#x}", address);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn scan_region(&self, region: &MemoryRegion, signature: &Signature) -> Result<Vec<usize>> {
+    self.scan_range(region.start, region.end, signature, false)
+  }
+
+  /// Returns address of the first `signature` match in `region`.  
+  /// If there is no match, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn scan_region_first(&self, region: &MemoryRegion, signature: &Signature) -> Result<usize> {
+    self
+      .scan_range(region.start, region.end, signature, true)?
+      .first()
+      .cloned()
+      .ok_or_else(|| anyhow!("Could not find signature in region at {:#x}.", region.start))
+  }
+
+  /// Returns addresses of all `signature` matches in all readable memory regions.  
+  /// Regions which could not be read at all are skipped.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `scan();`.
+  pub fn scan(&self, signature: &Signature) -> Result<Vec<usize>> {
+    let mut addresses: Vec<usize> = Vec::new();
+
+    for region in self.get_memory_regions()? {
+      if !region.permissions.readable {
+        continue;
+      }
+
+      if let Ok(region_addresses) = self.scan_region(region, signature) {
+        addresses.extend(region_addresses);
+      }
+    }
+
+    Ok(addresses)
+  }
+
+  /// Returns address of the first `signature` match in all readable memory regions.  
+  /// If there is no match, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `scan_first();`.
+  pub fn scan_first(&self, signature: &Signature) -> Result<usize> {
+    for region in self.get_memory_regions()? {
+      if !region.permissions.readable {
+        continue;
+      }
+
+      if let Ok(address) = self.scan_region_first(region, signature) {
+        return Ok(address);
+      }
+    }
+
+    Err(anyhow!("Could not find signature."))
+  }
+
+  /// Returns addresses of all `signature` matches in all readable memory regions,  
+  /// just like `scan()` does, but distributes the work across threads of [`rayon`]  
+  /// global thread pool. Regions are split into chunks, so a single huge region  
+  /// (e.g. heap) is scanned by many threads too. Returned addresses are sorted.
+  ///
+  /// [`rayon`]: https://crates.io/crates/rayon
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `scan_parallel();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Signature};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let signature = Signature::new("48 8B ?? ?? E8 ? ? ? ?")?;
+  ///   println!("matches: {}", ctx.scan_parallel(&signature)?.len());
+  ///   Ok(())
+  /// }
+  /// ```
+  #[cfg(feature = "parallel-scan")]
+  pub fn scan_parallel(&self, signature: &Signature) -> Result<Vec<usize>> {
+    use rayon::prelude::*;
+
+    let overlap = signature.len() - 1;
+    let mut chunks: Vec<(usize, usize)> = Vec::new();
+
+    for region in self.get_memory_regions()? {
+      if !region.permissions.readable {
+        continue;
+      }
+
+      // Every chunk is extended by signature length minus one, so matches
+      // starting at the very end of the chunk are found only once.
+      for chunk_start in (region.start..region.end).step_by(SCAN_CHUNK_SIZE) {
+        let chunk_end = (chunk_start + SCAN_CHUNK_SIZE + overlap).min(region.end);
+        chunks.push((chunk_start, chunk_end));
+      }
+    }
+
+    let mut addresses: Vec<usize> = chunks
+      .par_iter()
+      .filter_map(|&(chunk_start, chunk_end)| {
+        self.scan_range(chunk_start, chunk_end, signature, false).ok()
+      })
+      .flatten()
+      .collect();
+
+    addresses.sort_unstable();
+    Ok(addresses)
+  }
+
+  /// Returns addresses of all `signature` matches in all mappings of file  
+  /// which name equals `module_name` (see `region_find_first_by_name()`).  
+  /// Non-readable mappings are skipped, so there is no need to scan  
+  /// the whole address space just to find a signature inside one library.  
+  /// If there is no mapping of such file, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `scan_module();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Signature};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let signature = Signature::new("48 8B ?? ?? E8 ? ? ? ?")?;
+  ///   for address in ctx.scan_module("libfoo.so", &signature)? {
+  ///     println!("match at {:#x}", address);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn scan_module(&self, module_name: &str, signature: &Signature) -> Result<Vec<usize>> {
+    let mut addresses: Vec<usize> = Vec::new();
+
+    for region in self.module_regions(module_name)? {
+      if region.permissions.readable {
+        addresses.extend(self.scan_region(region, signature)?);
+      }
+    }
+
+    Ok(addresses)
+  }
+
+  /// Returns address of the first `signature` match in all mappings of file  
+  /// which name equals `module_name` (see `scan_module()`).  
+  /// If there is no match, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn scan_module_first(&self, module_name: &str, signature: &Signature) -> Result<usize> {
+    for region in self.module_regions(module_name)? {
+      if !region.permissions.readable {
+        continue;
+      }
+
+      if let Some(address) = self.scan_range(region.start, region.end, signature, true)?.first() {
+        return Ok(*address);
+      }
+    }
+
+    Err(anyhow!("Could not find signature in {}.", module_name))
+  }
+
+  /// Returns addresses of all occurrences of exact byte sequence `needle`  
+  /// in readable regions yielded by `regions` query (see `regions()`).  
+  /// Unlike `scan()`, there are no wildcards, so memory is searched with  
+  /// fast substring search algorithm, which makes looking for magic numbers  
+  /// or string literals considerably faster. Regions which could not be read  
+  /// at all are skipped. If `needle` is empty, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `find_bytes();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for address in ctx.find_bytes(b"\x7fELF", ctx.regions()?.not_file_backed())? {
+  ///     println!("ELF header at {:#x}", address);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn find_bytes(&self, needle: &[u8], regions: RegionQuery<'_>) -> Result<Vec<usize>> {
+    let finder = needle_finder(needle)?;
+    let mut addresses: Vec<usize> = Vec::new();
+
+    for region in regions.filter(|region| region.permissions.readable) {
+      if let Ok(region_addresses) = self.find_bytes_range(region.start, region.end, &finder, false)
+      {
+        addresses.extend(region_addresses);
+      }
+    }
+
+    Ok(addresses)
+  }
+
+  /// Returns address of the first occurrence of `needle` in readable regions  
+  /// yielded by `regions` query (see `find_bytes()`).  
+  /// If there is no occurrence or `needle` is empty, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `find_first_bytes();`.
+  pub fn find_first_bytes(&self, needle: &[u8], regions: RegionQuery<'_>) -> Result<usize> {
+    let finder = needle_finder(needle)?;
+
+    for region in regions.filter(|region| region.permissions.readable) {
+      if let Ok(addresses) = self.find_bytes_range(region.start, region.end, &finder, true) {
+        if let Some(address) = addresses.first() {
+          return Ok(*address);
+        }
+      }
+    }
+
+    Err(anyhow!("Could not find bytes {:02x?}.", needle))
+  }
+
+  /// Returns all regions which file name equals `module_name`.
+  fn module_regions(&self, module_name: &str) -> Result<Vec<&MemoryRegion>> {
+    let regions = self.region_find_all_by_name(module_name, None)?;
+
+    if regions.is_empty() {
+      return Err(anyhow!("Could not find {}.", module_name));
+    }
+
+    Ok(regions)
+  }
+
+  /// Scans memory between `start` and `end` for `signature` matches.
+  fn scan_range(
+    &self,
+    start: usize,
+    end: usize,
+    signature: &Signature,
+    first_only: bool,
+  ) -> Result<Vec<usize>> {
+    self.find_in_range(start, end, signature.len() - 1, first_only, |chunk| {
+      signature.find_in(chunk, first_only)
+    })
+  }
+
+  /// Searches memory between `start` and `end` for needle of `finder`.
+  fn find_bytes_range(
+    &self,
+    start: usize,
+    end: usize,
+    finder: &Finder<'_>,
+    first_only: bool,
+  ) -> Result<Vec<usize>> {
+    self.find_in_range(start, end, finder.needle().len() - 1, first_only, |chunk| {
+      finder.find_iter(chunk).collect()
+    })
+  }
+
+  /// Searches memory between `start` and `end` in chunks overlapping by `overlap`  
+  /// bytes (see `walk_chunks()`). `matcher` returns offsets of matches found  
+  /// in every chunk, which are returned as addresses.
+  fn find_in_range<F>(
+    &self,
+    start: usize,
+    end: usize,
+    overlap: usize,
+    first_only: bool,
+    mut matcher: F,
+  ) -> Result<Vec<usize>>
+  where
+    F: FnMut(&[u8]) -> Vec<usize>,
+  {
+    let mut addresses: Vec<usize> = Vec::new();
+
+    self.walk_chunks(start, end, overlap, |chunk_start, chunk| {
+      for offset in matcher(chunk) {
+        addresses.push(chunk_start + offset);
+        if first_only {
+          return false;
+        }
+      }
+      true
+    })?;
+
+    Ok(addresses)
+  }
+
+  /// Reads memory between `start` and `end` in chunks and passes every chunk  
+  /// together with its address to `visit`, until it returns **false**. Consecutive  
+  /// chunks overlap by `overlap` bytes, so matches crossing chunk boundaries  
+  /// are not missed. Memory which could not be read is skipped page by page,  
+  /// so only readable parts of chunks are visited.
+  pub(crate) fn walk_chunks<F>(
+    &self,
+    start: usize,
+    end: usize,
+    overlap: usize,
+    mut visit: F,
+  ) -> Result<()>
+  where
+    F: FnMut(usize, &[u8]) -> bool,
+  {
+    let mut chunk_start = start;
+
+    while chunk_start < end {
+      let chunk_length = SCAN_CHUNK_SIZE.min(end - chunk_start);
+      let (chunk, bytes_read) = self.read_memory_partial(chunk_start, chunk_length)?;
+
+      if bytes_read > 0 && !visit(chunk_start, &chunk[..bytes_read]) {
+        break;
+      }
+
+      if bytes_read < chunk_length {
+        // Nothing crosses the hole, so reading continues without overlap
+        // at the page following the first byte which could not be read.
+        chunk_start = (chunk_start + bytes_read + 1).next_multiple_of(page_size());
+      } else if chunk_start + chunk_length >= end {
+        break;
+      } else {
+        chunk_start += chunk_length.saturating_sub(overlap).max(1);
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Builds substring searcher for `needle`, which has to contain at least one byte.
+fn needle_finder(needle: &[u8]) -> Result<Finder<'_>> {
+  if needle.is_empty() {
+    return Err(anyhow!("Needle has to contain at least one byte."));
+  }
+
+  Ok(Finder::new(needle))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Signature;
+
+  #[test]
+  fn finds_all_matches() {
+    let signature = Signature::new("48 8B ?? 05").unwrap();
+    let haystack = [
+      0x90, 0x48, 0x8b, 0x01, 0x05, 0x48, 0x8b, 0xff, 0x05, 0x48, 0x8b,
+    ];
+
+    assert_eq!(signature.find_in(&haystack, false), vec![1, 5]);
+    assert_eq!(signature.find_in(&haystack, true), vec![1]);
+  }
+
+  #[test]
+  fn finds_overlapping_matches() {
+    let signature = Signature::new("AA ? AA").unwrap();
+
+    assert_eq!(signature.find_in(&[0xaa; 5], false), vec![0, 1, 2]);
+  }
+
+  #[test]
+  fn anchors_on_first_non_wildcard_byte() {
+    let signature = Signature::new("?? ? E8 ?? 00").unwrap();
+    let haystack = [0xe8, 0x00, 0x11, 0x22, 0xe8, 0x33, 0x00];
+
+    assert_eq!(signature.find_in(&haystack, false), vec![2]);
+    assert_eq!(
+      signature.find_in(&haystack[..6], false),
+      Vec::<usize>::new()
+    );
+  }
+
+  #[test]
+  fn finds_match_at_haystack_edges() {
+    let signature = Signature::new("DE AD").unwrap();
+
+    assert_eq!(signature.find_in(&[0xde, 0xad], false), vec![0]);
+    assert_eq!(signature.find_in(&[0x00, 0xde, 0xad], false), vec![1]);
+    assert_eq!(signature.find_in(&[0xde], false), Vec::<usize>::new());
+    assert_eq!(signature.find_in(&[], false), Vec::<usize>::new());
+  }
+
+  #[test]
+  fn rejects_invalid_patterns() {
+    assert!(Signature::new("").is_err());
+    assert!(Signature::new("?? ?").is_err());
+    assert!(Signature::new("48 8").is_err());
+    assert!(Signature::new("48 GG").is_err());
+    assert!(Signature::new("488B").is_err());
+  }
+}
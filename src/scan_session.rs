@@ -0,0 +1,933 @@
+use anyhow::Result;
+use std::cmp::Ordering;
+#[cfg(feature = "serde-support")]
+use std::convert::TryInto;
+#[cfg(feature = "serde-support")]
+use std::io::Write;
+#[cfg(feature = "serde-support")]
+use std::{fs, io, path};
+
+use super::pagemap::page_size;
+use super::{ChunkedRead, HolePolicy, Process};
+
+/// Size of chunks in which memory is read while scanning for values.
+const VALUE_SCAN_CHUNK_SIZE: usize = 0x10000;
+
+/// Type of value looked for in `ScanSession`.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub enum ScanValueType {
+  I32,
+  I64,
+  F32,
+  F64,
+  /// Byte array of given length.
+  Bytes(usize),
+}
+
+impl ScanValueType {
+  /// Returns size of value in bytes.
+  pub fn size(&self) -> usize {
+    match self {
+      ScanValueType::I32 | ScanValueType::F32 => 4,
+      ScanValueType::I64 | ScanValueType::F64 => 8,
+      ScanValueType::Bytes(length) => *length,
+    }
+  }
+
+  /// Returns alignment at which values are looked for.  
+  /// Numbers are aligned to their size, byte arrays are not aligned at all.
+  pub fn alignment(&self) -> usize {
+    match self {
+      ScanValueType::Bytes(_) => 1,
+      _ => self.size(),
+    }
+  }
+
+  /// Returns value decoded from `bytes` using local machine's endianness.  
+  /// `bytes` has to be exactly `size()` bytes long.
+  pub fn decode(&self, bytes: &[u8]) -> ScanValue {
+    match self {
+      ScanValueType::I32 => ScanValue::I32(bytemuck::pod_read_unaligned(bytes)),
+      ScanValueType::I64 => ScanValue::I64(bytemuck::pod_read_unaligned(bytes)),
+      ScanValueType::F32 => ScanValue::F32(bytemuck::pod_read_unaligned(bytes)),
+      ScanValueType::F64 => ScanValue::F64(bytemuck::pod_read_unaligned(bytes)),
+      ScanValueType::Bytes(_) => ScanValue::Bytes(bytes.to_vec()),
+    }
+  }
+}
+
+/// Value looked for in `ScanSession`.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub enum ScanValue {
+  I32(i32),
+  I64(i64),
+  F32(f32),
+  F64(f64),
+  Bytes(Vec<u8>),
+}
+
+impl ScanValue {
+  /// Returns type of the value.
+  pub fn value_type(&self) -> ScanValueType {
+    match self {
+      ScanValue::I32(_) => ScanValueType::I32,
+      ScanValue::I64(_) => ScanValueType::I64,
+      ScanValue::F32(_) => ScanValueType::F32,
+      ScanValue::F64(_) => ScanValueType::F64,
+      ScanValue::Bytes(bytes) => ScanValueType::Bytes(bytes.len()),
+    }
+  }
+
+  /// Returns value encoded using local machine's endianness.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    match self {
+      ScanValue::I32(value) => value.to_ne_bytes().to_vec(),
+      ScanValue::I64(value) => value.to_ne_bytes().to_vec(),
+      ScanValue::F32(value) => value.to_ne_bytes().to_vec(),
+      ScanValue::F64(value) => value.to_ne_bytes().to_vec(),
+      ScanValue::Bytes(bytes) => bytes.clone(),
+    }
+  }
+}
+
+/// Refinement pass applied to candidates of `ScanSession`.  
+/// Every pass compares value currently stored at candidate address  
+/// with the value seen during the previous pass (or initial scan).
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub enum ScanRefinement {
+  /// Value is equal to the given one.
+  Exact(ScanValue),
+  /// Value is different than previously.
+  Changed,
+  /// Value is the same as previously.
+  Unchanged,
+  /// Value is greater than previously.
+  Increased,
+  /// Value is lower than previously.
+  Decreased,
+  /// Value is greater than previously by exactly the given amount.
+  IncreasedBy(ScanValue),
+  /// Value is lower than previously by exactly the given amount.
+  DecreasedBy(ScanValue),
+  /// Value differs from the given one by at most `tolerance`, e.g. `100.0 ± 0.5`.  
+  /// Useful for floats, which are rarely exact after physics updates.
+  Approximately { value: ScanValue, tolerance: f64 },
+  /// Value rounded to the nearest integer is equal to the given one  
+  /// rounded the same way, so `100.0` matches everything in `[99.5, 100.5)`.
+  Rounded(ScanValue),
+}
+
+/// Cheat Engine style value scan. Session starts with initial scan  
+/// for an exact value in all readable and writeable memory regions  
+/// and is then narrowed down with refinement passes (see `ScanRefinement`)  
+/// until only a handful of candidate addresses is left.
+///
+/// If initial value is not known, session can also start with a snapshot  
+/// of all readable and writeable memory regions (see `ScanSession::new_unknown()`),  
+/// in which case every aligned address is a candidate until the first refinement.
+///
+/// Session does not hold reference to `Process`, so it has to be passed  
+/// to every method that accesses remote memory. With `serde-support` feature  
+/// enabled, session can be saved to disk and loaded later (see `ScanSession::save()`).
+#[derive(Clone, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct ScanSession {
+  value_type: ScanValueType,
+  addresses: Vec<usize>,
+  /// Values seen during the last pass, `value_type.size()` bytes per address.
+  values: Vec<u8>,
+  /// Contiguous blocks of memory captured by unknown initial value scan  
+  /// as `(start address, bytes)` pairs. [`None`] after the first refinement.  
+  /// Saved to separate binary file, see `ScanSession::save()`.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  #[cfg_attr(feature = "serde-support", serde(skip))]
+  snapshot: Option<Vec<(usize, Vec<u8>)>>,
+  /// Initial scan and refinement passes applied so far.
+  history: Vec<ScanRefinement>,
+  /// Whether refinements read only pages written since the previous pass,  
+  /// see `ScanSession::track_soft_dirty()`. Not saved, as soft-dirty bits  
+  /// of the process may be cleared by anyone before the session is loaded.
+  #[cfg_attr(feature = "serde-support", serde(skip))]
+  soft_dirty: bool,
+}
+
+impl ScanSession {
+  /// ScanSession object constructor. Performs initial scan for `value`  
+  /// in all readable and writeable memory regions of `process`.  
+  /// Numbers are looked for only at addresses aligned to their size.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `ScanSession::new();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, ScanRefinement, ScanSession, ScanValue};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let mut session = ScanSession::new(&ctx, &ScanValue::I32(100))?;
+  ///   // ... player takes some damage ...
+  ///   session.refine(&ctx, &ScanRefinement::Decreased)?;
+  ///   // ... player heals to full health ...
+  ///   session.refine(&ctx, &ScanRefinement::Exact(ScanValue::I32(100)))?;
+  ///
+  ///   for (address, value) in session.candidates() {
+  ///     println!("{:#x}: {:?}", address, value);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn new(process: &Process, value: &ScanValue) -> Result<ScanSession> {
+    let value_bytes = value.to_bytes();
+
+    if value_bytes.is_empty() {
+      return Err(anyhow!("Could not scan for empty value."));
+    }
+
+    let initial_scan = ScanRefinement::Exact(value.clone());
+    ScanSession::scan(process, value.value_type(), initial_scan, |bytes| {
+      bytes == &value_bytes[..]
+    })
+  }
+
+  /// ScanSession object constructor. Performs initial scan for values  
+  /// satisfying value-based `refinement` (that is `Exact`, `Approximately`  
+  /// or `Rounded`) in all readable and writeable memory regions of `process`.  
+  /// This makes it possible to start the session with fuzzy float scan.
+  ///
+  /// If `refinement` is not value-based or can not be applied to its value type, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `ScanSession::new_matching();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, ScanRefinement, ScanSession, ScanValue};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let mut session = ScanSession::new_matching(
+  ///     &ctx,
+  ///     &ScanRefinement::Approximately {
+  ///       value: ScanValue::F32(100.0),
+  ///       tolerance: 0.5,
+  ///     },
+  ///   )?;
+  ///   // ... player takes some damage ...
+  ///   session.refine(&ctx, &ScanRefinement::Decreased)?;
+  ///
+  ///   println!("candidates left: {}", session.len());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn new_matching(process: &Process, refinement: &ScanRefinement) -> Result<ScanSession> {
+    let value_type = match refinement {
+      ScanRefinement::Exact(value)
+      | ScanRefinement::Approximately { value, .. }
+      | ScanRefinement::Rounded(value) => value.value_type(),
+      _ => {
+        return Err(anyhow!(
+          "Could not start scan with refinement {:?}, it does not carry a value.",
+          refinement
+        ));
+      }
+    };
+
+    if value_type.size() == 0 {
+      return Err(anyhow!("Could not scan for empty value."));
+    }
+    check_refinement(value_type, refinement)?;
+
+    ScanSession::scan(process, value_type, refinement.clone(), |bytes| {
+      satisfies(value_type, bytes, bytes, refinement)
+    })
+  }
+
+  /// Performs initial scan for values of `value_type` for which  
+  /// `matches` returns **true** in all readable and writeable memory regions.  
+  /// `initial_scan` is recorded as the first entry of session history.
+  fn scan<F>(
+    process: &Process,
+    value_type: ScanValueType,
+    initial_scan: ScanRefinement,
+    matches: F,
+  ) -> Result<ScanSession>
+  where
+    F: Fn(&[u8]) -> bool,
+  {
+    let mut session = ScanSession {
+      value_type,
+      addresses: Vec::new(),
+      values: Vec::new(),
+      snapshot: None,
+      history: vec![initial_scan],
+      soft_dirty: false,
+    };
+
+    let size = value_type.size();
+    let alignment = value_type.alignment();
+
+    for region in process.get_memory_regions()? {
+      if !region.permissions.readable || !region.permissions.writeable {
+        continue;
+      }
+
+      // Chunks overlap by value size minus one, so values crossing chunk boundaries
+      // are not missed, and memory which could not be read is skipped.
+      process.walk_chunks(region.start, region.end, size - 1, |chunk_start, chunk| {
+        let mut address = chunk_start.next_multiple_of(alignment);
+        while address + size <= chunk_start + chunk.len() {
+          let current = &chunk[address - chunk_start..address - chunk_start + size];
+          if matches(current) {
+            session.addresses.push(address);
+            session.values.extend_from_slice(current);
+          }
+          address += alignment;
+        }
+        true
+      })?;
+    }
+
+    Ok(session)
+  }
+
+  /// ScanSession object constructor. Performs unknown initial value scan,  
+  /// that is captures snapshot of all readable and writeable memory regions  
+  /// of `process`, treating every address aligned to `value_type` size as candidate.  
+  /// The first refinement compares the snapshot with current memory chunk by chunk.
+  ///
+  /// If `value_type` is byte array, [`Err`] is returned, as there is no way  
+  /// to tell how byte array of unknown value could change.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `ScanSession::new_unknown();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, ScanRefinement, ScanSession, ScanValueType};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let mut session = ScanSession::new_unknown(&ctx, ScanValueType::F32)?;
+  ///   // ... player moves forward ...
+  ///   session.refine(&ctx, &ScanRefinement::Increased)?;
+  ///   // ... player stands still ...
+  ///   session.refine(&ctx, &ScanRefinement::Unchanged)?;
+  ///
+  ///   println!("candidates left: {}", session.len());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn new_unknown(process: &Process, value_type: ScanValueType) -> Result<ScanSession> {
+    if let ScanValueType::Bytes(_) = value_type {
+      return Err(anyhow!("Could not scan for byte array of unknown value."));
+    }
+
+    let mut snapshot: Vec<(usize, Vec<u8>)> = Vec::new();
+
+    for region in process.get_memory_regions()? {
+      if !region.permissions.readable || !region.permissions.writeable {
+        continue;
+      }
+
+      // Memory which could not be read is skipped, and chunks following each other
+      // are merged into a single block, so blocks are split only where it was.
+      process.walk_chunks(region.start, region.end, 0, |chunk_start, chunk| {
+        match snapshot.last_mut() {
+          Some((block_start, block)) if *block_start + block.len() == chunk_start => {
+            block.extend_from_slice(chunk)
+          }
+          _ => snapshot.push((chunk_start, chunk.to_vec())),
+        }
+        true
+      })?;
+    }
+
+    Ok(ScanSession {
+      value_type,
+      addresses: Vec::new(),
+      values: Vec::new(),
+      snapshot: Some(snapshot),
+      history: Vec::new(),
+      soft_dirty: false,
+    })
+  }
+
+  /// Applies `refinement` pass, keeping only candidates which current value  
+  /// satisfies it. Candidates which could not be read anymore are removed.  
+  /// Returns number of candidates left.
+  ///
+  /// If `refinement` can not be applied to session value type (e.g. `Increased`  
+  /// for byte arrays) or its value type differs from session one, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn refine(&mut self, process: &Process, refinement: &ScanRefinement) -> Result<usize> {
+    check_refinement(self.value_type, refinement)?;
+
+    let changed_pages = if self.soft_dirty {
+      Some(self.changed_pages(process)?)
+    } else {
+      None
+    };
+
+    self.history.push(refinement.clone());
+
+    if let Some(snapshot) = self.snapshot.take() {
+      self.refine_snapshot(process, refinement, &snapshot, changed_pages.as_deref());
+      return Ok(self.addresses.len());
+    }
+
+    let size = self.value_type.size();
+    let mut addresses: Vec<usize> = Vec::new();
+    let mut values: Vec<u8> = Vec::new();
+    let mut index = 0;
+
+    // Candidates are sorted by address, so the ones located close to each
+    // other are read at once instead of issuing a syscall for every single one.
+    while index < self.addresses.len() {
+      let chunk_start = self.addresses[index];
+      let chunk_end = (chunk_start + VALUE_SCAN_CHUNK_SIZE).max(chunk_start + size);
+      // Values in chunks without written pages are the same as previously.
+      let changed = is_changed(changed_pages.as_deref(), chunk_start, chunk_end);
+      // Pages which could not be read are skipped, so candidates after them are still read.
+      let chunk = if changed {
+        process
+          .read_memory_chunked(chunk_start, chunk_end - chunk_start, HolePolicy::ZeroFill)
+          .ok()
+      } else {
+        None
+      };
+
+      while index < self.addresses.len() && self.addresses[index] + size <= chunk_end {
+        let address = self.addresses[index];
+        let offset = address - chunk_start;
+        let previous = &self.values[index * size..(index + 1) * size];
+
+        let current = match &chunk {
+          _ if !changed => Some(previous),
+          Some(chunk) if !is_in_hole(chunk, address, address + size) => {
+            Some(&chunk.bytes[offset..offset + size])
+          }
+          _ => None,
+        };
+        if let Some(current) = current {
+          if satisfies(self.value_type, previous, current, refinement) {
+            addresses.push(self.addresses[index]);
+            values.extend_from_slice(current);
+          }
+        }
+
+        index += 1;
+      }
+    }
+
+    self.addresses = addresses;
+    self.values = values;
+
+    Ok(self.addresses.len())
+  }
+
+  /// Applies the first `refinement` pass of unknown initial value scan,  
+  /// comparing every aligned value of `snapshot` with current memory.  
+  /// Chunks without any of `changed_pages` are compared with themselves.
+  fn refine_snapshot(
+    &mut self,
+    process: &Process,
+    refinement: &ScanRefinement,
+    snapshot: &[(usize, Vec<u8>)],
+    changed_pages: Option<&[usize]>,
+  ) {
+    let size = self.value_type.size();
+
+    for (block_start, block) in snapshot {
+      for (chunk_index, previous_chunk) in block.chunks(VALUE_SCAN_CHUNK_SIZE).enumerate() {
+        let chunk_start = block_start + chunk_index * VALUE_SCAN_CHUNK_SIZE;
+        let chunk_end = chunk_start + previous_chunk.len();
+        let read;
+        let chunk = if is_changed(changed_pages, chunk_start, chunk_end) {
+          read = match process.read_memory_chunked(
+            chunk_start,
+            previous_chunk.len(),
+            HolePolicy::ZeroFill,
+          ) {
+            Ok(read) => read,
+            Err(_) => continue,
+          };
+          Some(&read)
+        } else {
+          None
+        };
+
+        let mut offset = 0;
+        while offset + size <= previous_chunk.len() {
+          let address = chunk_start + offset;
+          let previous = &previous_chunk[offset..offset + size];
+          let current = match chunk {
+            Some(chunk) if is_in_hole(chunk, address, address + size) => None,
+            Some(chunk) => Some(&chunk.bytes[offset..offset + size]),
+            None => Some(previous),
+          };
+
+          if let Some(current) = current {
+            if satisfies(self.value_type, previous, current, refinement) {
+              self.addresses.push(address);
+              self.values.extend_from_slice(current);
+            }
+          }
+
+          offset += size;
+        }
+      }
+    }
+  }
+
+  /// Enables incremental refinement based on soft-dirty bits of `process`  
+  /// (see `Process::clear_soft_dirty()`). Bits are cleared now and before  
+  /// reading memory in every refinement pass, so the next pass reads only  
+  /// chunks with pages written in the meantime, and reuses previous values  
+  /// for the rest. This makes refinement of huge sessions much faster,  
+  /// especially unknown initial value ones. Writes done in the short moment  
+  /// between checking and clearing bits at the start of a pass may be missed.
+  ///
+  /// Requires kernel built with **CONFIG_MEM_SOFT_DIRTY**, otherwise [`Err`]  
+  /// is returned. Tracking is not saved by `ScanSession::save()`.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, ScanRefinement, ScanSession, ScanValueType};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let mut session = ScanSession::new_unknown(&ctx, ScanValueType::I32)?;
+  ///   session.track_soft_dirty(&ctx)?;
+  ///   // ... player stands still ...
+  ///   session.refine(&ctx, &ScanRefinement::Unchanged)?;
+  ///
+  ///   println!("candidates left: {}", session.len());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn track_soft_dirty(&mut self, process: &Process) -> Result<()> {
+    process.clear_soft_dirty()?;
+    self.soft_dirty = true;
+    Ok(())
+  }
+
+  /// Returns sorted starting addresses of pages of session memory which may  
+  /// have changed since the previous pass, and clears soft-dirty bits.
+  fn changed_pages(&self, process: &Process) -> Result<Vec<usize>> {
+    let size = self.value_type.size();
+    let mut pages: Vec<usize> = Vec::new();
+
+    let mut append = |start: usize, end: usize| -> Result<()> {
+      for page in process.possibly_changed_pages(start, end - start)? {
+        if pages.last().is_none_or(|last| *last < page) {
+          pages.push(page);
+        }
+      }
+      Ok(())
+    };
+
+    match &self.snapshot {
+      Some(snapshot) => {
+        for (block_start, block) in snapshot {
+          append(*block_start, block_start + block.len())?;
+        }
+      }
+      None => {
+        let mut index = 0;
+        while index < self.addresses.len() {
+          let start = self.addresses[index];
+          let mut end = start + size;
+          while index < self.addresses.len()
+            && self.addresses[index] + size <= start + VALUE_SCAN_CHUNK_SIZE.max(size)
+          {
+            end = self.addresses[index] + size;
+            index += 1;
+          }
+          append(start, end)?;
+        }
+      }
+    }
+
+    process.clear_soft_dirty()?;
+    Ok(pages)
+  }
+
+  /// Saves session to file located at `path` as JSON, so it can be  
+  /// loaded later with `ScanSession::load()`, e.g. after restarting your tool.  
+  /// Snapshot of unknown initial value scan which was not refined yet  
+  /// (see `is_unknown()`) is saved to binary file next to it, named  
+  /// like the file with `.snapshot` suffix appended.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, ScanRefinement, ScanSession, ScanValue};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let session = ScanSession::new(&ctx, &ScanValue::I32(100))?;
+  ///   session.save("health.json")?;
+  ///
+  ///   // ... later ...
+  ///   let mut session = ScanSession::load("health.json")?;
+  ///   session.refine(&ctx, &ScanRefinement::Decreased)?;
+  ///   Ok(())
+  /// }
+  /// ```
+  #[cfg(feature = "serde-support")]
+  pub fn save<P: AsRef<path::Path>>(&self, path: P) -> Result<()> {
+    let path = path.as_ref();
+    let file = fs::File::create(path)?;
+    serde_json::to_writer(io::BufWriter::new(file), self)
+      .map_err(|error| anyhow!("Could not save scan session ({}).", error))?;
+
+    if let Some(snapshot) = &self.snapshot {
+      write_snapshot(&snapshot_path(path), snapshot)
+        .map_err(|error| anyhow!("Could not save scan session snapshot ({}).", error))?;
+    }
+    Ok(())
+  }
+
+  /// Loads session saved with `ScanSession::save()` from file located at `path`,  
+  /// together with its snapshot file if the session is unknown initial value scan.
+  #[cfg(feature = "serde-support")]
+  pub fn load<P: AsRef<path::Path>>(path: P) -> Result<ScanSession> {
+    let path = path.as_ref();
+    let file = fs::File::open(path)?;
+    let mut session: ScanSession = serde_json::from_reader(io::BufReader::new(file))
+      .map_err(|error| anyhow!("Could not load scan session ({}).", error))?;
+
+    // Every session except unknown initial value scan which was not refined yet
+    // records its initial scan in history.
+    if session.history.is_empty() {
+      let snapshot = read_snapshot(&snapshot_path(path))
+        .map_err(|error| anyhow!("Could not load scan session snapshot ({}).", error))?;
+      session.snapshot = Some(snapshot);
+    }
+    Ok(session)
+  }
+
+  /// Returns initial scan and refinement passes applied so far, in order.  
+  /// Unknown initial value scan is not recorded, as it does not carry any value.
+  pub fn history(&self) -> &[ScanRefinement] {
+    &self.history
+  }
+
+  /// Returns type of the value looked for.
+  pub fn value_type(&self) -> ScanValueType {
+    self.value_type
+  }
+
+  /// Returns number of candidates left.  
+  /// For unknown initial value scan which was not refined yet, this is  
+  /// the number of aligned addresses in the snapshot.
+  pub fn len(&self) -> usize {
+    match &self.snapshot {
+      Some(snapshot) => {
+        let size = self.value_type.size();
+        snapshot.iter().map(|(_, block)| block.len() / size).sum()
+      }
+      None => self.addresses.len(),
+    }
+  }
+
+  /// Returns **true** if there are no candidates left.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns **true** if session is unknown initial value scan  
+  /// which was not refined yet.
+  pub fn is_unknown(&self) -> bool {
+    self.snapshot.is_some()
+  }
+
+  /// Returns candidate addresses, sorted in ascending order.
+  ///
+  /// **NOTE**: For unknown initial value scan which was not refined yet  
+  /// this is empty, use `candidates()` to iterate over the snapshot.
+  pub fn addresses(&self) -> &[usize] {
+    &self.addresses
+  }
+
+  /// Returns iterator over candidate addresses and values seen during the last pass.
+  pub fn candidates<'a>(&'a self) -> impl Iterator<Item = (usize, ScanValue)> + 'a {
+    let size = self.value_type.size();
+    let snapshot_candidates =
+      self.snapshot.iter().flatten().flat_map(move |(block_start, block)| {
+        block
+          .chunks_exact(size)
+          .enumerate()
+          .map(move |(index, value)| (block_start + index * size, value))
+      });
+
+    self
+      .addresses
+      .iter()
+      .cloned()
+      .zip(self.values.chunks(size))
+      .chain(snapshot_candidates)
+      .map(move |(address, value)| (address, self.value_type.decode(value)))
+  }
+}
+
+/// Checks whether `refinement` can be applied to `value_type`.
+fn check_refinement(value_type: ScanValueType, refinement: &ScanRefinement) -> Result<()> {
+  if let ScanRefinement::Exact(value)
+  | ScanRefinement::IncreasedBy(value)
+  | ScanRefinement::DecreasedBy(value)
+  | ScanRefinement::Approximately { value, .. }
+  | ScanRefinement::Rounded(value) = refinement
+  {
+    if value.value_type() != value_type {
+      return Err(anyhow!(
+        "Refinement value type {:?} differs from session value type {:?}.",
+        value.value_type(),
+        value_type
+      ));
+    }
+  }
+
+  match (value_type, refinement) {
+    (ScanValueType::Bytes(_), ScanRefinement::Exact(_))
+    | (ScanValueType::Bytes(_), ScanRefinement::Changed)
+    | (ScanValueType::Bytes(_), ScanRefinement::Unchanged) => Ok(()),
+    (ScanValueType::Bytes(_), _) => Err(anyhow!(
+      "Refinement {:?} can not be applied to byte arrays.",
+      refinement
+    )),
+    _ => Ok(()),
+  }
+}
+
+/// Returns **true** if `current` value satisfies `refinement`  
+/// in relation to `previous` value.
+fn satisfies(
+  value_type: ScanValueType,
+  previous: &[u8],
+  current: &[u8],
+  refinement: &ScanRefinement,
+) -> bool {
+  let previous = value_type.decode(previous);
+  let current = value_type.decode(current);
+
+  match refinement {
+    ScanRefinement::Exact(value) => current == *value,
+    ScanRefinement::Changed => current != previous,
+    ScanRefinement::Unchanged => current == previous,
+    ScanRefinement::Increased => compare(&current, &previous) == Some(Ordering::Greater),
+    ScanRefinement::Decreased => compare(&current, &previous) == Some(Ordering::Less),
+    ScanRefinement::IncreasedBy(delta) => add(&previous, delta).is_some_and(|sum| current == sum),
+    ScanRefinement::DecreasedBy(delta) => add(&current, delta).is_some_and(|sum| previous == sum),
+    ScanRefinement::Approximately { value, tolerance } => match (as_f64(&current), as_f64(value)) {
+      (Some(current), Some(value)) => (current - value).abs() <= *tolerance,
+      _ => false,
+    },
+    ScanRefinement::Rounded(value) => match (as_f64(&current), as_f64(value)) {
+      (Some(current), Some(value)) => current.round() == value.round(),
+      _ => false,
+    },
+  }
+}
+
+/// Returns numeric value converted to `f64`.
+fn as_f64(value: &ScanValue) -> Option<f64> {
+  match value {
+    ScanValue::I32(value) => Some(f64::from(*value)),
+    ScanValue::I64(value) => Some(*value as f64),
+    ScanValue::F32(value) => Some(f64::from(*value)),
+    ScanValue::F64(value) => Some(*value),
+    ScanValue::Bytes(_) => None,
+  }
+}
+
+/// Compares two numeric values of the same type.
+fn compare(left: &ScanValue, right: &ScanValue) -> Option<Ordering> {
+  match (left, right) {
+    (ScanValue::I32(left), ScanValue::I32(right)) => left.partial_cmp(right),
+    (ScanValue::I64(left), ScanValue::I64(right)) => left.partial_cmp(right),
+    (ScanValue::F32(left), ScanValue::F32(right)) => left.partial_cmp(right),
+    (ScanValue::F64(left), ScanValue::F64(right)) => left.partial_cmp(right),
+    _ => None,
+  }
+}
+
+/// Adds two numeric values of the same type, integers wrap around on overflow.
+fn add(left: &ScanValue, right: &ScanValue) -> Option<ScanValue> {
+  match (left, right) {
+    (ScanValue::I32(left), ScanValue::I32(right)) => {
+      Some(ScanValue::I32(left.wrapping_add(*right)))
+    }
+    (ScanValue::I64(left), ScanValue::I64(right)) => {
+      Some(ScanValue::I64(left.wrapping_add(*right)))
+    }
+    (ScanValue::F32(left), ScanValue::F32(right)) => Some(ScanValue::F32(left + right)),
+    (ScanValue::F64(left), ScanValue::F64(right)) => Some(ScanValue::F64(left + right)),
+    _ => None,
+  }
+}
+
+/// Returns **true** if memory from `start` to `end` (exclusive) contains any  
+/// of sorted `changed_pages`, or if changed pages are not tracked at all.
+fn is_changed(changed_pages: Option<&[usize]>, start: usize, end: usize) -> bool {
+  let changed_pages = match changed_pages {
+    Some(changed_pages) => changed_pages,
+    None => return true,
+  };
+  let page_size = page_size();
+  let index = changed_pages.partition_point(|page| page + page_size <= start);
+  changed_pages.get(index).is_some_and(|page| *page < end)
+}
+
+/// Returns **true** if any byte between `start` and `end` is in one of holes of `read`.
+fn is_in_hole(read: &ChunkedRead, start: usize, end: usize) -> bool {
+  read
+    .holes
+    .iter()
+    .any(|(hole_start, hole_end)| *hole_start < end && start < *hole_end)
+}
+
+/// Returns path of file snapshot of session saved at `path` is stored in.
+#[cfg(feature = "serde-support")]
+fn snapshot_path(path: &path::Path) -> path::PathBuf {
+  let mut snapshot_path = path.as_os_str().to_os_string();
+  snapshot_path.push(".snapshot");
+  snapshot_path.into()
+}
+
+/// Writes `snapshot` blocks to file located at `path`, every block as its start  
+/// address and length (little-endian 64-bit integers) followed by its bytes.
+#[cfg(feature = "serde-support")]
+fn write_snapshot(path: &path::Path, snapshot: &[(usize, Vec<u8>)]) -> io::Result<()> {
+  let mut writer = io::BufWriter::new(fs::File::create(path)?);
+  for (block_start, block) in snapshot {
+    writer.write_all(&(*block_start as u64).to_le_bytes())?;
+    writer.write_all(&(block.len() as u64).to_le_bytes())?;
+    writer.write_all(block)?;
+  }
+  writer.flush()
+}
+
+/// Reads snapshot blocks written by `write_snapshot()` from file located at `path`.
+#[cfg(feature = "serde-support")]
+fn read_snapshot(path: &path::Path) -> io::Result<Vec<(usize, Vec<u8>)>> {
+  let data = fs::read(path)?;
+  let invalid = || io::Error::new(io::ErrorKind::InvalidData, "snapshot file is truncated");
+  let mut snapshot: Vec<(usize, Vec<u8>)> = Vec::new();
+  let mut offset = 0;
+
+  while offset < data.len() {
+    let header = data.get(offset..offset + 16).ok_or_else(invalid)?;
+    let block_start = u64::from_le_bytes(header[..8].try_into().unwrap()) as usize;
+    let block_length = u64::from_le_bytes(header[8..].try_into().unwrap()) as usize;
+    offset += 16;
+
+    // Length is checked against the file, so corrupted one does not exhaust memory.
+    if block_length > data.len() - offset {
+      return Err(invalid());
+    }
+    snapshot.push((block_start, data[offset..offset + block_length].to_vec()));
+    offset += block_length;
+  }
+
+  Ok(snapshot)
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+  use super::super::{MockMemory, Process};
+  use super::{ScanRefinement, ScanSession, ScanValue, ScanValueType};
+
+  /// Returns process with `value` at the start of the first and the third page  
+  /// of heap, which second page can not be read.
+  fn process_with_hole(value: u32) -> Process {
+    let mut heap = vec![0; 0x3000];
+    heap[0x100..0x104].copy_from_slice(&value.to_ne_bytes());
+    heap[0x2100..0x2104].copy_from_slice(&value.to_ne_bytes());
+    MockMemory::new()
+      .region(0x10000000, "rw-p", Some("[heap]"), heap)
+      .hole(0x10001000, 0x1000)
+      .into_process("game")
+      .unwrap()
+  }
+
+  #[test]
+  fn scans_past_holes() {
+    let process = process_with_hole(1337);
+    let session = ScanSession::new(&process, &ScanValue::I32(1337)).unwrap();
+
+    assert_eq!(session.addresses(), &[0x10000100, 0x10002100]);
+  }
+
+  #[test]
+  fn captures_snapshot_past_holes() {
+    let process = process_with_hole(1337);
+    let mut session = ScanSession::new_unknown(&process, ScanValueType::I32).unwrap();
+    process.write::<u32>(0x10002100, &1338).unwrap();
+    session.refine(&process, &ScanRefinement::Increased).unwrap();
+
+    assert_eq!(session.addresses(), &[0x10002100]);
+    assert_eq!(
+      session.candidates().collect::<Vec<_>>(),
+      vec![(0x10002100, ScanValue::I32(1338))]
+    );
+  }
+
+  #[test]
+  fn rejects_empty_values() {
+    let process = MockMemory::new()
+      .region(0x10000000, "rw-p", Some("[heap]"), vec![0; 0x1000])
+      .into_process("game")
+      .unwrap();
+    let empty = ScanValue::Bytes(Vec::new());
+
+    assert!(ScanSession::new(&process, &empty).is_err());
+    assert!(ScanSession::new_matching(&process, &ScanRefinement::Exact(empty)).is_err());
+
+    let session = ScanSession::new_matching(
+      &process,
+      &ScanRefinement::Exact(ScanValue::Bytes(vec![0; 2])),
+    )
+    .unwrap();
+    assert_eq!(session.len(), 0xfff);
+    assert_eq!(session.candidates().count(), 0xfff);
+  }
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use std::collections::HashSet;
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+use super::got_hook::GOT_RELOCATION_TYPES;
+use super::{MemoryRegion, Process, RegionKind};
+
+/// Prefix of path of files created with `memfd_create()`, as shown in maps.
+const MEMFD_PATH_PREFIX: &str = "/memfd:";
+
+/// Kind of suspicious mapping reported by `Process::security_findings()`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub enum SecurityFindingKind {
+  /// Region which is both writeable and executable, so code  
+  /// can be written into it and run without changing protection.
+  WritableExecutable,
+  /// Executable region which is not mapped from a file, including  
+  /// executable heap and stack, typical for injected shellcode.
+  AnonymousExecutable,
+  /// Executable region mapped from file created with `memfd_create()`,  
+  /// which is used to run code that never touched the disk.
+  MemfdExecutable,
+  /// Writeable region containing GOT slots of a module (only x86-64  
+  /// and AArch64), so imported functions can be redirected by overwriting  
+  /// them. Modules linked without full RELRO (`-z now`) have such regions.
+  WritableGot,
+}
+
+/// Suspicious mapping found by `Process::security_findings()`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde-support", derive(::serde::Serialize))]
+pub struct SecurityFinding<'a> {
+  /// What is suspicious about the region.
+  pub kind: SecurityFindingKind,
+  /// Region the finding is about.
+  pub region: &'a MemoryRegion,
+}
+
+impl Process {
+  /// Returns findings about suspicious memory regions of the process: regions  
+  /// which are both writeable and executable, anonymous executable regions,  
+  /// executable regions mapped from memfd files and writeable regions  
+  /// containing GOT slots. Region can be reported multiple times with  
+  /// different kinds. Findings are sorted by address of their regions.
+  ///
+  /// Findings are not necessarily signs of malicious activity,  
+  /// e.g. JIT compilers legitimately create anonymous executable regions.  
+  /// Modules which ELF file could not be read are skipped when looking  
+  /// for writeable GOT slots.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `security_findings();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for finding in ctx.security_findings()? {
+  ///     println!("{:?} {}", finding.kind, finding.region);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `WritableExecutable 7f3a5c000000-7f3a5c021000 rwxp    132.0 KiB`
+  pub fn security_findings(&self) -> Result<Vec<SecurityFinding<'_>>> {
+    let got_regions = self.writable_got_regions()?;
+    let mut findings: Vec<SecurityFinding> = Vec::new();
+
+    for region in self.get_memory_regions()? {
+      let permissions = &region.permissions;
+      let mut kinds: Vec<SecurityFindingKind> = Vec::new();
+
+      if permissions.writeable && permissions.executable {
+        kinds.push(SecurityFindingKind::WritableExecutable);
+      }
+      if permissions.executable {
+        match region.kind() {
+          RegionKind::Anonymous | RegionKind::Heap | RegionKind::Stack => {
+            kinds.push(SecurityFindingKind::AnonymousExecutable)
+          }
+          _ if is_memfd(region) => kinds.push(SecurityFindingKind::MemfdExecutable),
+          _ => {}
+        }
+      }
+      if got_regions.contains(&region.start) {
+        kinds.push(SecurityFindingKind::WritableGot);
+      }
+
+      findings.extend(kinds.into_iter().map(|kind| SecurityFinding { kind, region }));
+    }
+
+    Ok(findings)
+  }
+
+  /// Returns start addresses of writeable regions containing GOT slots  
+  /// of any module, that is targets of relocations used by `hook_got()`.
+  #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+  fn writable_got_regions(&self) -> Result<HashSet<usize>> {
+    let mut regions: HashSet<usize> = HashSet::new();
+
+    for module in self.modules()? {
+      if !module.regions().iter().any(|region| region.permissions.writeable) {
+        continue;
+      }
+      let elf = match module.read_elf() {
+        Ok(elf) => elf,
+        Err(_) => continue,
+      };
+      let load_bias = match module.load_bias_of(&elf) {
+        Ok(load_bias) => load_bias,
+        Err(_) => continue,
+      };
+
+      for target in elf.all_relocation_targets(&GOT_RELOCATION_TYPES) {
+        let slot = load_bias.wrapping_add(target);
+        if let Some(region) = module.regions().iter().find(|region| region.contains(slot)) {
+          if region.permissions.writeable {
+            regions.insert(region.start);
+          }
+        }
+      }
+    }
+
+    Ok(regions)
+  }
+
+  #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+  fn writable_got_regions(&self) -> Result<HashSet<usize>> {
+    Ok(HashSet::new())
+  }
+}
+
+/// Returns **true** if `region` is mapped from file created with `memfd_create()`.
+fn is_memfd(region: &MemoryRegion) -> bool {
+  region.path.as_ref().is_some_and(|path| path.starts_with(MEMFD_PATH_PREFIX))
+}
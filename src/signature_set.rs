@@ -0,0 +1,255 @@
+use anyhow::Result;
+#[cfg(feature = "serde-support")]
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use super::{Process, Signature};
+
+/// Signature entry of JSON signature database, either bare pattern  
+/// or pattern together with module it is searched in.
+#[cfg(feature = "serde-support")]
+#[derive(::serde::Deserialize)]
+#[serde(untagged)]
+enum SignatureDefinition {
+  Pattern(String),
+  Scoped {
+    pattern: String,
+    module: Option<String>,
+  },
+}
+
+#[derive(Clone, Debug)]
+struct SignatureEntry {
+  name: String,
+  /// Module signature is searched in, whole memory if [`None`].
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  module: Option<String>,
+  signature: Signature,
+}
+
+/// Set of named signatures resolved together with `Process::resolve_signatures()`,  
+/// which reads memory only once for all of them, instead of once per signature.  
+/// Signatures are added with `add()` and `add_in_module()`, or loaded from JSON  
+/// with `from_json()`.
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::{Process, Signature, SignatureSet};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut ctx = Process::new("current_process_name")?;
+///   ctx.parse_maps()?;
+///
+///   let mut signatures = SignatureSet::new();
+///   signatures.add_in_module("update_health", "game", Signature::new("55 48 89 E5 89 7D FC")?);
+///   signatures.add_in_module("local_player", "game", Signature::new("48 8B 05 ?? ?? ?? ?? 48 85 C0")?);
+///   signatures.add("entity_list", Signature::new("E8 ?? ?? ?? ?? 48 8D 0D")?);
+///
+///   let resolved = ctx.resolve_signatures(&signatures)?;
+///   println!("update_health: {:#x}", resolved.get("update_health")?);
+///   for (name, error) in &resolved.errors {
+///     println!("{}: {}", name, error);
+///   }
+///   Ok(())
+/// }
+/// ```
+/// This prints output like:  
+/// `update_health: 0x55d4c0e3a6f0`  
+/// `entity_list: Could not find signature entity_list.`
+#[derive(Clone, Default, Debug)]
+pub struct SignatureSet {
+  entries: Vec<SignatureEntry>,
+}
+
+impl SignatureSet {
+  /// SignatureSet object constructor. Creates empty set.
+  pub fn new() -> SignatureSet {
+    SignatureSet::default()
+  }
+
+  /// Loads signatures from JSON object, which maps names either to IDA-style  
+  /// patterns (see `Signature::new()`) searched in whole memory, or to objects  
+  /// with `pattern` and `module` searched in, e.g.  
+  /// `{"entity_list": "E8 ?? ?? ?? ?? 48 8D 0D", "update_health": {"pattern": "55 48 89 E5", "module": "game"}}`.  
+  /// Requires `serde-support` feature. If JSON or any of patterns  
+  /// is invalid, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  #[cfg(feature = "serde-support")]
+  pub fn from_json(json: &str) -> Result<SignatureSet> {
+    let definitions: BTreeMap<String, SignatureDefinition> = serde_json::from_str(json)
+      .map_err(|error| anyhow!("Could not parse signature database ({}).", error))?;
+
+    let mut signatures = SignatureSet::new();
+    for (name, definition) in definitions {
+      let (pattern, module) = match definition {
+        SignatureDefinition::Pattern(pattern) => (pattern, None),
+        SignatureDefinition::Scoped { pattern, module } => (pattern, module),
+      };
+      let signature = Signature::new(&pattern)
+        .map_err(|error| anyhow!("Could not parse signature {} ({}).", name, error))?;
+      signatures.entries.push(SignatureEntry {
+        name,
+        module,
+        signature,
+      });
+    }
+
+    Ok(signatures)
+  }
+
+  /// Adds `signature` named `name`, searched in all readable memory regions.  
+  /// Signature with the same name is replaced.
+  pub fn add(&mut self, name: &str, signature: Signature) -> &mut SignatureSet {
+    self.insert(name, None, signature)
+  }
+
+  /// Adds `signature` named `name`, searched only in mappings of file  
+  /// which name equals `module_name` (see `Process::scan_module()`).  
+  /// Signature with the same name is replaced.
+  pub fn add_in_module(
+    &mut self,
+    name: &str,
+    module_name: &str,
+    signature: Signature,
+  ) -> &mut SignatureSet {
+    self.insert(name, Some(module_name.to_string()), signature)
+  }
+
+  /// Returns number of signatures in the set.
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  /// Returns **true** if the set contains no signatures.
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  fn insert(
+    &mut self,
+    name: &str,
+    module: Option<String>,
+    signature: Signature,
+  ) -> &mut SignatureSet {
+    self.entries.retain(|entry| entry.name != name);
+    self.entries.push(SignatureEntry {
+      name: name.to_string(),
+      module,
+      signature,
+    });
+    self
+  }
+}
+
+/// Result of `Process::resolve_signatures()`. Every signature of the set  
+/// is either in `addresses`, or in `errors` with reason why it was not resolved.
+#[derive(Debug, Default)]
+pub struct ResolvedSignatures {
+  /// Addresses of the first match of every found signature, by name.
+  pub addresses: HashMap<String, usize>,
+  /// Errors of signatures which were not found, or which module is not mapped.
+  pub errors: HashMap<String, anyhow::Error>,
+}
+
+impl ResolvedSignatures {
+  /// Returns address of signature named `name`. If it was not resolved,  
+  /// or there is no such signature in the set, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn get(&self, name: &str) -> Result<usize> {
+    if let Some(address) = self.addresses.get(name) {
+      return Ok(*address);
+    }
+
+    match self.errors.get(name) {
+      Some(error) => Err(anyhow!("{}", error)),
+      None => Err(anyhow!("Could not find signature {} in the set.", name)),
+    }
+  }
+}
+
+impl Process {
+  /// Resolves all signatures of `signatures` in a single pass over memory,  
+  /// that is every readable region is read once, in chunks, and searched  
+  /// for all signatures which are not found yet and apply to the region.  
+  /// Address of the first match of every signature is returned. Signatures  
+  /// which were not found do not fail the whole resolution, they are  
+  /// reported in `ResolvedSignatures::errors` instead.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `resolve_signatures();`.
+  pub fn resolve_signatures(&self, signatures: &SignatureSet) -> Result<ResolvedSignatures> {
+    let regions = self.get_memory_regions()?;
+    let mut resolved = ResolvedSignatures::default();
+
+    // Region starts of every module signatures are scoped to.
+    let mut module_regions: HashMap<&str, Vec<usize>> = HashMap::new();
+    for module in signatures.entries.iter().filter_map(|entry| entry.module.as_deref()) {
+      let starts = self
+        .region_find_all_by_name(module, None)?
+        .iter()
+        .map(|region| region.start)
+        .collect();
+      module_regions.insert(module, starts);
+    }
+
+    let mut pending: Vec<&SignatureEntry> = Vec::new();
+    for entry in &signatures.entries {
+      match &entry.module {
+        Some(module) if module_regions[module.as_str()].is_empty() => {
+          resolved
+            .errors
+            .insert(entry.name.clone(), anyhow!("Could not find {}.", module));
+        }
+        _ => pending.push(entry),
+      }
+    }
+
+    for region in regions.iter().filter(|region| region.permissions.readable) {
+      if pending.is_empty() {
+        break;
+      }
+      let mut region_entries: Vec<&SignatureEntry> = pending
+        .iter()
+        .filter(|entry| match &entry.module {
+          Some(module) => module_regions[module.as_str()].contains(&region.start),
+          None => true,
+        })
+        .cloned()
+        .collect();
+      if region_entries.is_empty() {
+        continue;
+      }
+
+      // Consecutive chunks overlap by length of the longest signature minus one,
+      // so matches crossing chunk boundaries are not missed.
+      let overlap = region_entries.iter().map(|entry| entry.signature.len()).max().unwrap_or(1) - 1;
+
+      // Regions which could not be read are skipped, like in `scan()`.
+      let _ = self.walk_chunks(region.start, region.end, overlap, |chunk_start, chunk| {
+        region_entries.retain(|entry| match entry.signature.find_in(chunk, true).first() {
+          Some(offset) => {
+            resolved.addresses.insert(entry.name.clone(), chunk_start + offset);
+            false
+          }
+          None => true,
+        });
+        !region_entries.is_empty()
+      });
+
+      pending.retain(|entry| !resolved.addresses.contains_key(&entry.name));
+    }
+
+    for entry in pending {
+      resolved.errors.insert(
+        entry.name.clone(),
+        anyhow!("Could not find signature {}.", entry.name),
+      );
+    }
+
+    Ok(resolved)
+  }
+}
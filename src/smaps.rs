@@ -0,0 +1,320 @@
+use anyhow::Result;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path;
+
+use super::maps::{parse_maps_line, MainExecutable};
+use super::{MemoryRegion, Process};
+
+/// Memory region with statistics from `/proc/\[pid\]/smaps` file.  
+/// All sizes are in bytes.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct SmapsRegion {
+  /// Region described by the row of `/proc/\[pid\]/maps` file.
+  pub region: MemoryRegion,
+  /// Size of the region, the same as `region.end - region.start`.
+  pub size: usize,
+  /// Resident set size, that is amount of region memory currently in RAM.
+  pub rss: usize,
+  /// Proportional set size, that is process share of resident memory.  
+  /// Pages shared with other processes are divided by number of sharers.
+  pub pss: usize,
+  /// Amount of resident memory shared with other processes, not modified.
+  pub shared_clean: usize,
+  /// Amount of resident memory shared with other processes, modified.
+  pub shared_dirty: usize,
+  /// Amount of resident memory used only by this process, not modified.
+  pub private_clean: usize,
+  /// Amount of resident memory used only by this process, modified.
+  pub private_dirty: usize,
+  /// Amount of anonymous memory of the region which is swapped out.
+  pub swap: usize,
+  /// Two letter codes of kernel flags associated with the region, like `rd`,  
+  /// `wr`, `ex` or `sd` (soft-dirty). See **proc(5)** for the full list.
+  pub vm_flags: Vec<String>,
+}
+
+impl SmapsRegion {
+  /// Returns **true** if region has `vm_flag` kernel flag set.
+  pub fn has_vm_flag(&self, vm_flag: &str) -> bool {
+    self.vm_flags.iter().any(|flag| flag == vm_flag)
+  }
+}
+
+/// Memory usage of single module (file mapped into memory) of the process,  
+/// summed over all its regions. All sizes are in bytes.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct ModuleFootprint {
+  /// Path of the mapped file.
+  pub path: String,
+  /// Virtual size of all regions of the module.
+  pub size: usize,
+  /// Resident set size of the module.
+  pub rss: usize,
+  /// Proportional set size of the module.
+  pub pss: usize,
+  /// Amount of module memory which is swapped out.
+  pub swap: usize,
+}
+
+/// Memory usage totals of the process, see `Process::memory_stats()`.  
+/// All sizes are in bytes.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct MemoryStats {
+  /// Virtual size of all memory regions.
+  pub size: usize,
+  /// Resident set size, that is amount of memory currently in RAM.
+  pub rss: usize,
+  /// Proportional set size, that is process share of resident memory.
+  pub pss: usize,
+  /// Amount of resident memory shared with other processes.
+  pub shared: usize,
+  /// Amount of resident memory used only by this process.
+  pub private: usize,
+  /// Amount of memory which is swapped out.
+  pub swap: usize,
+  /// Resident memory of regions not backed by files, like heap and stacks.
+  pub anonymous_rss: usize,
+  /// Resident memory of regions backed by files, like executables and libraries.
+  pub file_rss: usize,
+  /// Memory usage of every module, in order of its first region address.
+  pub modules: Vec<ModuleFootprint>,
+}
+
+impl Process {
+  /// Reads `/proc/\[pid\]/smaps` file and returns all memory regions  
+  /// together with their memory usage statistics. Reading this file  
+  /// is much slower than reading `/proc/\[pid\]/maps`, since kernel has  
+  /// to walk page tables of every region, so regions are not stored  
+  /// in `self.memory_regions`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   for smaps_region in ctx.parse_smaps()? {
+  ///     println!(
+  ///       "{:#x} rss: {} pss: {} swap: {}",
+  ///       smaps_region.region.start, smaps_region.rss, smaps_region.pss, smaps_region.swap
+  ///     );
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x55d0c0ffe000 rss: 8192 pss: 8192 swap: 0`
+  pub fn parse_smaps(&self) -> Result<Vec<SmapsRegion>> {
+    let smaps_path = path::Path::new("/proc/").join(self.get_pid().to_string()).join("smaps");
+
+    let file = fs::File::open(smaps_path)
+      .map_err(|error| anyhow!("Could not open smaps file ({}).", error))?;
+    let mut reader = io::BufReader::new(file);
+    let mut buffer = Vec::<u8>::new();
+    let mut smaps_regions: Vec<SmapsRegion> = Vec::new();
+    let mut main_executable = MainExecutable::default();
+
+    loop {
+      buffer.clear();
+      if reader
+        .read_until(b'\n', &mut buffer)
+        .map_err(|error| anyhow!("Could not read smaps file ({}).", error))?
+        == 0
+      {
+        break;
+      }
+
+      let line = String::from_utf8_lossy(&buffer);
+      let (key, value) = match smaps_field(&line) {
+        Some(field) => field,
+        None => {
+          let mut region = parse_maps_line(&buffer)?;
+          main_executable.mark(&mut region);
+          smaps_regions.push(SmapsRegion {
+            size: region.size(),
+            region,
+            rss: 0,
+            pss: 0,
+            shared_clean: 0,
+            shared_dirty: 0,
+            private_clean: 0,
+            private_dirty: 0,
+            swap: 0,
+            vm_flags: Vec::new(),
+          });
+          continue;
+        }
+      };
+
+      let smaps_region = smaps_regions
+        .last_mut()
+        .ok_or_else(|| anyhow!("Could not parse smaps file (field before region header)."))?;
+
+      match key {
+        "Rss" => smaps_region.rss = parse_kilobytes(value)?,
+        "Pss" => smaps_region.pss = parse_kilobytes(value)?,
+        "Shared_Clean" => smaps_region.shared_clean = parse_kilobytes(value)?,
+        "Shared_Dirty" => smaps_region.shared_dirty = parse_kilobytes(value)?,
+        "Private_Clean" => smaps_region.private_clean = parse_kilobytes(value)?,
+        "Private_Dirty" => smaps_region.private_dirty = parse_kilobytes(value)?,
+        "Swap" => smaps_region.swap = parse_kilobytes(value)?,
+        "VmFlags" => smaps_region.vm_flags = value.split_whitespace().map(String::from).collect(),
+        _ => continue,
+      }
+    }
+
+    Ok(smaps_regions)
+  }
+
+  /// Reads `/proc/\[pid\]/smaps` file (see `parse_smaps()`) and aggregates  
+  /// memory usage of all regions into totals of the whole process, split into  
+  /// anonymous and file-backed memory, and per module footprint. Useful  
+  /// for monitoring, when statistics of single regions are not needed.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let stats = ctx.memory_stats()?;
+  ///   println!("rss: {} anonymous: {} swap: {}", stats.rss, stats.anonymous_rss, stats.swap);
+  ///   for module in stats.modules.iter().filter(|module| module.rss > 0x100000) {
+  ///     println!("{} rss: {}", module.path, module.rss);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `rss: 5361664 anonymous: 1327104 swap: 0`  
+  /// `/usr/lib/libc.so.6 rss: 1781760`
+  pub fn memory_stats(&self) -> Result<MemoryStats> {
+    let mut stats = MemoryStats {
+      size: 0,
+      rss: 0,
+      pss: 0,
+      shared: 0,
+      private: 0,
+      swap: 0,
+      anonymous_rss: 0,
+      file_rss: 0,
+      modules: Vec::new(),
+    };
+
+    for smaps_region in self.parse_smaps()? {
+      stats.size += smaps_region.size;
+      stats.rss += smaps_region.rss;
+      stats.pss += smaps_region.pss;
+      stats.shared += smaps_region.shared_clean + smaps_region.shared_dirty;
+      stats.private += smaps_region.private_clean + smaps_region.private_dirty;
+      stats.swap += smaps_region.swap;
+
+      let path = match &smaps_region.region.path {
+        Some(path) if path.starts_with('/') => path,
+        _ => {
+          stats.anonymous_rss += smaps_region.rss;
+          continue;
+        }
+      };
+      stats.file_rss += smaps_region.rss;
+
+      let index = match stats.modules.iter().position(|module| &module.path == path) {
+        Some(index) => index,
+        None => {
+          stats.modules.push(ModuleFootprint {
+            path: path.clone(),
+            size: 0,
+            rss: 0,
+            pss: 0,
+            swap: 0,
+          });
+          stats.modules.len() - 1
+        }
+      };
+      let module = &mut stats.modules[index];
+      module.size += smaps_region.size;
+      module.rss += smaps_region.rss;
+      module.pss += smaps_region.pss;
+      module.swap += smaps_region.swap;
+    }
+
+    Ok(stats)
+  }
+}
+
+/// Splits smaps field line like `Rss:   8 kB` into key and value.  
+/// Returns [`None`] for region header lines.
+///
+/// [`None`]: https://doc.rust-lang.org/std/option/
+fn smaps_field(line: &str) -> Option<(&str, &str)> {
+  let first_field = line.split_whitespace().next()?;
+  let key = first_field.strip_suffix(':')?;
+  Some((key, line[first_field.len()..].trim()))
+}
+
+/// Parses smaps size value like `8 kB` and returns it in bytes.
+fn parse_kilobytes(value: &str) -> Result<usize> {
+  let kilobytes = value.trim_end_matches("kB").trim();
+  kilobytes
+    .parse::<usize>()
+    .map(|kilobytes| kilobytes * 1024)
+    .map_err(|error| anyhow!("Could not parse smaps value {} ({}).", value, error))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse_kilobytes, smaps_field};
+
+  #[test]
+  fn splits_fields() {
+    assert_eq!(
+      smaps_field("Rss:                 132 kB"),
+      Some(("Rss", "132 kB"))
+    );
+    assert_eq!(smaps_field("THPeligible:    0"), Some(("THPeligible", "0")));
+    assert_eq!(
+      smaps_field("VmFlags: rd wr mr mw me ac sd"),
+      Some(("VmFlags", "rd wr mr mw me ac sd"))
+    );
+  }
+
+  #[test]
+  fn skips_region_headers() {
+    assert_eq!(
+      smaps_field("7f3a5c000000-7f3a5c021000 rw-p 00000000 00:00 0   [heap]"),
+      None
+    );
+    assert_eq!(
+      smaps_field("55d0c1000000-55d0c1021000 r-xp 00000000 fd:01 77   /usr/bin/a: b"),
+      None
+    );
+    assert_eq!(smaps_field(""), None);
+  }
+
+  #[test]
+  fn parses_kilobytes() {
+    assert_eq!(parse_kilobytes("132 kB").unwrap(), 132 * 1024);
+    assert_eq!(parse_kilobytes("0 kB").unwrap(), 0);
+    assert_eq!(parse_kilobytes("4").unwrap(), 4 * 1024);
+    assert!(parse_kilobytes("").is_err());
+    assert!(parse_kilobytes("-4 kB").is_err());
+    assert!(parse_kilobytes("rd wr").is_err());
+  }
+}
@@ -0,0 +1,285 @@
+use anyhow::Result;
+use std::time::Instant;
+
+use super::{MemoryRegion, Process, RegionKind};
+
+/// Size of chunks in which memory is read while capturing snapshot.
+const SNAPSHOT_CHUNK_SIZE: usize = 0x10000;
+
+/// Range of bytes that differ between two snapshots.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub struct ChangedRange {
+  /// Address of the first changed byte.
+  pub start: usize,
+  /// Address one past the last changed byte.
+  pub end: usize,
+  /// Bytes in the range, as captured by the older snapshot.
+  pub old_bytes: Vec<u8>,
+  /// Bytes in the range, as captured by the newer snapshot.
+  pub new_bytes: Vec<u8>,
+}
+
+impl ChangedRange {
+  /// Returns number of changed bytes.
+  pub fn len(&self) -> usize {
+    self.end - self.start
+  }
+
+  /// Returns **true** if range does not contain any bytes.
+  pub fn is_empty(&self) -> bool {
+    self.start == self.end
+  }
+}
+
+/// Copy of memory region contents captured at some point in time.
+#[derive(Clone, Debug)]
+pub struct RegionSnapshot {
+  start: usize,
+  bytes: Vec<u8>,
+  captured_at: Instant,
+}
+
+impl RegionSnapshot {
+  /// Reads whole `region` of `process` memory. If some page of the region  
+  /// can not be read, snapshot ends right before it.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RegionSnapshot};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let heap = ctx.region_find_first_by_name("[heap]", None)?;
+  ///   let before = RegionSnapshot::capture(&ctx, heap)?;
+  ///   // ... do something in target process ...
+  ///   let after = RegionSnapshot::capture(&ctx, heap)?;
+  ///
+  ///   for changed in before.diff(&after) {
+  ///     println!("{:#x}: {:02x?} -> {:02x?}", changed.start, changed.old_bytes, changed.new_bytes);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x55d0c0ffee08: [05] -> [04]`
+  pub fn capture(process: &Process, region: &MemoryRegion) -> Result<RegionSnapshot> {
+    let captured_at = Instant::now();
+    let mut bytes: Vec<u8> = Vec::with_capacity(region.size());
+
+    let mut chunk_start = region.start;
+    while chunk_start < region.end {
+      let chunk_length = SNAPSHOT_CHUNK_SIZE.min(region.end - chunk_start);
+      let (chunk, bytes_read) = process.read_memory_partial(chunk_start, chunk_length)?;
+      bytes.extend_from_slice(&chunk[..bytes_read]);
+
+      if bytes_read < chunk_length {
+        break;
+      }
+      chunk_start += chunk_length;
+    }
+
+    Ok(RegionSnapshot {
+      start: region.start,
+      bytes,
+      captured_at,
+    })
+  }
+
+  /// Returns address of the first captured byte.
+  pub fn start(&self) -> usize {
+    self.start
+  }
+
+  /// Returns address one past the last captured byte.
+  pub fn end(&self) -> usize {
+    self.start + self.bytes.len()
+  }
+
+  /// Returns captured bytes.
+  pub fn bytes(&self) -> &[u8] {
+    &self.bytes
+  }
+
+  /// Returns time at which capture started.
+  pub fn captured_at(&self) -> Instant {
+    self.captured_at
+  }
+
+  /// Returns Shannon entropy of captured bytes in bits per byte, between 0.0  
+  /// for snapshot filled with single value and 8.0 for uniformly random bytes.  
+  /// Compressed or encrypted data (e.g. packed code) usually scores above 7.5,  
+  /// while code and plain data rarely exceed 6.5. Empty snapshot has entropy 0.0.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, RegionSnapshot};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let heap = ctx.region_find_first_by_name("[heap]", None)?;
+  ///   println!("heap entropy: {:.2}", RegionSnapshot::capture(&ctx, heap)?.entropy());
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn entropy(&self) -> f64 {
+    let mut histogram = ByteHistogram::default();
+    histogram.add(&self.bytes);
+    histogram.entropy()
+  }
+
+  /// Returns ranges of bytes that differ between `self` and `other`.  
+  /// `self` is treated as the older snapshot. Only addresses captured  
+  /// by both snapshots are compared, adjacent changed bytes are merged  
+  /// into single range.
+  pub fn diff(&self, other: &RegionSnapshot) -> Vec<ChangedRange> {
+    let start = self.start.max(other.start);
+    let end = self.end().min(other.end());
+    if start >= end {
+      return Vec::new();
+    }
+
+    let old_bytes = &self.bytes[start - self.start..end - self.start];
+    let new_bytes = &other.bytes[start - other.start..end - other.start];
+
+    let mut changed_ranges: Vec<ChangedRange> = Vec::new();
+    let mut index = 0;
+    while index < old_bytes.len() {
+      if old_bytes[index] == new_bytes[index] {
+        index += 1;
+        continue;
+      }
+
+      let range_start = index;
+      while index < old_bytes.len() && old_bytes[index] != new_bytes[index] {
+        index += 1;
+      }
+
+      changed_ranges.push(ChangedRange {
+        start: start + range_start,
+        end: start + index,
+        old_bytes: old_bytes[range_start..index].to_vec(),
+        new_bytes: new_bytes[range_start..index].to_vec(),
+      });
+    }
+
+    changed_ranges
+  }
+}
+
+impl Process {
+  /// Returns readable regions which entropy (see `RegionSnapshot::entropy()`)  
+  /// is at least `threshold` bits per byte, together with the entropy,  
+  /// which helps to locate packed or encrypted blobs in memory. Regions are  
+  /// read in chunks without capturing them whole. Like in `RegionSnapshot::capture()`,  
+  /// only bytes before the first page which could not be read are taken  
+  /// into account, regions which could not be read at all are skipped.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `high_entropy_regions();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for (region, entropy) in ctx.high_entropy_regions(7.5)? {
+  ///     println!("{:.2} {}", entropy, region);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn high_entropy_regions(&self, threshold: f64) -> Result<Vec<(&MemoryRegion, f64)>> {
+    let mut regions: Vec<(&MemoryRegion, f64)> = Vec::new();
+
+    for region in self.get_memory_regions()? {
+      if !region.permissions.readable || region.kind() == RegionKind::Vvar {
+        continue;
+      }
+
+      let mut histogram = ByteHistogram::default();
+      let mut chunk_start = region.start;
+      while chunk_start < region.end {
+        let chunk_length = SNAPSHOT_CHUNK_SIZE.min(region.end - chunk_start);
+        let bytes_read = match self.read_memory_partial(chunk_start, chunk_length) {
+          Ok((chunk, bytes_read)) => {
+            histogram.add(&chunk[..bytes_read]);
+            bytes_read
+          }
+          Err(_) => 0,
+        };
+
+        if bytes_read < chunk_length {
+          break;
+        }
+        chunk_start += chunk_length;
+      }
+
+      if histogram.total == 0 {
+        continue;
+      }
+
+      let entropy = histogram.entropy();
+      if entropy >= threshold {
+        regions.push((region, entropy));
+      }
+    }
+
+    Ok(regions)
+  }
+}
+
+/// Number of occurrences of every byte value.
+struct ByteHistogram {
+  counts: [usize; 256],
+  total: usize,
+}
+
+impl Default for ByteHistogram {
+  fn default() -> ByteHistogram {
+    ByteHistogram {
+      counts: [0; 256],
+      total: 0,
+    }
+  }
+}
+
+impl ByteHistogram {
+  fn add(&mut self, bytes: &[u8]) {
+    for byte in bytes {
+      self.counts[*byte as usize] += 1;
+    }
+    self.total += bytes.len();
+  }
+
+  /// Returns Shannon entropy of counted bytes in bits per byte.
+  fn entropy(&self) -> f64 {
+    if self.total == 0 {
+      return 0.0;
+    }
+
+    let total = self.total as f64;
+    self
+      .counts
+      .iter()
+      .filter(|count| **count > 0)
+      .map(|count| {
+        let probability = *count as f64 / total;
+        -probability * probability.log2()
+      })
+      .sum()
+  }
+}
@@ -0,0 +1,246 @@
+use anyhow::Result;
+use nix::unistd::{sysconf, Pid, SysconfVar};
+use std::fs;
+use std::path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::Process;
+
+/// State of a process, as reported in the third field of `/proc/\[pid\]/stat`.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum ProcessState {
+  /// `R` - running or runnable.
+  Running,
+  /// `S` - interruptible sleep, waiting for an event.
+  Sleeping,
+  /// `D` - uninterruptible sleep, usually waiting for disk IO.
+  DiskSleep,
+  /// `Z` - terminated, but not yet reaped by its parent.
+  Zombie,
+  /// `T` - stopped by a signal.
+  Stopped,
+  /// `t` - stopped by debugger during tracing.
+  TracingStop,
+  /// `X` or `x` - dead.
+  Dead,
+  /// `I` - idle kernel thread.
+  Idle,
+  /// Any other state character, these differ between kernel versions.
+  Other(char),
+}
+
+impl From<char> for ProcessState {
+  fn from(state: char) -> ProcessState {
+    match state {
+      'R' => ProcessState::Running,
+      'S' => ProcessState::Sleeping,
+      'D' => ProcessState::DiskSleep,
+      'Z' => ProcessState::Zombie,
+      'T' => ProcessState::Stopped,
+      't' => ProcessState::TracingStop,
+      'X' | 'x' => ProcessState::Dead,
+      'I' => ProcessState::Idle,
+      other => ProcessState::Other(other),
+    }
+  }
+}
+
+/// Status information about a process read from `/proc/\[pid\]/stat` file.  
+/// Times are expressed in clock ticks (see `ProcessStat::ticks_to_duration()`).
+///
+/// You can find more detailed fields description in  
+/// [**proc(5)**](http://man7.org/linux/man-pages/man5/proc.5.html).
+#[derive(Clone, Debug)]
+pub struct ProcessStat {
+  pub pid: Pid,
+  /// Name of the executable, without parentheses. It can contain spaces  
+  /// and parentheses itself, which are handled correctly while parsing.
+  pub comm: String,
+  pub state: ProcessState,
+  /// Process id of the parent process.
+  pub ppid: Pid,
+  /// Process group id.
+  pub pgrp: i32,
+  /// Session id.
+  pub session: i32,
+  /// Time spent in user mode, in clock ticks.
+  pub utime: u64,
+  /// Time spent in kernel mode, in clock ticks.
+  pub stime: u64,
+  /// Scheduling priority, as seen by the kernel.
+  pub priority: i64,
+  /// Nice value, from 19 (lowest priority) to -20 (highest priority).
+  pub nice: i64,
+  pub num_threads: i64,
+  /// Time the process started after system boot, in clock ticks.
+  pub start_time: u64,
+  /// Virtual memory size in bytes.
+  pub vsize: u64,
+  /// Resident set size in pages.
+  pub rss: i64,
+  /// Address of the start (bottom) of the stack of the main thread.
+  pub start_stack: u64,
+  /// Current value of stack pointer, as found in kernel stack page.  
+  /// Modern kernels report `0` here, unless the thread is being core dumped.
+  pub kstkesp: u64,
+  /// CPU number process last executed on.
+  pub processor: i32,
+}
+
+impl ProcessStat {
+  /// Returns time spent by process in user and kernel mode together.
+  pub fn cpu_time(&self) -> Result<Duration> {
+    ProcessStat::ticks_to_duration(self.utime + self.stime)
+  }
+
+  /// Converts clock ticks used by `/proc/\[pid\]/stat` to [`Duration`].
+  ///
+  /// [`Duration`]: https://doc.rust-lang.org/std/time/struct.Duration.html
+  pub fn ticks_to_duration(ticks: u64) -> Result<Duration> {
+    let ticks_per_second = match sysconf(SysconfVar::CLK_TCK) {
+      Ok(Some(ticks_per_second)) if ticks_per_second > 0 => ticks_per_second as u64,
+      _ => return Err(anyhow!("Could not get number of clock ticks per second.")),
+    };
+
+    Ok(
+      Duration::from_secs(ticks / ticks_per_second)
+        + Duration::from_nanos(ticks % ticks_per_second * 1_000_000_000 / ticks_per_second),
+    )
+  }
+}
+
+impl FromStr for ProcessStat {
+  type Err = anyhow::Error;
+
+  /// Parses contents of `/proc/\[pid\]/stat` file. Name of the executable  
+  /// is everything between the first `(` and the last `)`, since it  
+  /// can contain both spaces and parentheses.
+  fn from_str(stat: &str) -> Result<ProcessStat> {
+    let invalid_stat = || anyhow!("Could not parse stat file ({}).", stat.trim_end());
+
+    let comm_start = stat.find('(').ok_or_else(invalid_stat)?;
+    let comm_end = stat.rfind(')').ok_or_else(invalid_stat)?;
+    if comm_end < comm_start {
+      return Err(invalid_stat());
+    }
+
+    let pid = stat[..comm_start].trim().parse::<i32>().map_err(|_| invalid_stat())?;
+    let comm = stat[comm_start + 1..comm_end].to_string();
+
+    // Fields after comm, so `fields[0]` is the third field of the file.
+    let fields: Vec<&str> = stat[comm_end + 1..].split_whitespace().collect();
+    let field = |number: usize| fields.get(number - 3).copied().ok_or_else(invalid_stat);
+    let number = |number: usize| {
+      field(number).and_then(|value| value.parse::<i64>().map_err(|_| invalid_stat()))
+    };
+    let unsigned = |number: usize| {
+      field(number).and_then(|value| value.parse::<u64>().map_err(|_| invalid_stat()))
+    };
+
+    Ok(ProcessStat {
+      pid: Pid::from_raw(pid),
+      comm,
+      state: ProcessState::from(field(3)?.chars().next().ok_or_else(invalid_stat)?),
+      ppid: Pid::from_raw(number(4)? as i32),
+      pgrp: number(5)? as i32,
+      session: number(6)? as i32,
+      utime: unsigned(14)?,
+      stime: unsigned(15)?,
+      priority: number(18)?,
+      nice: number(19)?,
+      num_threads: number(20)?,
+      start_time: unsigned(22)?,
+      vsize: unsigned(23)?,
+      rss: number(24)?,
+      start_stack: unsigned(28)?,
+      kstkesp: unsigned(29)?,
+      processor: number(39)? as i32,
+    })
+  }
+}
+
+/// Reads and parses stat file located at `stat_path`.
+pub(crate) fn read_stat(stat_path: &path::Path) -> Result<ProcessStat> {
+  fs::read_to_string(stat_path)
+    .map_err(|error| anyhow!("Could not read {} ({}).", stat_path.display(), error))?
+    .parse::<ProcessStat>()
+}
+
+impl Process {
+  /// Reads `/proc/\[pid\]/stat` file and returns status information  
+  /// about the process, like its state, CPU times and start time.  
+  /// Values are read every time this method is called.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let stat = ctx.stat()?;
+  ///   println!("{:?} cpu time: {:?} priority: {}", stat.state, stat.cpu_time()?, stat.priority);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `Sleeping cpu time: 1.73s priority: 20`
+  pub fn stat(&self) -> Result<ProcessStat> {
+    read_stat(&path::Path::new("/proc/").join(self.get_pid().to_string()).join("stat"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ProcessStat, ProcessState};
+
+  /// Returns stat file of process named `comm`, with fields after state  
+  /// taken from a real process.
+  fn stat_line(comm: &str) -> String {
+    format!(
+      "1234 ({}) S 1 1234 1200 0 -1 4194560 100 0 0 0 150 30 0 0 20 -5 4 0 98765 \
+       1048576 256 18446744073709551615 1 1 140737488345000 140737488344000 0 0 0 0 0 0 0 0 \
+       17 3 0 0 0 0 0 0 0 0 0 0 0 0 0\n",
+      comm
+    )
+  }
+
+  #[test]
+  fn parses_fields() {
+    let stat: ProcessStat = stat_line("game").parse().unwrap();
+
+    assert_eq!(stat.pid.as_raw(), 1234);
+    assert_eq!(stat.comm, "game");
+    assert_eq!(stat.state, ProcessState::Sleeping);
+    assert_eq!(stat.ppid.as_raw(), 1);
+    assert_eq!((stat.pgrp, stat.session), (1234, 1200));
+    assert_eq!((stat.utime, stat.stime), (150, 30));
+    assert_eq!((stat.priority, stat.nice, stat.num_threads), (20, -5, 4));
+    assert_eq!(stat.start_time, 98765);
+    assert_eq!((stat.vsize, stat.rss), (1048576, 256));
+    assert_eq!(
+      (stat.start_stack, stat.kstkesp),
+      (140737488345000, 140737488344000)
+    );
+    assert_eq!(stat.processor, 3);
+  }
+
+  #[test]
+  fn parses_comm_with_parentheses_and_spaces() {
+    let stat: ProcessStat = stat_line("a) (b) S 2 (c").parse().unwrap();
+
+    assert_eq!(stat.comm, "a) (b) S 2 (c");
+    assert_eq!(stat.state, ProcessState::Sleeping);
+    assert_eq!(stat.start_time, 98765);
+  }
+
+  #[test]
+  fn rejects_malformed_stat() {
+    assert!("".parse::<ProcessStat>().is_err());
+    assert!("1234 game S 1".parse::<ProcessStat>().is_err());
+    assert!("1234 )game( S 1".parse::<ProcessStat>().is_err());
+    assert!("1234 (game) S 1 1234".parse::<ProcessStat>().is_err());
+  }
+}
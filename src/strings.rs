@@ -0,0 +1,200 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::mem;
+use std::slice;
+
+use super::pagemap::page_size;
+use super::scan::SCAN_CHUNK_SIZE;
+use super::{MemoryRegion, Process, RegionKind};
+
+/// Encoding of string found by `Process::strings()`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+#[cfg_attr(
+  feature = "serde-support",
+  derive(::serde::Serialize, ::serde::Deserialize)
+)]
+pub enum StringEncoding {
+  /// Printable ASCII characters, one byte each.
+  Ascii,
+  /// Printable ASCII characters encoded as little-endian UTF-16, two bytes  
+  /// each, like wide strings of Windows programs running under Wine.
+  Utf16,
+}
+
+/// String found in process memory by `Process::strings()`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde-support", derive(::serde::Serialize))]
+pub struct FoundString<'a> {
+  /// Address of the first character of the string.
+  pub address: usize,
+  /// Region containing the string.
+  pub region: &'a MemoryRegion,
+  /// Encoding the string was found in.
+  pub encoding: StringEncoding,
+  /// Characters of the string, without terminator.
+  pub value: String,
+}
+
+/// String being collected from consecutive printable characters.
+#[derive(Default)]
+struct Run {
+  address: usize,
+  value: String,
+}
+
+/// Lazy iterator over strings in readable memory regions of a process,  
+/// created by `Process::strings()`. Memory is read in chunks only when  
+/// more strings are requested.
+pub struct StringsIter<'a> {
+  process: &'a Process,
+  regions: slice::Iter<'a, MemoryRegion>,
+  /// Region being read and address of its next chunk.
+  region: Option<(&'a MemoryRegion, usize)>,
+  min_length: usize,
+  ascii: Run,
+  utf16: Run,
+  found: VecDeque<FoundString<'a>>,
+}
+
+impl<'a> StringsIter<'a> {
+  /// Reads the next chunk of the current region and collects strings ended in it.  
+  /// Strings are also ended by the end of the region and memory which could not be read.
+  fn read_chunk(&mut self, region: &'a MemoryRegion, address: usize) {
+    let length = SCAN_CHUNK_SIZE.min(region.end - address);
+    let (chunk, bytes_read) = self
+      .process
+      .read_memory_partial(address, length)
+      .unwrap_or_else(|_| (Vec::new(), 0));
+
+    for (offset, byte) in chunk[..bytes_read].iter().enumerate() {
+      let character = Some(*byte).filter(|byte| is_printable(*byte));
+      self.push(region, StringEncoding::Ascii, address + offset, character);
+    }
+    // Chunks start at page boundaries, so UTF-16 characters are 2-byte aligned.
+    for (index, pair) in chunk[..bytes_read].chunks_exact(2).enumerate() {
+      let character = Some(pair[0]).filter(|byte| pair[1] == 0 && is_printable(*byte));
+      self.push(
+        region,
+        StringEncoding::Utf16,
+        address + index * 2,
+        character,
+      );
+    }
+
+    // Reading continues at the page following the first byte which could not be read.
+    let next_address = if bytes_read < length {
+      (address + bytes_read + 1).next_multiple_of(page_size())
+    } else {
+      address + length
+    };
+    if bytes_read < length || next_address >= region.end {
+      self.push(region, StringEncoding::Ascii, next_address, None);
+      self.push(region, StringEncoding::Utf16, next_address, None);
+    }
+    self.region = Some((region, next_address));
+  }
+
+  /// Appends `character` at `address` to the string being collected in `encoding`,  
+  /// or ends the string if `character` is [`None`], keeping it if it is long enough.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  fn push(
+    &mut self,
+    region: &'a MemoryRegion,
+    encoding: StringEncoding,
+    address: usize,
+    character: Option<u8>,
+  ) {
+    let run = match encoding {
+      StringEncoding::Ascii => &mut self.ascii,
+      StringEncoding::Utf16 => &mut self.utf16,
+    };
+
+    match character {
+      Some(character) => {
+        if run.value.is_empty() {
+          run.address = address;
+        }
+        run.value.push(character as char);
+      }
+      None if run.value.len() >= self.min_length => self.found.push_back(FoundString {
+        address: run.address,
+        region,
+        encoding,
+        value: mem::take(&mut run.value),
+      }),
+      None => run.value.clear(),
+    }
+  }
+}
+
+impl<'a> Iterator for StringsIter<'a> {
+  type Item = FoundString<'a>;
+
+  fn next(&mut self) -> Option<FoundString<'a>> {
+    loop {
+      if let Some(found) = self.found.pop_front() {
+        return Some(found);
+      }
+
+      match self.region {
+        Some((region, address)) if address < region.end => self.read_chunk(region, address),
+        _ => {
+          // Special regions like [vvar] are readable according to maps,
+          // but can not be accessed by other processes.
+          let region = self
+            .regions
+            .by_ref()
+            .find(|region| region.permissions.readable && region.kind() != RegionKind::Vvar)?;
+          self.region = Some((region, region.start));
+        }
+      }
+    }
+  }
+}
+
+impl Process {
+  /// Returns lazy iterator over strings in all readable memory regions,  
+  /// like **strings(1)** does for files: every run of at least `min_length`  
+  /// printable ASCII characters (including tab), and every such run encoded  
+  /// as little-endian UTF-16 at 2-byte aligned address. Strings are yielded  
+  /// region by region, together with their addresses and regions.  
+  /// Memory which could not be read is skipped and ends strings.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `strings();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   for found in ctx.strings(8)?.filter(|found| found.value.contains("http")) {
+  ///     println!("{:#x} {:?} {}", found.address, found.encoding, found.value);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x55d0c1012a40 Ascii https://example.com/api/v1/login`
+  pub fn strings(&self, min_length: usize) -> Result<StringsIter<'_>> {
+    Ok(StringsIter {
+      process: self,
+      regions: self.get_memory_regions()?.iter(),
+      region: None,
+      min_length: min_length.max(1),
+      ascii: Run::default(),
+      utf16: Run::default(),
+      found: VecDeque::new(),
+    })
+  }
+}
+
+/// Returns **true** if `byte` is printable ASCII character or tab.
+fn is_printable(byte: u8) -> bool {
+  byte == b'\t' || (0x20..=0x7e).contains(&byte)
+}
@@ -0,0 +1,246 @@
+use anyhow::Result;
+use nix::errno::Errno;
+use nix::sys::ptrace;
+use nix::sys::signal::{kill, Signal};
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::error::system_call_error;
+use super::trace::{interrupt, wait_for_interrupt};
+use super::{Process, ProcessState, Thread};
+
+/// Maximum time `Process::suspend()` waits for all threads to stop.
+const SUSPEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// Interval between checks of thread states while waiting for them to stop.
+const SUSPEND_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Guard returned by `Process::suspended()`. While it is alive,  
+/// the process is suspended. Dropping the guard resumes the process.
+pub struct SuspendGuard {
+  process: Process,
+}
+
+impl SuspendGuard {
+  /// Resumes the process, returning [`Err`] if it could not be resumed.  
+  /// Dropping the guard does the same, but ignores errors.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn resume(self) -> Result<()> {
+    self.process.resume()
+  }
+}
+
+impl Drop for SuspendGuard {
+  fn drop(&mut self) {
+    let _ = self.process.resume();
+  }
+}
+
+impl Process {
+  /// Suspends all threads of the process by sending **SIGSTOP** to it and waits  
+  /// until all of them are stopped, so the process can not race with memory  
+  /// writes made afterwards. **SIGSTOP** can not be caught or ignored by the process.  
+  /// Use `resume()` to let it continue, or `suspended()` to resume it automatically.
+  ///
+  /// If threads do not stop within a second, **SIGCONT** is sent  
+  /// to the process, so it is not left stopped, and [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: Stopping and continuing the process is visible to its parent  
+  /// (e.g. shell reports it as stopped job) and to its tracer, if it is traced.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   ctx.suspend()?;
+  ///   ctx.write::<u32>(0x601040, &100)?;
+  ///   ctx.write::<u32>(0x601044, &100)?;
+  ///   ctx.resume()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn suspend(&self) -> Result<()> {
+    self.verify_identity()?;
+
+    kill(self.get_pid(), Signal::SIGSTOP).map_err(|error| {
+      system_call_error(
+        self.get_pid(),
+        error,
+        format!("Could not suspend process {}", self.get_pid()),
+      )
+    })?;
+
+    if let Err(error) = self.wait_until_stopped() {
+      // Process is not left half-suspended, as there is no guard to resume it.
+      let _ = kill(self.get_pid(), Signal::SIGCONT);
+      return Err(error);
+    }
+
+    Ok(())
+  }
+
+  /// Waits until all threads of the process are stopped, see `suspend()`.
+  fn wait_until_stopped(&self) -> Result<()> {
+    let start = Instant::now();
+    while !self.all_threads_stopped()? {
+      if start.elapsed() >= SUSPEND_TIMEOUT {
+        return Err(anyhow!(
+          "Could not suspend process {} (threads did not stop within {:?}).",
+          self.get_pid(),
+          SUSPEND_TIMEOUT
+        ));
+      }
+      thread::sleep(SUSPEND_POLL_INTERVAL);
+    }
+
+    Ok(())
+  }
+
+  /// Resumes process suspended with `suspend()` by sending **SIGCONT** to it.
+  pub fn resume(&self) -> Result<()> {
+    self.verify_identity()?;
+
+    kill(self.get_pid(), Signal::SIGCONT).map_err(|error| {
+      system_call_error(
+        self.get_pid(),
+        error,
+        format!("Could not resume process {}", self.get_pid()),
+      )
+    })
+  }
+
+  /// Suspends the process (see `suspend()`) and returns guard which  
+  /// resumes it when dropped, so the process is never left suspended  
+  /// by accident (e.g. when one of the writes in between fails).
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   {
+  ///     let _suspended = ctx.suspended()?;
+  ///     ctx.write::<u32>(0x601040, &100)?;
+  ///     ctx.write::<u32>(0x601044, &100)?;
+  ///   }
+  ///   // Process runs again here.
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn suspended(&self) -> Result<SuspendGuard> {
+    self.suspend()?;
+
+    Ok(SuspendGuard {
+      process: self.clone(),
+    })
+  }
+
+  /// Returns **true** if none of the threads of the process is running.
+  fn all_threads_stopped(&self) -> Result<bool> {
+    Ok(self.threads()?.iter().all(|thread| {
+      matches!(
+        thread.state,
+        ProcessState::Stopped | ProcessState::TracingStop | ProcessState::Zombie | ProcessState::Dead
+      )
+    }))
+  }
+}
+
+impl Thread {
+  /// Suspends only this thread, leaving other threads of the process running.  
+  /// Signals stop whole processes, so the thread is attached to with **PTRACE_SEIZE**  
+  /// and stopped with **PTRACE_INTERRUPT** instead. Use `resume()` to let it continue.
+  ///
+  /// It fails if the thread is already suspended or traced (e.g. by a debugger  
+  /// or by `Process::attach()`) and requires the same permissions as reading  
+  /// process memory.
+  ///
+  /// **NOTE**: Thread which called `suspend()` becomes tracer of the suspended  
+  /// thread, so `resume()` has to be called from the same thread. Suspended  
+  /// threads are resumed automatically when the calling process exits.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   for thread in ctx.threads()? {
+  ///     if thread.name == "RenderThread" {
+  ///       thread.suspend()?;
+  ///       ctx.write::<u32>(0x601040, &100)?;
+  ///       thread.resume()?;
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn suspend(&self) -> Result<()> {
+    match ptrace::seize(self.tid, ptrace::Options::empty()) {
+      Ok(()) => {}
+      Err(nix::Error::Sys(Errno::EPERM)) if self.is_traced() => {
+        return Err(anyhow!(
+          "Could not suspend thread {} (thread is already suspended or traced).",
+          self.tid
+        ))
+      }
+      Err(error) => {
+        return Err(system_call_error(
+          self.tid,
+          error,
+          format!("Could not suspend thread {}", self.tid),
+        ))
+      }
+    }
+
+    // Thread is seized without options, so it does not attach threads it creates.
+    interrupt(self.tid)?;
+    if !wait_for_interrupt(self.tid, &mut Vec::new())? {
+      return Err(anyhow!(
+        "Could not suspend thread {} (thread exited).",
+        self.tid
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// Resumes thread suspended with `suspend()`, detaching from it.  
+  /// If thread exited in the meantime, nothing is done.
+  pub fn resume(&self) -> Result<()> {
+    match ptrace::detach(self.tid, None) {
+      Ok(()) | Err(nix::Error::Sys(Errno::ESRCH)) => Ok(()),
+      Err(error) => Err(system_call_error(
+        self.tid,
+        error,
+        format!("Could not resume thread {}", self.tid),
+      )),
+    }
+  }
+
+  /// Returns **true** if the thread has a tracer, according to `TracerPid`  
+  /// field of `/proc/\[pid\]/task/\[tid\]/status` file.
+  fn is_traced(&self) -> bool {
+    fs::read_to_string(self.task_path().join("status"))
+      .ok()
+      .and_then(|status| {
+        status
+          .lines()
+          .find_map(|line| line.strip_prefix("TracerPid:"))
+          .map(|tracer| tracer.trim() != "0")
+      })
+      .unwrap_or(false)
+  }
+}
\ No newline at end of file
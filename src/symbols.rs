@@ -0,0 +1,179 @@
+use anyhow::Result;
+use std::fmt;
+
+#[cfg(feature = "demangle")]
+use super::demangle::demangle;
+use super::Process;
+
+/// Location of an address in process memory, returned by `Process::symbolicate()`.  
+/// It is displayed as `module!symbol+offset` (e.g. `libc.so.6!malloc+0x2f`),  
+/// or `module+offset` if there is no symbol preceding the address.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SymbolInfo {
+  /// File name of module containing the address.
+  pub module: String,
+  /// Name of the nearest symbol preceding the address, if there is one.
+  pub symbol: Option<String>,
+  /// Offset of the address from the symbol or, if there is none, from module base.
+  pub offset: usize,
+}
+
+#[cfg(feature = "demangle")]
+impl SymbolInfo {
+  /// Returns copy of the info with demangled symbol name, see [`demangle()`].  
+  /// Names which are not mangled are left as they are.
+  ///
+  /// [`demangle()`]: fn.demangle.html
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   println!("{}", ctx.symbolicate(0x55d4c1a4f6c0)?.demangled());
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `game!Player::takeDamage(int)+0x1c`
+  pub fn demangled(&self) -> SymbolInfo {
+    SymbolInfo {
+      module: self.module.clone(),
+      symbol: self
+        .symbol
+        .as_ref()
+        .map(|symbol| demangle(symbol).unwrap_or_else(|| symbol.clone())),
+      offset: self.offset,
+    }
+  }
+}
+
+impl fmt::Display for SymbolInfo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.module)?;
+    if let Some(symbol) = &self.symbol {
+      write!(f, "!{}", symbol)?;
+    }
+    if self.offset != 0 || self.symbol.is_none() {
+      write!(f, "+{:#x}", self.offset)?;
+    }
+    Ok(())
+  }
+}
+
+impl Process {
+  /// Returns address of `symbol` defined in module which file name or full path  
+  /// equals `module_name` (see `module()`), e.g. address of `malloc` in `libc.so.6`.  
+  /// Symbol is looked up in `.dynsym` and `.symtab` sections of ELF file the module  
+  /// is mapped from, and its address is adjusted by `Module::load_bias()`.  
+  /// Global symbols and default versions of them are preferred, if there are  
+  /// multiple symbols with the same name. With `demangle` feature, demangled  
+  /// names (e.g. `Player::takeDamage(int)` or `Player::takeDamage`) match too.  
+  /// If there is no such module or symbol, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// Address of `STT_GNU_IFUNC` symbols (e.g. `strlen` in glibc) is address  
+  /// of their resolver function, not of the implementation.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `resolve_symbol();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let malloc_address = ctx.resolve_symbol("libc.so.6", "malloc")?;
+  ///   println!("malloc: {:#x}", malloc_address);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `malloc: 0x7f0c8e41e0a0`
+  pub fn resolve_symbol(&self, module_name: &str, symbol: &str) -> Result<usize> {
+    let module = self.module(module_name)?;
+    let elf = module.read_elf()?;
+    let load_bias = module.load_bias_of(&elf)?;
+
+    elf
+      .find_symbol(symbol)
+      .map(|value| load_bias.wrapping_add(value))
+      .ok_or_else(|| {
+        anyhow!(
+          "Could not find symbol {} in module {}.",
+          symbol,
+          module_name
+        )
+      })
+  }
+
+  /// Returns module containing `address` and the nearest symbol preceding it  
+  /// in `.dynsym` and `.symtab` sections of the module (see `resolve_symbol()`),  
+  /// e.g. for logging addresses of scan results or crashes in readable form.  
+  /// If `address` is not located in any module, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: Stripped modules contain only exported symbols, so the nearest  
+  /// of them may belong to unrelated code, visible as large offset.
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `symbolicate();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let malloc_address = ctx.resolve_symbol("libc.so.6", "malloc")?;
+  ///   println!("{}", ctx.symbolicate(malloc_address + 0x2f)?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `libc.so.6!malloc+0x2f`
+  pub fn symbolicate(&self, address: usize) -> Result<SymbolInfo> {
+    let module = self
+      .modules()?
+      .into_iter()
+      .find(|module| module.contains(address))
+      .ok_or_else(|| anyhow!("Could not find module containing address {:#x}.", address))?;
+    let elf = module.read_elf()?;
+    let load_bias = module.load_bias_of(&elf)?;
+
+    // Symbol placed after the address by inconsistent load bias is skipped.
+    let nearest = elf.nearest_symbol(address.wrapping_sub(load_bias)).and_then(|symbol| {
+      address
+        .checked_sub(load_bias.wrapping_add(symbol.value))
+        .map(|offset| (symbol.name, offset))
+    });
+
+    let info = match nearest {
+      Some((name, offset)) => SymbolInfo {
+        module: module.name().to_string(),
+        symbol: Some(name),
+        offset,
+      },
+      None => SymbolInfo {
+        module: module.name().to_string(),
+        symbol: None,
+        offset: address - module.base(),
+      },
+    };
+
+    Ok(info)
+  }
+}
@@ -0,0 +1,487 @@
+use nix::errno::Errno;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use super::Process;
+
+/// Maximum number of bytes of a buffer argument read from the process.
+const MAX_BUFFER_LENGTH: usize = 32;
+/// Maximum number of elements of a string array argument read from the process.
+const MAX_ARRAY_LENGTH: usize = 32;
+/// Maximum length of a path argument, **PATH_MAX** on Linux.
+const MAX_PATH_LENGTH: usize = 4096;
+/// Size of `struct sockaddr_storage`.
+const MAX_SOCKADDR_LENGTH: usize = 128;
+
+/// How system call argument is decoded.
+#[derive(Clone, Copy)]
+enum ArgKind {
+  Int,
+  Octal,
+  Pointer,
+  Fd,
+  /// Directory file descriptor of `*at` system calls, which can be **AT_FDCWD**.
+  DirFd,
+  Path,
+  /// Buffer with length given by argument at index.
+  Buffer(usize),
+  OpenFlags,
+  /// Socket address with length given by argument at index.
+  SocketAddress(usize),
+  StrArray,
+}
+
+/// Returns name and argument kinds of system call `number`,  
+/// or [`None`] if it is not known to the decoder.
+///
+/// [`None`]: https://doc.rust-lang.org/std/option/
+fn syscall_signature(number: i64) -> Option<(&'static str, &'static [ArgKind])> {
+  use self::ArgKind::*;
+
+  let signature: (&'static str, &'static [ArgKind]) = match number {
+    libc::SYS_read => ("read", &[Fd, Pointer, Int]),
+    libc::SYS_write => ("write", &[Fd, Buffer(2), Int]),
+    libc::SYS_close => ("close", &[Fd]),
+    libc::SYS_openat => ("openat", &[DirFd, Path, OpenFlags, Octal]),
+    libc::SYS_unlinkat => ("unlinkat", &[DirFd, Path, Int]),
+    libc::SYS_mkdirat => ("mkdirat", &[DirFd, Path, Octal]),
+    libc::SYS_execve => ("execve", &[Path, StrArray, Pointer]),
+    libc::SYS_socket => ("socket", &[Int, Int, Int]),
+    libc::SYS_connect => ("connect", &[Fd, SocketAddress(2), Int]),
+    libc::SYS_bind => ("bind", &[Fd, SocketAddress(2), Int]),
+    libc::SYS_accept4 => ("accept4", &[Fd, Pointer, Pointer, Int]),
+    libc::SYS_sendto => ("sendto", &[Fd, Buffer(2), Int, Int, SocketAddress(5), Int]),
+    libc::SYS_recvfrom => ("recvfrom", &[Fd, Pointer, Int, Int, Pointer, Pointer]),
+    libc::SYS_getpid => ("getpid", &[]),
+    libc::SYS_exit_group => ("exit_group", &[Int]),
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_open => ("open", &[Path, OpenFlags, Octal]),
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_unlink => ("unlink", &[Path]),
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_mkdir => ("mkdir", &[Path, Octal]),
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_accept => ("accept", &[Fd, Pointer, Pointer]),
+    _ => return None,
+  };
+
+  Some(signature)
+}
+
+/// Socket address decoded from `struct sockaddr` argument.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SocketAddress {
+  /// **AF_INET** or **AF_INET6** address.
+  Inet(SocketAddr),
+  /// **AF_UNIX** socket path, abstract socket names start with `@`.
+  Unix(String),
+  /// Address of other family, with given family number.
+  Other(u16),
+}
+
+impl fmt::Display for SocketAddress {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SocketAddress::Inet(address) => write!(formatter, "{{inet {}}}", address),
+      SocketAddress::Unix(path) => write!(formatter, "{{unix {:?}}}", path),
+      SocketAddress::Other(family) => write!(formatter, "{{family {}}}", family),
+    }
+  }
+}
+
+/// Decoded system call argument.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SyscallArg {
+  Int(i64),
+  /// File mode, printed in octal.
+  Octal(u64),
+  /// Address in the process, printed as `NULL` if it is zero. Arguments  
+  /// pointing to memory which could not be read are decoded as pointers too.
+  Pointer(u64),
+  /// File descriptor, **AT_FDCWD** for `*at` system calls is kept as is (-100).
+  Fd(i32),
+  /// String or buffer read from the process, invalid UTF-8 sequences  
+  /// are replaced with `U+FFFD`.
+  Str {
+    value: String,
+    truncated: bool,
+  },
+  /// NULL terminated array of strings, like `argv` of **execve(2)**.
+  StrArray {
+    values: Vec<String>,
+    truncated: bool,
+  },
+  /// Flags rendered as names joined with `|`.
+  Flags(String),
+  SocketAddress(SocketAddress),
+}
+
+impl fmt::Display for SyscallArg {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SyscallArg::Int(value) => write!(formatter, "{}", value),
+      SyscallArg::Octal(value) => write!(formatter, "0{:o}", value),
+      SyscallArg::Pointer(0) => write!(formatter, "NULL"),
+      SyscallArg::Pointer(address) => write!(formatter, "{:#x}", address),
+      SyscallArg::Fd(libc::AT_FDCWD) => write!(formatter, "AT_FDCWD"),
+      SyscallArg::Fd(fd) => write!(formatter, "{}", fd),
+      SyscallArg::Str { value, truncated } => {
+        write!(formatter, "{:?}", value)?;
+        if *truncated {
+          write!(formatter, "...")?;
+        }
+        Ok(())
+      }
+      SyscallArg::StrArray { values, truncated } => {
+        write!(formatter, "[")?;
+        for (index, value) in values.iter().enumerate() {
+          if index > 0 {
+            write!(formatter, ", ")?;
+          }
+          write!(formatter, "{:?}", value)?;
+        }
+        if *truncated {
+          write!(formatter, ", ...")?;
+        }
+        write!(formatter, "]")
+      }
+      SyscallArg::Flags(flags) => write!(formatter, "{}", flags),
+      SyscallArg::SocketAddress(address) => write!(formatter, "{}", address),
+    }
+  }
+}
+
+/// System call with arguments decoded by `Process::decode_syscall()`.  
+/// It is formatted like **strace(1)** output, e.g.  
+/// `openat(AT_FDCWD, "/etc/hostname", O_RDONLY|O_CLOEXEC) = 3`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DecodedSyscall {
+  pub number: i64,
+  /// Name of the system call, [`None`] if it is not known to the decoder.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  pub name: Option<&'static str>,
+  pub args: Vec<SyscallArg>,
+  /// Return value, set by the caller once system call exits  
+  /// (see `TraceEvent::SyscallExit`).
+  pub result: Option<i64>,
+}
+
+impl fmt::Display for DecodedSyscall {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self.name {
+      Some(name) => write!(formatter, "{}(", name)?,
+      None => write!(formatter, "syscall_{}(", self.number)?,
+    }
+    for (index, arg) in self.args.iter().enumerate() {
+      if index > 0 {
+        write!(formatter, ", ")?;
+      }
+      write!(formatter, "{}", arg)?;
+    }
+    write!(formatter, ")")?;
+
+    match self.result {
+      // Values in this range are negated error numbers.
+      Some(result) if (-4095..0).contains(&result) => {
+        let errno = Errno::from_i32(-result as i32);
+        write!(formatter, " = -1 {:?} ({})", errno, errno.desc())
+      }
+      Some(result) => write!(formatter, " = {}", result),
+      None => Ok(()),
+    }
+  }
+}
+
+impl Process {
+  /// Decodes arguments of system call `number` into readable form, reading strings,  
+  /// buffers and socket addresses they point to from the process memory. System  
+  /// calls unknown to the decoder have all six arguments decoded as pointers.  
+  /// Arguments pointing to memory which could not be read are decoded as pointers too.
+  ///
+  /// Buffers filled by the system call (e.g. of **read(2)**) are not read, as  
+  /// they are not filled yet when system call enters.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use std::collections::HashMap;
+  /// use trickster::{Process, TraceEvent};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///   traced.set_syscall_tracing(true);
+  ///
+  ///   let mut pending = HashMap::new();
+  ///   let mut events = traced.events();
+  ///   while let Some(event) = events.next() {
+  ///     match event? {
+  ///       TraceEvent::SyscallEnter { tid, number, args } => {
+  ///         pending.insert(tid, events.traced().decode_syscall(number, &args));
+  ///       }
+  ///       TraceEvent::SyscallExit { tid, result, .. } => {
+  ///         if let Some(mut decoded) = pending.remove(&tid) {
+  ///           decoded.result = Some(result);
+  ///           println!("{}", decoded);
+  ///         }
+  ///       }
+  ///       _ => {}
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `openat(AT_FDCWD, "/etc/hostname", O_RDONLY|O_CLOEXEC) = 3`  
+  /// `connect(4, {inet 93.184.216.34:443}, 16) = -1 EINPROGRESS (Operation now in progress)`
+  pub fn decode_syscall(&self, number: i64, args: &[u64; 6]) -> DecodedSyscall {
+    let (name, decoded_args) = match syscall_signature(number) {
+      Some((name, kinds)) => (
+        Some(name),
+        kinds
+          .iter()
+          .enumerate()
+          .map(|(index, kind)| self.decode_syscall_arg(*kind, args[index], args))
+          .collect(),
+      ),
+      None => (
+        None,
+        args.iter().map(|arg| SyscallArg::Pointer(*arg)).collect(),
+      ),
+    };
+
+    DecodedSyscall {
+      number,
+      name,
+      args: decoded_args,
+      result: None,
+    }
+  }
+
+  fn decode_syscall_arg(&self, kind: ArgKind, arg: u64, args: &[u64; 6]) -> SyscallArg {
+    let decoded = match kind {
+      ArgKind::Int => Some(SyscallArg::Int(arg as i64)),
+      ArgKind::Octal => Some(SyscallArg::Octal(arg)),
+      ArgKind::Pointer => None,
+      ArgKind::Fd | ArgKind::DirFd => Some(SyscallArg::Fd(arg as i32)),
+      ArgKind::OpenFlags => Some(SyscallArg::Flags(open_flags(arg as i32))),
+      _ if arg == 0 => None,
+      ArgKind::Path => {
+        self
+          .read_cstring(arg as usize, MAX_PATH_LENGTH)
+          .ok()
+          .map(|value| SyscallArg::Str {
+            truncated: value.len() == MAX_PATH_LENGTH,
+            value,
+          })
+      }
+      ArgKind::Buffer(length_index) => {
+        let length = args[length_index] as usize;
+        self
+          .read_memory_partial(arg as usize, length.min(MAX_BUFFER_LENGTH))
+          .ok()
+          .filter(|(_, bytes_read)| *bytes_read > 0)
+          .map(|(buffer, bytes_read)| SyscallArg::Str {
+            value: String::from_utf8_lossy(&buffer[..bytes_read]).into_owned(),
+            truncated: length > bytes_read,
+          })
+      }
+      ArgKind::SocketAddress(length_index) => {
+        let length = (args[length_index] as usize).min(MAX_SOCKADDR_LENGTH);
+        self
+          .read_memory_bytes(arg as usize, length)
+          .ok()
+          .and_then(|bytes| parse_socket_address(&bytes))
+          .map(SyscallArg::SocketAddress)
+      }
+      ArgKind::StrArray => self.read_string_array(arg as usize),
+    };
+
+    decoded.unwrap_or(SyscallArg::Pointer(arg))
+  }
+
+  /// Reads NULL terminated array of string pointers at `address`.
+  fn read_string_array(&self, address: usize) -> Option<SyscallArg> {
+    let mut values = Vec::new();
+
+    for index in 0..MAX_ARRAY_LENGTH {
+      let pointer = self.read::<usize>(address + index * std::mem::size_of::<usize>()).ok()?;
+      if pointer == 0 {
+        return Some(SyscallArg::StrArray {
+          values,
+          truncated: false,
+        });
+      }
+      values.push(self.read_cstring(pointer, MAX_PATH_LENGTH).ok()?);
+    }
+
+    Some(SyscallArg::StrArray {
+      values,
+      truncated: true,
+    })
+  }
+}
+
+/// Renders **open(2)** `flags` like `O_WRONLY|O_CREAT|O_TRUNC`.
+fn open_flags(flags: i32) -> String {
+  const FLAG_NAMES: [(i32, &str); 10] = [
+    (libc::O_CREAT, "O_CREAT"),
+    (libc::O_EXCL, "O_EXCL"),
+    (libc::O_NOCTTY, "O_NOCTTY"),
+    (libc::O_TRUNC, "O_TRUNC"),
+    (libc::O_APPEND, "O_APPEND"),
+    (libc::O_NONBLOCK, "O_NONBLOCK"),
+    (libc::O_DIRECTORY, "O_DIRECTORY"),
+    (libc::O_NOFOLLOW, "O_NOFOLLOW"),
+    (libc::O_CLOEXEC, "O_CLOEXEC"),
+    (libc::O_PATH, "O_PATH"),
+  ];
+
+  let mut names = vec![match flags & libc::O_ACCMODE {
+    libc::O_RDONLY => "O_RDONLY".to_string(),
+    libc::O_WRONLY => "O_WRONLY".to_string(),
+    libc::O_RDWR => "O_RDWR".to_string(),
+    access_mode => format!("{:#x}", access_mode),
+  }];
+
+  let mut remaining = flags & !libc::O_ACCMODE;
+  for (flag, name) in FLAG_NAMES.iter() {
+    if remaining & flag != 0 {
+      names.push(name.to_string());
+      remaining &= !flag;
+    }
+  }
+  if remaining != 0 {
+    names.push(format!("{:#x}", remaining));
+  }
+
+  names.join("|")
+}
+
+/// Parses `struct sockaddr_in`, `struct sockaddr_in6` or `struct sockaddr_un`  
+/// from `bytes`, family being the first field of all of them.
+fn parse_socket_address(bytes: &[u8]) -> Option<SocketAddress> {
+  let family = u16::from_ne_bytes([*bytes.first()?, *bytes.get(1)?]);
+
+  match i32::from(family) {
+    libc::AF_INET if bytes.len() >= 8 => {
+      let port = u16::from_be_bytes([bytes[2], bytes[3]]);
+      let ip = Ipv4Addr::new(bytes[4], bytes[5], bytes[6], bytes[7]);
+      Some(SocketAddress::Inet(SocketAddr::V4(SocketAddrV4::new(
+        ip, port,
+      ))))
+    }
+    libc::AF_INET6 if bytes.len() >= 28 => {
+      let port = u16::from_be_bytes([bytes[2], bytes[3]]);
+      let flow_info = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+      let mut octets = [0u8; 16];
+      octets.copy_from_slice(&bytes[8..24]);
+      let scope_id = u32::from_ne_bytes([bytes[24], bytes[25], bytes[26], bytes[27]]);
+      Some(SocketAddress::Inet(SocketAddr::V6(SocketAddrV6::new(
+        Ipv6Addr::from(octets),
+        port,
+        flow_info,
+        scope_id,
+      ))))
+    }
+    libc::AF_UNIX => {
+      let path = &bytes[2..];
+      // Abstract socket names start with NUL byte and are not NUL terminated.
+      if let Some(name) = path.strip_prefix(&[0]) {
+        return Some(SocketAddress::Unix(format!(
+          "@{}",
+          String::from_utf8_lossy(name)
+        )));
+      }
+      let end = path.iter().position(|byte| *byte == 0).unwrap_or(path.len());
+      Some(SocketAddress::Unix(
+        String::from_utf8_lossy(&path[..end]).into_owned(),
+      ))
+    }
+    _ => Some(SocketAddress::Other(family)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{open_flags, parse_socket_address, SocketAddress};
+  use std::net::SocketAddr;
+
+  /// Returns `family` in native byte order followed by `rest`, like `struct sockaddr`.
+  fn socket_address(family: i32, rest: &[u8]) -> Vec<u8> {
+    let mut bytes = (family as u16).to_ne_bytes().to_vec();
+    bytes.extend_from_slice(rest);
+    bytes
+  }
+
+  #[test]
+  fn renders_open_flags() {
+    assert_eq!(open_flags(libc::O_RDONLY), "O_RDONLY");
+    assert_eq!(
+      open_flags(libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC),
+      "O_WRONLY|O_CREAT|O_TRUNC"
+    );
+    assert_eq!(
+      open_flags(libc::O_RDWR | libc::O_CLOEXEC | libc::O_DIRECTORY),
+      "O_RDWR|O_DIRECTORY|O_CLOEXEC"
+    );
+  }
+
+  #[test]
+  fn renders_unknown_open_flags_as_numbers() {
+    assert_eq!(
+      open_flags(libc::O_ACCMODE),
+      format!("{:#x}", libc::O_ACCMODE)
+    );
+    assert_eq!(
+      open_flags(libc::O_RDONLY | libc::O_CREAT | 0x4000_0000),
+      "O_RDONLY|O_CREAT|0x40000000"
+    );
+  }
+
+  #[test]
+  fn parses_inet_addresses() {
+    let address = socket_address(libc::AF_INET, &[0x1f, 0x90, 127, 0, 0, 1, 0, 0, 0, 0]);
+    assert_eq!(
+      parse_socket_address(&address),
+      Some(SocketAddress::Inet(
+        "127.0.0.1:8080".parse::<SocketAddr>().unwrap()
+      ))
+    );
+
+    let mut rest = vec![0x00, 0x35, 0, 0, 0, 0];
+    rest.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    rest.extend_from_slice(&0u32.to_ne_bytes());
+    assert_eq!(
+      parse_socket_address(&socket_address(libc::AF_INET6, &rest)),
+      Some(SocketAddress::Inet(
+        "[2001:db8::1]:53".parse::<SocketAddr>().unwrap()
+      ))
+    );
+  }
+
+  #[test]
+  fn parses_unix_addresses() {
+    assert_eq!(
+      parse_socket_address(&socket_address(libc::AF_UNIX, b"/run/app.sock\0garbage")),
+      Some(SocketAddress::Unix("/run/app.sock".to_string()))
+    );
+    assert_eq!(
+      parse_socket_address(&socket_address(libc::AF_UNIX, b"\0abstract")),
+      Some(SocketAddress::Unix("@abstract".to_string()))
+    );
+  }
+
+  #[test]
+  fn parses_truncated_and_other_addresses() {
+    assert_eq!(parse_socket_address(&[]), None);
+    assert_eq!(
+      parse_socket_address(&socket_address(libc::AF_INET, &[0x1f, 0x90, 127])),
+      Some(SocketAddress::Other(libc::AF_INET as u16))
+    );
+    assert_eq!(
+      parse_socket_address(&socket_address(libc::AF_NETLINK, &[0; 10])),
+      Some(SocketAddress::Other(libc::AF_NETLINK as u16))
+    );
+  }
+}
@@ -0,0 +1,248 @@
+//! Utilities for testing code built on top of this crate, without (see [`MockMemory`])  
+//! or with (see [`TargetFixture`]) real processes. Requires `testing` feature.
+//!
+//! [`MockMemory`]: struct.MockMemory.html
+//! [`TargetFixture`]: struct.TargetFixture.html
+
+use anyhow::Result;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub use super::mock::MockMemory;
+use super::Process;
+
+/// Source of the target program, compiled when fixture is spawned.
+const TARGET_SOURCE: &str = include_str!("../fixtures/target.rs");
+
+/// Name of the target program, short enough to fit in `/proc/\[pid\]/comm`.
+const TARGET_NAME: &str = "fixture_target";
+
+/// Running target program with variables of known values at known addresses,  
+/// for integration tests of code using this crate. The program is compiled  
+/// with **rustc** (or compiler pointed to by `RUSTC` environment variable)  
+/// into temporary directory and spawned as a child process. It is killed  
+/// and the directory is removed when the fixture is dropped.
+///
+/// Variables of the target, which can be passed to `address()`:  
+/// * `integer` - `u32` equal to [`TargetFixture::INTEGER`],  
+/// * `float` - `f32` equal to [`TargetFixture::FLOAT`],  
+/// * `string` - 16 byte array holding NUL-terminated [`TargetFixture::STRING`],  
+/// * `signature` - 8 byte array holding [`TargetFixture::SIGNATURE`],  
+/// * `pointer` - pointer to heap object, with `heap_value` at offset `0x10`,  
+/// * `heap_value` - `u32` on heap equal to [`TargetFixture::HEAP_VALUE`].
+///
+/// Variables except heap ones are also exported as `TRICKSTER_INTEGER`,  
+/// `TRICKSTER_FLOAT`, etc. symbols. The target runs two additional idle threads.
+///
+/// [`TargetFixture::INTEGER`]: struct.TargetFixture.html#associatedconstant.INTEGER
+/// [`TargetFixture::FLOAT`]: struct.TargetFixture.html#associatedconstant.FLOAT
+/// [`TargetFixture::STRING`]: struct.TargetFixture.html#associatedconstant.STRING
+/// [`TargetFixture::SIGNATURE`]: struct.TargetFixture.html#associatedconstant.SIGNATURE
+/// [`TargetFixture::HEAP_VALUE`]: struct.TargetFixture.html#associatedconstant.HEAP_VALUE
+///
+/// # Examples
+/// ```no_run
+/// extern crate trickster;
+/// use trickster::testing::TargetFixture;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///   let mut fixture = TargetFixture::spawn()?;
+///   let integer = fixture.address("integer")?;
+///
+///   assert_eq!(fixture.process().read::<u32>(integer)?, TargetFixture::INTEGER);
+///   fixture.process().write::<u32>(integer, &10)?;
+///   assert_eq!(fixture.query("integer")?, "10");
+///   Ok(())
+/// }
+/// ```
+pub struct TargetFixture {
+  child: Child,
+  stdin: ChildStdin,
+  stdout: BufReader<ChildStdout>,
+  directory: PathBuf,
+  addresses: HashMap<String, usize>,
+  process: Process,
+}
+
+impl TargetFixture {
+  /// Initial value of `integer` variable.
+  pub const INTEGER: u32 = 1337;
+  /// Initial value of `float` variable.
+  pub const FLOAT: f32 = 13.37;
+  /// Initial value of `string` variable.
+  pub const STRING: &'static str = "trickster";
+  /// Value of `signature` variable.
+  pub const SIGNATURE: [u8; 8] = [0xDE, 0xAD, 0xBE, 0xEF, 0x13, 0x37, 0xC0, 0xDE];
+  /// Initial value of `heap_value` variable.
+  pub const HEAP_VALUE: u32 = 4242;
+
+  /// TargetFixture object constructor. Compiles and spawns the target program  
+  /// and waits until it reports addresses of its variables. Memory regions  
+  /// of returned process are already parsed.
+  pub fn spawn() -> Result<TargetFixture> {
+    let directory = env::temp_dir().join(format!(
+      "trickster-fixture-{}-{}",
+      std::process::id(),
+      Self::next_id()
+    ));
+    fs::create_dir_all(&directory).map_err(|error| {
+      anyhow!(
+        "Could not create fixture directory {} ({}).",
+        directory.display(),
+        error
+      )
+    })?;
+
+    match Self::spawn_in(&directory) {
+      Ok(fixture) => Ok(fixture),
+      Err(error) => {
+        let _ = fs::remove_dir_all(&directory);
+        Err(error)
+      }
+    }
+  }
+
+  /// Returns unique number of fixture spawned by this process.
+  fn next_id() -> usize {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+  }
+
+  /// Compiles the target program in `directory`, spawns it and reads its addresses.
+  fn spawn_in(directory: &Path) -> Result<TargetFixture> {
+    let source_path = directory.join("target.rs");
+    let binary_path = directory.join(TARGET_NAME);
+    fs::write(&source_path, TARGET_SOURCE)?;
+
+    let compiler = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    let output = Command::new(&compiler)
+      .args(["--edition", "2021", "-C", "debuginfo=0", "-o"])
+      .arg(&binary_path)
+      .arg(&source_path)
+      .output()
+      .map_err(|error| anyhow!("Could not run {} ({}).", compiler, error))?;
+    if !output.status.success() {
+      return Err(anyhow!(
+        "Could not compile fixture target ({}).",
+        String::from_utf8_lossy(&output.stderr).trim()
+      ));
+    }
+
+    let mut child = Command::new(&binary_path)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .map_err(|error| anyhow!("Could not spawn fixture target ({}).", error))?;
+    let stdin = child.stdin.take().expect("Could not take stdin of fixture target.");
+    let mut stdout =
+      BufReader::new(child.stdout.take().expect("Could not take stdout of fixture target."));
+
+    // Process object is built only after the target reports its addresses,
+    // as until it executes, the child still runs the program of this process.
+    let process = Self::read_addresses(&mut stdout).and_then(|addresses| {
+      let mut process = Process::from_pid(Pid::from_raw(child.id() as i32))?;
+      process.parse_maps()?;
+      Ok((addresses, process))
+    });
+    let (addresses, process) = match process {
+      Ok(result) => result,
+      Err(error) => {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(error);
+      }
+    };
+
+    Ok(TargetFixture {
+      child,
+      stdin,
+      stdout,
+      directory: directory.to_path_buf(),
+      addresses,
+      process,
+    })
+  }
+
+  /// Reads addresses of variables written by the target, until it reports it is ready.
+  fn read_addresses(stdout: &mut BufReader<ChildStdout>) -> Result<HashMap<String, usize>> {
+    let mut addresses = HashMap::new();
+
+    loop {
+      let line = Self::read_line(stdout)?;
+      if line == "ready" {
+        return Ok(addresses);
+      }
+      let (name, address) = line
+        .split_once('=')
+        .and_then(|(name, address)| {
+          usize::from_str_radix(address.trim_start_matches("0x"), 16)
+            .ok()
+            .map(|address| (name.to_string(), address))
+        })
+        .ok_or_else(|| anyhow!("Could not parse fixture target output ({}).", line))?;
+      addresses.insert(name, address);
+    }
+  }
+
+  /// Reads single line written by the target to `stdout`, without line terminator.
+  fn read_line(stdout: &mut BufReader<ChildStdout>) -> Result<String> {
+    let mut line = String::new();
+    if stdout.read_line(&mut line)? == 0 {
+      return Err(anyhow!(
+        "Could not read fixture target output (target exited)."
+      ));
+    }
+    Ok(line.trim_end().to_string())
+  }
+
+  /// Returns process id of the target.
+  pub fn pid(&self) -> Pid {
+    self.process.get_pid()
+  }
+
+  /// Returns process object of the target.
+  pub fn process(&self) -> &Process {
+    &self.process
+  }
+
+  /// Returns mutable process object of the target, e.g. for `attach()`.
+  pub fn process_mut(&mut self) -> &mut Process {
+    &mut self.process
+  }
+
+  /// Returns address of variable `name` of the target (see [`TargetFixture`]).  
+  /// If there is no such variable, [`Err`] is returned.
+  ///
+  /// [`TargetFixture`]: struct.TargetFixture.html
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub fn address(&self, name: &str) -> Result<usize> {
+    self
+      .addresses
+      .get(name)
+      .copied()
+      .ok_or_else(|| anyhow!("Could not find fixture variable {}.", name))
+  }
+
+  /// Asks the target for current value of variable `name` (except `signature`  
+  /// and `pointer`) formatted as string, so tests can verify that writes are  
+  /// visible to the target itself. Returns `unknown` for unknown variables.
+  pub fn query(&mut self, name: &str) -> Result<String> {
+    writeln!(self.stdin, "get {}", name)?;
+    self.stdin.flush()?;
+    Self::read_line(&mut self.stdout)
+  }
+}
+
+impl Drop for TargetFixture {
+  fn drop(&mut self) {
+    let _ = self.child.kill();
+    let _ = self.child.wait();
+    let _ = fs::remove_dir_all(&self.directory);
+  }
+}
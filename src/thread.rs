@@ -0,0 +1,170 @@
+use anyhow::Result;
+use nix::unistd::Pid;
+use std::fs;
+use std::path;
+
+use super::stat::read_stat;
+use super::{MemoryRegion, Process, ProcessStat, ProcessState};
+
+/// Thread of a process, described by `/proc/\[pid\]/task/\[tid\]/` directory.  
+/// Values are read when thread list is built, use `Thread::stat()`  
+/// to get current ones.
+#[derive(Clone, Debug)]
+pub struct Thread {
+  /// Process id of the process thread belongs to (thread group id).
+  pub pid: Pid,
+  /// Thread id. Thread with `tid` equal to `pid` is the main thread.
+  pub tid: Pid,
+  /// Name of the thread, which can differ from process name  
+  /// (see **pthread_setname_np(3)**).
+  pub name: String,
+  pub state: ProcessState,
+}
+
+impl Thread {
+  /// Returns path of `/proc/\[pid\]/task/\[tid\]/` directory.
+  pub(crate) fn task_path(&self) -> path::PathBuf {
+    task_directory(self.pid).join(self.tid.to_string())
+  }
+
+  /// Returns **true** if this is the main thread of the process.
+  pub fn is_main_thread(&self) -> bool {
+    self.pid == self.tid
+  }
+
+  /// Reads `/proc/\[pid\]/task/\[tid\]/stat` file and returns  
+  /// current status information about the thread.
+  pub fn stat(&self) -> Result<ProcessStat> {
+    read_stat(&self.task_path().join("stat"))
+  }
+
+  /// Returns current stack pointer of the thread, if it can be found  
+  /// without stopping the thread. It is read from `/proc/\[pid\]/task/\[tid\]/syscall`  
+  /// file (available when thread is blocked, not running), falling back to  
+  /// `kstkesp` field of `/proc/\[pid\]/task/\[tid\]/stat` file.
+  pub fn stack_pointer(&self) -> Option<usize> {
+    // File contains "running" or syscall number and arguments
+    // followed by stack pointer and program counter.
+    let syscall_stack_pointer =
+      fs::read_to_string(self.task_path().join("syscall")).ok().and_then(|syscall| {
+        let fields: Vec<&str> = syscall.split_whitespace().collect();
+        match fields.len() {
+          0 | 1 => None,
+          length => usize::from_str_radix(fields[length - 2].trim_start_matches("0x"), 16).ok(),
+        }
+      });
+
+    syscall_stack_pointer.or_else(|| {
+      self
+        .stat()
+        .ok()
+        .map(|stat| stat.kstkesp as usize)
+        .filter(|stack_pointer| *stack_pointer != 0)
+    })
+  }
+
+  /// Returns memory region containing stack of the thread, located  
+  /// by current stack pointer of the thread (see `stack_pointer()`).  
+  /// For the main thread, `start_stack` field of `/proc/\[pid\]/stat`  
+  /// is used when stack pointer is not available. If the region  
+  /// can not be found, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: `parse_maps();` should be called minimum once  
+  /// before calling `stack_region();`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, Signature};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let signature = Signature::new("EF BE AD DE")?;
+  ///   for thread in ctx.threads()? {
+  ///     if let Ok(stack) = thread.stack_region(&ctx) {
+  ///       println!("{}: {:x?}", thread.tid, ctx.scan_region(stack, &signature)?);
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn stack_region<'a>(&self, process: &'a Process) -> Result<&'a MemoryRegion> {
+    let stack_pointer = match self.stack_pointer() {
+      Some(stack_pointer) => stack_pointer,
+      None if self.is_main_thread() => process.stat()?.start_stack as usize,
+      None => {
+        return Err(anyhow!(
+          "Could not get stack pointer of thread {}.",
+          self.tid
+        ))
+      }
+    };
+
+    process.get_address_region(stack_pointer)
+  }
+}
+
+/// Returns path of `/proc/\[pid\]/task/` directory.
+fn task_directory(pid: Pid) -> path::PathBuf {
+  path::Path::new("/proc/").join(pid.to_string()).join("task")
+}
+
+impl Process {
+  /// Returns all threads of the process, sorted by thread id, by iterating  
+  /// over `/proc/\[pid\]/task/` directory. Threads which exit while iterating are skipped.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   for thread in ctx.threads()? {
+  ///     println!("{} {} {:?}", thread.tid, thread.name, thread.state);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `2686 current_process Sleeping`  
+  /// `2690 RenderThread Running`
+  pub fn threads(&self) -> Result<Vec<Thread>> {
+    let task_list = fs::read_dir(task_directory(self.get_pid())).map_err(|error| {
+      anyhow!(
+        "Could not list threads of process {} ({}).",
+        self.get_pid(),
+        error
+      )
+    })?;
+
+    let mut threads: Vec<Thread> = Vec::new();
+
+    for task in task_list.filter_map(|task| task.ok()) {
+      let tid = match task.file_name().to_str().map(str::parse::<i32>) {
+        Some(Ok(tid)) => Pid::from_raw(tid),
+        _ => continue,
+      };
+
+      let stat = match read_stat(&task.path().join("stat")) {
+        Ok(stat) => stat,
+        Err(_) => continue,
+      };
+
+      threads.push(Thread {
+        pid: self.get_pid(),
+        tid,
+        name: stat.comm,
+        state: stat.state,
+      });
+    }
+
+    threads.sort_by_key(|thread| thread.tid.as_raw());
+    Ok(threads)
+  }
+}
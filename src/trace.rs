@@ -0,0 +1,1060 @@
+use anyhow::Result;
+use nix::errno::Errno;
+use nix::sys::ptrace;
+use nix::fcntl::OFlag;
+use nix::sys::signal::{self, Signal};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::{self, ForkResult, Pid};
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::fmt;
+use std::iter;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::os::unix::io::RawFd;
+use std::ptr;
+use std::result;
+use std::thread;
+use std::time::Duration;
+
+use super::error::system_call_error;
+use super::Process;
+
+/// Interval between checks of traced threads while other child  
+/// of the calling process has status pending, see `wait_for_stop()`.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Thread of traced process together with its tracing state.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TracedThread {
+  pub(crate) tid: Pid,
+  /// Process id of the process thread belongs to.
+  pub(crate) pid: Pid,
+  /// Whether thread is in ptrace-stop, so ptrace requests can be issued for it.
+  pub(crate) stopped: bool,
+  /// Whether thread is inside system call, so its next syscall-stop is exit.
+  pub(crate) in_syscall: bool,
+}
+
+/// Process traced with [**ptrace(2)**](http://man7.org/linux/man-pages/man2/ptrace.2.html),  
+/// created by `Process::attach()`. All threads of the process are attached  
+/// and stopped. Tracer is detached from all of them when `TracedProcess`  
+/// is dropped (or `detach()` is called), which lets the process continue.  
+/// Child processes are traced too when following forks is enabled  
+/// with `set_follow_forks()`.
+///
+/// `TracedProcess` dereferences to [`Process`], so all memory reading  
+/// and writing methods can be used directly on it.
+///
+/// [`Process`]: struct.Process.html
+pub struct TracedProcess {
+  process: Process,
+  /// Traced threads, kept in a cell so that system calls can be injected  
+  /// (see `remote_syscall()`) through shared reference.
+  threads: RefCell<Vec<TracedThread>>,
+  /// Ids of traced processes, the first one being the process which was attached to.
+  processes: RefCell<Vec<Pid>>,
+  /// Thread which reported the last stop in `wait_for_stop()`.
+  last_stop: Option<Pid>,
+  /// Whether threads are resumed with **PTRACE_SYSCALL** instead of **PTRACE_CONT**.
+  syscall_tracing: bool,
+  /// Numbers of system calls reported by `events()`, all if [`None`].
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  syscall_filter: Option<Vec<i64>>,
+  /// Whether child processes are attached automatically.
+  follow_forks: bool,
+  detached: bool,
+}
+
+impl Process {
+  /// Attaches to all threads of the process with **PTRACE_SEIZE** and stops them  
+  /// with **PTRACE_INTERRUPT**. Threads created while attaching are attached too,  
+  /// threads created later are attached automatically (see `events()`).  
+  /// Only one tracer can be attached to a thread at a time, so attaching fails  
+  /// if the process is already traced (e.g. by a debugger).
+  ///
+  /// This requires the same permissions as reading process memory,  
+  /// see `read_memory()`.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let ctx = Process::new("current_process_name")?;
+  ///
+  ///   let traced = ctx.attach()?;
+  ///   // Process is stopped now, so it can not race with these writes.
+  ///   traced.write::<u32>(0x601040, &100)?;
+  ///   traced.write::<u32>(0x601044, &100)?;
+  ///   traced.detach()?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn attach(&self) -> Result<TracedProcess> {
+    let traced = TracedProcess::new(self.clone(), Vec::new());
+
+    // Threads can be created while attaching, so keep listing them until
+    // there are no new ones. Threads are listed again only once all attached
+    // ones are stopped, so threads created right after listing by threads which
+    // were still running are not missed. Tracing options are set only once
+    // all threads are stopped, as threads attached automatically because
+    // of PTRACE_O_TRACECLONE could not be seized again.
+    loop {
+      let mut attached_any = false;
+      for thread in self.threads()? {
+        if traced.threads.borrow().iter().any(|traced_thread| traced_thread.tid == thread.tid) {
+          continue;
+        }
+
+        match ptrace::seize(thread.tid, ptrace::Options::empty()) {
+          Ok(()) => {}
+          // Thread exited after it was listed.
+          Err(nix::Error::Sys(Errno::ESRCH)) => continue,
+          Err(error) => {
+            return Err(system_call_error(
+              thread.tid,
+              error,
+              format!("Could not attach to thread {}", thread.tid),
+            ))
+          }
+        }
+
+        traced.threads.borrow_mut().push(TracedThread {
+          tid: thread.tid,
+          pid: thread.pid,
+          stopped: false,
+          in_syscall: false,
+        });
+        attached_any = true;
+      }
+
+      if !attached_any {
+        break;
+      }
+      traced.stop_all()?;
+    }
+
+    traced.set_options()?;
+    Ok(traced)
+  }
+
+  /// Starts program at `path` with arguments `args` (program path being passed  
+  /// as the first argument) under trace, so it can be inspected and modified  
+  /// before any of its code runs. Returned process is stopped right after  
+  /// **execve(2)**, at the entry point of the dynamic loader (or of the program  
+  /// itself, if it is statically linked). Environment and standard streams  
+  /// are inherited from the calling process. `path` is not looked up in **PATH**.
+  ///
+  /// If program could not be executed, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::spawn("/usr/bin/current_process_name", &["--windowed"])?;
+  ///   let tid = traced.get_pid();
+  ///   println!("stopped at {:#x}", traced.get_regs(tid)?.instruction_pointer());
+  ///
+  ///   traced.cont()?;
+  ///   println!("{:?}", traced.wait_for_stop()?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `stopped at 0x7f3a4c1e2290`  
+  /// `Exited(Pid(2686), 0)`
+  pub fn spawn(path: &str, args: &[&str]) -> Result<TracedProcess> {
+    let spawn_error = |error: &dyn fmt::Display| anyhow!("Could not spawn {} ({}).", path, error);
+
+    // Everything child needs is allocated before fork(2), since
+    // only async-signal-safe functions can be called in the child.
+    let path_cstring = CString::new(path).map_err(|error| spawn_error(&error))?;
+    let arg_cstrings = iter::once(path)
+      .chain(args.iter().cloned())
+      .map(CString::new)
+      .collect::<result::Result<Vec<CString>, _>>()
+      .map_err(|error| spawn_error(&error))?;
+    let mut argv: Vec<*const libc::c_char> = arg_cstrings.iter().map(|arg| arg.as_ptr()).collect();
+    argv.push(ptr::null());
+
+    // Child reports execve(2) error number through the pipe,
+    // which is closed without writing anything on success.
+    let (error_reader, error_writer) =
+      unistd::pipe2(OFlag::O_CLOEXEC).map_err(|error| spawn_error(&error))?;
+
+    let child = match unistd::fork() {
+      Ok(ForkResult::Child) => unsafe {
+        // Child stops itself, so the parent can seize it before execve(2).
+        // PTRACE_TRACEME is not used, because PTRACE_INTERRUPT (used to stop
+        // threads later on) works only for tracees attached with PTRACE_SEIZE.
+        libc::close(error_reader);
+        libc::raise(libc::SIGSTOP);
+        libc::execv(path_cstring.as_ptr(), argv.as_ptr());
+
+        let errno = *libc::__errno_location();
+        libc::write(
+          error_writer,
+          &errno as *const libc::c_int as *const libc::c_void,
+          mem::size_of::<libc::c_int>(),
+        );
+        libc::_exit(127)
+      },
+      Ok(ForkResult::Parent { child }) => child,
+      Err(error) => {
+        let _ = unistd::close(error_reader);
+        let _ = unistd::close(error_writer);
+        return Err(spawn_error(&error));
+      }
+    };
+
+    let _ = unistd::close(error_writer);
+    let result = trace_spawned_child(child, error_reader)
+      .map_err(|error| spawn_error(&error))
+      .and_then(|()| Process::from_pid(child));
+    let _ = unistd::close(error_reader);
+
+    let process = match result {
+      Ok(process) => process,
+      Err(error) => {
+        let _ = signal::kill(child, Signal::SIGKILL);
+        let _ = waitpid(child, None);
+        return Err(error);
+      }
+    };
+
+    let traced = TracedProcess::new(
+      process,
+      // Child is stopped at exec event, inside execve(2).
+      vec![TracedThread {
+        tid: child,
+        pid: child,
+        stopped: true,
+        in_syscall: true,
+      }],
+    );
+    Ok(traced)
+  }
+}
+
+/// Seizes spawned `child` stopped before **execve(2)**, lets it continue  
+/// and waits until it reports **PTRACE_EVENT_EXEC** stop. If child exits  
+/// instead, error number is read from `error_reader` end of its error pipe.
+fn trace_spawned_child(child: Pid, error_reader: RawFd) -> Result<()> {
+  match waitpid(child, Some(WaitPidFlag::WSTOPPED))? {
+    WaitStatus::Stopped(_, Signal::SIGSTOP) => {}
+    status => return Err(anyhow!("unexpected child status {:?}", status)),
+  }
+
+  ptrace::seize(child, tracing_options(false))?;
+  signal::kill(child, Signal::SIGCONT)?;
+
+  loop {
+    match waitpid(child, Some(WaitPidFlag::__WALL))? {
+      WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_EXEC) => return Ok(()),
+      WaitStatus::Exited(..) | WaitStatus::Signaled(..) => {
+        let mut errno_bytes = [0u8; mem::size_of::<libc::c_int>()];
+        return match unistd::read(error_reader, &mut errno_bytes) {
+          Ok(length) if length == errno_bytes.len() => {
+            Err(Errno::from_i32(libc::c_int::from_ne_bytes(errno_bytes)).into())
+          }
+          _ => Err(anyhow!("child exited before execve")),
+        };
+      }
+      // Delivery of SIGCONT and stops caused by it.
+      WaitStatus::Stopped(_, signal) => resume_with(child, Some(signal), ptrace::cont)?,
+      _ => resume_with(child, None, ptrace::cont)?,
+    }
+  }
+}
+
+/// Returns options set for all traced threads (see `TracedProcess::set_options()`).
+fn tracing_options(follow_forks: bool) -> ptrace::Options {
+  let mut options = ptrace::Options::PTRACE_O_TRACECLONE
+    | ptrace::Options::PTRACE_O_TRACEEXEC
+    | ptrace::Options::PTRACE_O_TRACESYSGOOD;
+  if follow_forks {
+    options |= ptrace::Options::PTRACE_O_TRACEFORK | ptrace::Options::PTRACE_O_TRACEVFORK;
+  }
+  options
+}
+
+impl TracedProcess {
+  /// Builds traced process object for `process` with already attached `threads`.
+  fn new(process: Process, threads: Vec<TracedThread>) -> TracedProcess {
+    TracedProcess {
+      processes: RefCell::new(vec![process.get_pid()]),
+      process,
+      threads: RefCell::new(threads),
+      last_stop: None,
+      syscall_tracing: false,
+      syscall_filter: None,
+      follow_forks: false,
+      detached: false,
+    }
+  }
+
+  /// Returns ids of all traced threads.
+  pub fn traced_threads(&self) -> Vec<Pid> {
+    self.threads.borrow().iter().map(|thread| thread.tid).collect()
+  }
+
+  /// Returns **true** if thread `tid` is traced and currently stopped.
+  pub fn is_stopped(&self, tid: Pid) -> bool {
+    self.threads.borrow().iter().any(|thread| thread.tid == tid && thread.stopped)
+  }
+
+  /// Executes a single instruction of stopped traced thread `tid` with  
+  /// **PTRACE_SINGLESTEP**, other threads stay stopped. Thread stops  
+  /// again after the instruction, use `wait_for_stop()` to wait for it.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///   let tid = traced.get_pid();
+  ///
+  ///   for _ in 0..5 {
+  ///     traced.step(tid)?;
+  ///     traced.wait_for_stop()?;
+  ///     println!("{:#x}", traced.get_regs(tid)?.instruction_pointer());
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `0x7f0c8e4b1d3e`  
+  /// `0x7f0c8e4b1d40`
+  pub fn step(&mut self, tid: Pid) -> Result<()> {
+    self.ensure_stopped(tid)?;
+
+    ptrace::step(tid, None).map_err(|error| {
+      system_call_error(
+        tid,
+        error,
+        format!("Could not single-step thread {}", tid),
+      )
+    })?;
+
+    self.set_stopped(tid, false);
+    Ok(())
+  }
+
+  /// Resumes all stopped traced threads with **PTRACE_CONT** (or **PTRACE_SYSCALL**,  
+  /// see `set_syscall_tracing()`). Signal which stopped a thread is suppressed,  
+  /// use `cont_with_signal()` to deliver it.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   traced.cont()?;
+  ///   println!("{:?}", traced.wait_for_stop()?);
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `Stopped(Pid(2686), SIGSEGV)`
+  pub fn cont(&mut self) -> Result<()> {
+    self.cont_all(None)
+  }
+
+  /// Resumes all stopped traced threads with **PTRACE_CONT**, delivering  
+  /// `signal` to the thread which reported the last stop in `wait_for_stop()`  
+  /// (or to the main thread if there was none). This is usually used  
+  /// to pass signal which stopped the thread on to the process.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, WaitStatus};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   traced.cont()?;
+  ///   if let WaitStatus::Stopped(_, signal) = traced.wait_for_stop()? {
+  ///     traced.cont_with_signal(signal)?;
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn cont_with_signal(&mut self, signal: Signal) -> Result<()> {
+    self.cont_all(Some(signal))
+  }
+
+  /// Blocks until one of running traced threads stops or exits and returns  
+  /// its wait status. Exited threads are no longer traced. If no traced  
+  /// thread is running, [`Err`] is returned instead of blocking forever.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// **NOTE**: Statuses of other children of the calling process  
+  /// (including ones traced by other `TracedProcess`) are not consumed,  
+  /// but while any of them is pending, waiting is done by polling.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, WaitStatus};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///
+  ///   traced.cont()?;
+  ///   match traced.wait_for_stop()? {
+  ///     WaitStatus::Exited(_, code) => println!("exited with {}", code),
+  ///     status => println!("{:?}", status),
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `exited with 0`
+  pub fn wait_for_stop(&mut self) -> Result<WaitStatus> {
+    let mut polling = false;
+
+    loop {
+      let mut running: Vec<Pid> = self
+        .threads
+        .borrow()
+        .iter()
+        .filter(|thread| !thread.stopped)
+        .map(|thread| thread.tid)
+        .collect();
+      if running.is_empty() {
+        return Err(anyhow!(
+          "Could not wait for process {} (no traced thread is running).",
+          self.get_pid()
+        ));
+      }
+      // Threads after the one which reported the last stop are checked first,
+      // so a thread stopping all the time can not starve the others.
+      if let Some(index) = running.iter().position(|tid| Some(*tid) == self.last_stop) {
+        running.rotate_left(index + 1);
+      }
+
+      for tid in running {
+        let status = match waitpid(tid, Some(WaitPidFlag::__WALL | WaitPidFlag::WNOHANG)) {
+          Ok(WaitStatus::StillAlive) => continue,
+          Ok(status) => status,
+          // Thread is gone, but its exit was not reported.
+          Err(nix::Error::Sys(Errno::ECHILD)) => {
+            self.threads.borrow_mut().retain(|thread| thread.tid != tid);
+            continue;
+          }
+          Err(error) => {
+            return Err(anyhow!(
+              "Could not wait for process {} ({}).",
+              self.get_pid(),
+              error
+            ))
+          }
+        };
+
+        match status {
+          WaitStatus::Exited(..) | WaitStatus::Signaled(..) => {
+            self.threads.borrow_mut().retain(|thread| thread.tid != tid)
+          }
+          WaitStatus::PtraceSyscall(_) => {
+            self.set_stopped(tid, true);
+            self.update_thread(tid, |thread| thread.in_syscall = !thread.in_syscall);
+          }
+          // System call interrupted by the stop is restarted after it,
+          // so the next syscall-stop is its entry again.
+          status if is_interrupt_stop(status) => {
+            self.set_stopped(tid, true);
+            self.update_thread(tid, |thread| thread.in_syscall = false);
+          }
+          _ => self.set_stopped(tid, true),
+        }
+
+        self.last_stop = Some(tid);
+        return Ok(status);
+      }
+
+      // Status which is pending, but not of any traced thread, belongs to other
+      // child of the calling process. It is left for the caller to wait for,
+      // so it keeps waking up waiting below until then, which becomes polling.
+      if polling {
+        thread::sleep(WAIT_POLL_INTERVAL);
+      }
+      wait_for_child_status().map_err(|error| {
+        anyhow!(
+          "Could not wait for process {} ({}).",
+          self.get_pid(),
+          error
+        )
+      })?;
+      polling = true;
+    }
+  }
+
+  /// Enables or disables system call tracing. When enabled, threads are resumed  
+  /// with **PTRACE_SYSCALL**, so they stop at every system call entry and exit,  
+  /// which are reported by `events()`. It is disabled by default, since stopping  
+  /// at every system call slows the process down considerably.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, TraceEvent};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///   traced.set_syscall_tracing(true);
+  ///
+  ///   for event in traced.events() {
+  ///     if let TraceEvent::SyscallEnter { tid, number, args } = event? {
+  ///       println!("{}: syscall {} {:x?}", tid, number, args);
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `2686: syscall 0 [3, 7ffd1c2a3b40, 1000, 0, 0, 0]`
+  pub fn set_syscall_tracing(&mut self, enabled: bool) {
+    self.syscall_tracing = enabled;
+  }
+
+  /// Limits system calls reported by `events()` to ones with given `numbers`  
+  /// (e.g. `libc::SYS_openat`), or reports all of them if `numbers` is [`None`].  
+  /// Threads still stop at every system call, others are resumed without  
+  /// being reported. It has no effect unless system call tracing is enabled  
+  /// with `set_syscall_tracing()`.
+  ///
+  /// [`None`]: https://doc.rust-lang.org/std/option/
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate libc;
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///   traced.set_syscall_tracing(true);
+  ///   traced.set_syscall_filter(Some(&[libc::SYS_openat, libc::SYS_connect]));
+  ///
+  ///   for event in traced.events() {
+  ///     println!("{:?}", event?);
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn set_syscall_filter(&mut self, numbers: Option<&[i64]>) {
+    self.syscall_filter = numbers.map(|numbers| numbers.to_vec());
+  }
+
+  /// Enables or disables following of child processes. When enabled, processes  
+  /// created by traced threads with **fork(2)**, **vfork(2)** and **clone(2)** are  
+  /// attached automatically (**PTRACE_O_TRACEFORK**, **PTRACE_O_TRACEVFORK**),  
+  /// reported as `TraceEvent::ProcessForked` and traced the same way as the process  
+  /// which was attached to. It is disabled by default.
+  ///
+  /// Tracing options can be changed only for stopped threads, so all traced  
+  /// threads have to be stopped (as they are after `Process::attach()`).
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::{Process, TraceEvent};
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut traced = Process::new("current_process_name")?.attach()?;
+  ///   traced.set_follow_forks(true)?;
+  ///
+  ///   for event in traced.events() {
+  ///     match event? {
+  ///       TraceEvent::ProcessForked { tid, child } => println!("{} forked {}", tid, child),
+  ///       TraceEvent::Exec { pid } => println!("{} executed new program", pid),
+  ///       _ => {}
+  ///     }
+  ///   }
+  ///   Ok(())
+  /// }
+  /// ```
+  /// This prints output like:  
+  /// `2686 forked 2701`  
+  /// `2701 executed new program`
+  pub fn set_follow_forks(&mut self, enabled: bool) -> Result<()> {
+    for tid in self.traced_threads() {
+      self.ensure_stopped(tid)?;
+    }
+
+    self.follow_forks = enabled;
+    self.set_options()
+  }
+
+  /// Returns ids of all traced processes, the first one being  
+  /// the process which was attached to.
+  pub fn traced_processes(&self) -> Vec<Pid> {
+    self.processes.borrow().clone()
+  }
+
+  /// Writes `value` at `address` with single **PTRACE_POKEDATA** request, so threads  
+  /// reading it concurrently see either old or new value, never mix of both  
+  /// like with **process_vm_writev(2)** used by `write()`. It is meant for flipping  
+  /// flags and hook pointers which running threads of the process are using.  
+  /// If `address` is not aligned to size of `usize`, [`Err`] is returned.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  ///
+  /// Request is issued for stopped thread of the process. If all of its threads  
+  /// are running, one of them is stopped only for the duration of the write.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// extern crate trickster;
+  /// use trickster::Process;
+  ///
+  /// fn main() -> Result<(), Box<dyn std::error::Error>> {
+  ///   let mut ctx = Process::new("current_process_name")?;
+  ///   ctx.parse_maps()?;
+  ///
+  ///   let mut traced = ctx.attach()?;
+  ///   traced.cont()?;
+  ///   let hook_pointer = ctx.resolve_symbol("game", "on_damage_callback")?;
+  ///   let detour = ctx.resolve_symbol("libhook.so", "hooked_on_damage")?;
+  ///   traced.write_word_atomic(hook_pointer, detour)?;
+  ///   Ok(())
+  /// }
+  /// ```
+  pub fn write_word_atomic(&self, address: usize, value: usize) -> Result<()> {
+    if !address.is_multiple_of(mem::size_of::<usize>()) {
+      return Err(anyhow!(
+        "Could not write word at {:#x} atomically (address is not aligned).",
+        address
+      ));
+    }
+    self.verify_identity()?;
+
+    let pid = self.get_pid();
+    let threads: Vec<TracedThread> = self
+      .threads
+      .borrow()
+      .iter()
+      .filter(|thread| thread.pid == pid)
+      .cloned()
+      .collect();
+    let (tid, interrupted) = match threads.iter().find(|thread| thread.stopped) {
+      Some(thread) => (thread.tid, false),
+      None => {
+        let tid = threads
+          .iter()
+          .map(|thread| thread.tid)
+          .min_by_key(|tid| *tid != pid)
+          .ok_or_else(|| anyhow!("Could not write word at {:#x} (no traced thread).", address))?;
+        if !self.interrupt_thread(tid)? {
+          return Err(anyhow!(
+            "Could not write word at {:#x} (thread {} exited).",
+            address,
+            tid
+          ));
+        }
+        (tid, true)
+      }
+    };
+
+    let result = ptrace::write(tid, address as ptrace::AddressType, value as *mut libc::c_void);
+    if interrupted {
+      self.resume(tid, None)?;
+    }
+    result.map_err(|error| {
+      system_call_error(tid, error, format!("Could not write word at {:#x}", address))
+    })?;
+
+    self.verify_write(address, &value.to_ne_bytes())
+  }
+
+  /// Sets tracing options of all traced threads, which have to be stopped.  
+  /// **PTRACE_O_TRACECLONE** attaches new threads, **PTRACE_O_TRACEEXEC**  
+  /// reports program execution and **PTRACE_O_TRACESYSGOOD** makes syscall-stops  
+  /// distinguishable from breakpoints. Threads and processes attached automatically  
+  /// inherit options of the thread which created them.
+  fn set_options(&self) -> Result<()> {
+    let options = tracing_options(self.follow_forks);
+
+    for tid in self.traced_threads() {
+      ptrace::setoptions(tid, options).map_err(|error| {
+        system_call_error(
+          tid,
+          error,
+          format!("Could not set tracing options of thread {}", tid),
+        )
+      })?;
+    }
+
+    Ok(())
+  }
+
+  /// Returns **true** if system call `number` passes the filter  
+  /// set with `set_syscall_filter()`.
+  pub(crate) fn is_syscall_reported(&self, number: i64) -> bool {
+    self
+      .syscall_filter
+      .as_ref()
+      .is_none_or(|numbers| numbers.contains(&number))
+  }
+
+  /// Detaches from all threads of the process, letting it continue.  
+  /// Threads which are running are stopped first, since ptrace  
+  /// requests can be issued only for stopped threads.
+  pub fn detach(mut self) -> Result<()> {
+    self.detach_all()
+  }
+
+  /// Stops all running threads with **PTRACE_INTERRUPT**  
+  /// and waits until they report ptrace-stop. Threads created  
+  /// in the meantime are stopped too.
+  pub(crate) fn stop_all(&self) -> Result<()> {
+    loop {
+      let running: Vec<Pid> = self
+        .threads
+        .borrow()
+        .iter()
+        .filter(|thread| !thread.stopped)
+        .map(|thread| thread.tid)
+        .collect();
+      if running.is_empty() {
+        return Ok(());
+      }
+
+      for tid in running {
+        self.interrupt_thread(tid)?;
+      }
+    }
+  }
+
+  /// Stops running thread `tid` with **PTRACE_INTERRUPT** and returns **true**  
+  /// once it is stopped, or **false** if it exited instead, which is no longer  
+  /// traced then. Threads and processes it created in the meantime are traced  
+  /// from now on, as they are attached automatically (see `set_options()`).
+  fn interrupt_thread(&self, tid: Pid) -> Result<bool> {
+    interrupt(tid)?;
+    let mut created: Vec<(i32, Pid)> = Vec::new();
+    let stopped = wait_for_interrupt(tid, &mut created);
+
+    for (event, new_tid) in created {
+      if event == libc::PTRACE_EVENT_CLONE {
+        self.add_thread(new_tid, self.thread_pid(tid).unwrap_or(tid));
+      } else {
+        self.add_process(new_tid);
+      }
+    }
+
+    let stopped = stopped?;
+    if stopped {
+      // Syscall-stops could be skipped while waiting, but the thread is not
+      // inside system call in interrupt stop, see `wait_for_stop()`.
+      self.update_thread(tid, |thread| {
+        thread.stopped = true;
+        thread.in_syscall = false;
+      });
+    } else {
+      self.threads.borrow_mut().retain(|thread| thread.tid != tid);
+    }
+    Ok(stopped)
+  }
+
+  /// Returns [`Err`] if thread `tid` is not traced or is not stopped.
+  ///
+  /// [`Err`]: https://doc.rust-lang.org/std/result/
+  pub(crate) fn ensure_stopped(&self, tid: Pid) -> Result<()> {
+    match self.threads.borrow().iter().find(|thread| thread.tid == tid) {
+      Some(thread) if thread.stopped => Ok(()),
+      Some(_) => Err(anyhow!("Thread {} is not stopped.", tid)),
+      None => Err(anyhow!("Thread {} is not traced.", tid)),
+    }
+  }
+
+  /// Marks thread `tid` as stopped or running.
+  pub(crate) fn set_stopped(&self, tid: Pid, stopped: bool) {
+    self.update_thread(tid, |thread| thread.stopped = stopped);
+  }
+
+  /// Returns **true** if thread `tid` stopped at system call entry rather than exit,  
+  /// which is tracked from syscall-stops reported by `wait_for_stop()`.
+  pub(crate) fn is_in_syscall(&self, tid: Pid) -> bool {
+    self.threads.borrow().iter().any(|thread| thread.tid == tid && thread.in_syscall)
+  }
+
+  /// Applies `update` to tracing state of thread `tid`, if it is traced.
+  fn update_thread<F: FnOnce(&mut TracedThread)>(&self, tid: Pid, update: F) {
+    if let Some(thread) = self.threads.borrow_mut().iter_mut().find(|thread| thread.tid == tid) {
+      update(thread);
+    }
+  }
+
+  /// Resumes all stopped threads, delivering `signal` to the thread  
+  /// which reported the last stop (see `cont_with_signal()`).
+  fn cont_all(&mut self, signal: Option<Signal>) -> Result<()> {
+    let signal_tid = self.last_stop.unwrap_or_else(|| self.get_pid());
+    if signal.is_some() {
+      self.ensure_stopped(signal_tid)?;
+    }
+
+    let stopped: Vec<Pid> = self
+      .threads
+      .borrow()
+      .iter()
+      .filter(|thread| thread.stopped)
+      .map(|thread| thread.tid)
+      .collect();
+
+    for tid in stopped {
+      let thread_signal = if tid == signal_tid { signal } else { None };
+      self.resume(tid, thread_signal)?;
+    }
+
+    Ok(())
+  }
+
+  /// Resumes stopped thread `tid` with **PTRACE_CONT**, or **PTRACE_SYSCALL**  
+  /// if system call tracing is enabled, delivering `signal` to it.
+  pub(crate) fn resume(&self, tid: Pid, signal: Option<Signal>) -> Result<()> {
+    self.ensure_stopped(tid)?;
+    // Thread can exit in the meantime, which is reported by `wait_for_stop()`.
+    if self.syscall_tracing {
+      resume_with(tid, signal, ptrace::syscall)?;
+    } else {
+      resume_with(tid, signal, ptrace::cont)?;
+    }
+    // Exit of system call is not reported to threads resumed with PTRACE_CONT.
+    let syscall_tracing = self.syscall_tracing;
+    self.update_thread(tid, |thread| {
+      thread.stopped = false;
+      thread.in_syscall &= syscall_tracing;
+    });
+    Ok(())
+  }
+
+  /// Starts tracing thread `tid` of process `pid`, attached automatically  
+  /// after it was created by one of traced threads. Its initial stop  
+  /// is reported by `wait_for_stop()` afterwards.
+  pub(crate) fn add_thread(&self, tid: Pid, pid: Pid) {
+    if !self.threads.borrow().iter().any(|thread| thread.tid == tid) {
+      self.threads.borrow_mut().push(TracedThread {
+        tid,
+        pid,
+        stopped: false,
+        in_syscall: false,
+      });
+    }
+  }
+
+  /// Starts tracing child process `pid`, attached automatically after  
+  /// it was created by one of traced threads.
+  pub(crate) fn add_process(&self, pid: Pid) {
+    if !self.processes.borrow().contains(&pid) {
+      self.processes.borrow_mut().push(pid);
+    }
+    self.add_thread(pid, pid);
+  }
+
+  /// Stops tracing process `pid` which exited.
+  pub(crate) fn remove_process(&mut self, pid: Pid) {
+    self.processes.borrow_mut().retain(|process| *process != pid);
+    self.threads.borrow_mut().retain(|thread| thread.pid != pid);
+  }
+
+  /// Returns process id of traced thread `tid`.
+  pub(crate) fn thread_pid(&self, tid: Pid) -> Option<Pid> {
+    self
+      .threads
+      .borrow()
+      .iter()
+      .find(|thread| thread.tid == tid)
+      .map(|thread| thread.pid)
+  }
+
+  /// Returns **true** if `pid` is id of one of traced processes.
+  pub(crate) fn is_traced_process(&self, pid: Pid) -> bool {
+    self.processes.borrow().contains(&pid)
+  }
+
+  /// Updates thread list after process `pid` executed new program. Other threads  
+  /// of the process are gone and the thread which called **execve(2)** took over  
+  /// process id, so the main thread is the only one left. Name and memory regions  
+  /// of the process which was attached to are refreshed too.
+  pub(crate) fn handle_exec(&mut self, pid: Pid) {
+    self.threads.borrow_mut().retain(|thread| thread.pid != pid);
+    // Thread is stopped inside execve(2), its exit is reported
+    // if the thread is resumed with PTRACE_SYSCALL.
+    self.threads.borrow_mut().push(TracedThread {
+      tid: pid,
+      pid,
+      stopped: true,
+      in_syscall: true,
+    });
+
+    if pid == self.process.get_pid() {
+      self.process.refresh_after_exec();
+    }
+  }
+
+  fn detach_all(&mut self) -> Result<()> {
+    if self.detached {
+      return Ok(());
+    }
+    self.detached = true;
+
+    self.stop_all()?;
+    let threads: Vec<TracedThread> = self.threads.borrow_mut().drain(..).collect();
+    for thread in threads {
+      match ptrace::detach(thread.tid, None) {
+        Ok(()) | Err(nix::Error::Sys(Errno::ESRCH)) => {}
+        Err(error) => {
+          return Err(system_call_error(
+            thread.tid,
+            error,
+            format!("Could not detach from thread {}", thread.tid),
+          ))
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl Deref for TracedProcess {
+  type Target = Process;
+
+  fn deref(&self) -> &Process {
+    &self.process
+  }
+}
+
+impl DerefMut for TracedProcess {
+  fn deref_mut(&mut self) -> &mut Process {
+    &mut self.process
+  }
+}
+
+impl Drop for TracedProcess {
+  fn drop(&mut self) {
+    let _ = self.detach_all();
+  }
+}
+
+/// Sends **PTRACE_INTERRUPT** to seized thread `tid`.  
+/// It is not wrapped by `nix` crate, so `libc` is called directly.
+pub(crate) fn interrupt(tid: Pid) -> Result<()> {
+  let result = unsafe {
+    libc::ptrace(
+      libc::PTRACE_INTERRUPT,
+      tid.as_raw(),
+      ptr::null_mut::<libc::c_void>(),
+      ptr::null_mut::<libc::c_void>(),
+    )
+  };
+
+  match Errno::result(result) {
+    Ok(_) | Err(nix::Error::Sys(Errno::ESRCH)) => Ok(()),
+    Err(error) => Err(system_call_error(
+      tid,
+      error,
+      format!("Could not interrupt thread {}", tid),
+    )),
+  }
+}
+
+/// Waits until interrupted thread `tid` reports ptrace-stop and returns **true**,  
+/// or **false** if thread exited instead. Signals delivered to the thread  
+/// in the meantime are passed to it, so they are not lost. Ids of threads  
+/// and processes it created in the meantime are added to `created`, together  
+/// with **PTRACE_EVENT_CLONE**, **PTRACE_EVENT_FORK** or **PTRACE_EVENT_VFORK**.
+pub(crate) fn wait_for_interrupt(tid: Pid, created: &mut Vec<(i32, Pid)>) -> Result<bool> {
+  loop {
+    let status = match waitpid(tid, Some(WaitPidFlag::__WALL)) {
+      Ok(status) => status,
+      Err(nix::Error::Sys(Errno::ECHILD)) => return Ok(false),
+      Err(error) => return Err(anyhow!("Could not wait for thread {} ({}).", tid, error)),
+    };
+
+    match status {
+      status if is_interrupt_stop(status) => return Ok(true),
+      WaitStatus::Exited(..) | WaitStatus::Signaled(..) => return Ok(false),
+      // Signal-delivery-stop, interrupt stop will be reported after the signal.
+      WaitStatus::Stopped(_, signal) => resume_with(tid, Some(signal), ptrace::cont)?,
+      WaitStatus::PtraceEvent(_, _, event)
+        if event == libc::PTRACE_EVENT_CLONE
+          || event == libc::PTRACE_EVENT_FORK
+          || event == libc::PTRACE_EVENT_VFORK =>
+      {
+        if let Ok(new_tid) = ptrace::getevent(tid) {
+          created.push((event, Pid::from_raw(new_tid as i32)));
+        }
+        resume_with(tid, None, ptrace::cont)?
+      }
+      // Syscall-stop which was pending when the thread was interrupted
+      // takes the interrupt over, so the thread is interrupted again.
+      WaitStatus::PtraceSyscall(_) => {
+        resume_with(tid, None, ptrace::cont)?;
+        interrupt(tid)?
+      }
+      _ => resume_with(tid, None, ptrace::cont)?,
+    }
+  }
+}
+
+/// Blocks until any child of the calling process has status to report,  
+/// without consuming it (**waitid(2)** with **WNOWAIT**).
+fn wait_for_child_status() -> nix::Result<()> {
+  loop {
+    let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+    let result = unsafe {
+      libc::waitid(
+        libc::P_ALL,
+        0,
+        &mut info,
+        libc::WEXITED | libc::WSTOPPED | libc::WNOWAIT | libc::__WALL,
+      )
+    };
+
+    match Errno::result(result) {
+      Err(nix::Error::Sys(Errno::EINTR)) => continue,
+      result => return result.map(drop),
+    }
+  }
+}
+
+/// Returns **true** if `status` is a **PTRACE_EVENT_STOP**, reported for  
+/// **PTRACE_INTERRUPT**, group-stop and initial stop of new threads.
+pub(crate) fn is_interrupt_stop(status: WaitStatus) -> bool {
+  matches!(
+    status,
+    WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_STOP)
+  )
+}
+
+/// Resumes stopped thread `tid` with ptrace `request`, delivering `signal` to it.
+fn resume_with(
+  tid: Pid,
+  signal: Option<Signal>,
+  request: fn(Pid, Option<Signal>) -> nix::Result<()>,
+) -> Result<()> {
+  match request(tid, signal) {
+    Ok(()) | Err(nix::Error::Sys(Errno::ESRCH)) => Ok(()),
+    Err(error) => Err(system_call_error(
+      tid,
+      error,
+      format!("Could not resume thread {}", tid),
+    )),
+  }
+}
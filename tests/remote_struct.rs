@@ -0,0 +1,83 @@
+//! Tests of `#[derive(RemoteStruct)]` against memory of `MockMemory`.
+#![cfg(all(feature = "derive", feature = "testing"))]
+
+extern crate trickster;
+
+use trickster::{MockMemory, Process, RemoteStruct};
+
+#[derive(RemoteStruct, Debug, PartialEq)]
+struct Player {
+  health: i32,
+  team: u8,
+  speed: f32,
+  #[remote(offset = 0x40)]
+  position: [f32; 3],
+  alive: u8,
+  score: u64,
+}
+
+#[derive(RemoteStruct, Debug, PartialEq)]
+struct Pair(u16, u64);
+
+/// Returns process with `length` bytes of writable memory filled with `0xAA` at `0x601000`.
+fn process_with_data(length: usize) -> Process {
+  MockMemory::new()
+    .region(0x601000, "rw-p", None, vec![0xAA; length])
+    .into_process("game")
+    .unwrap()
+}
+
+#[test]
+fn lays_out_fields_like_c() {
+  assert_eq!(Pair::field_offsets(), vec![0, 8]);
+  assert_eq!(Pair::remote_size(), 16);
+}
+
+#[test]
+fn continues_after_field_with_offset() {
+  assert_eq!(Player::field_offsets(), vec![0, 4, 8, 0x40, 0x4c, 0x50]);
+  assert_eq!(Player::remote_size(), 0x58);
+}
+
+#[test]
+fn reads_and_writes_structures() {
+  let process = process_with_data(0x100);
+  let player = Player {
+    health: 100,
+    team: 2,
+    speed: 1.5,
+    position: [12.5, 0.0, -3.25],
+    alive: 1,
+    score: 1337,
+  };
+
+  player.write_to(&process, 0x601010).unwrap();
+  assert_eq!(Player::read_from(&process, 0x601010).unwrap(), player);
+  assert_eq!(process.read::<i32>(0x601010).unwrap(), 100);
+  assert_eq!(
+    process.read::<[f32; 3]>(0x601050).unwrap(),
+    [12.5, 0.0, -3.25]
+  );
+  assert_eq!(process.read::<u64>(0x601060).unwrap(), 1337);
+
+  // Bytes between fields are not written.
+  assert_eq!(process.read::<[u8; 3]>(0x601015).unwrap(), [0xAA; 3]);
+  assert_eq!(
+    process.read_memory_bytes(0x60101c, 0x34).unwrap(),
+    vec![0xAA; 0x34]
+  );
+  assert_eq!(process.read::<[u8; 3]>(0x60105d).unwrap(), [0xAA; 3]);
+
+  let pair = Pair(7, u64::MAX);
+  pair.write_to(&process, 0x6010a0).unwrap();
+  assert_eq!(Pair::read_from(&process, 0x6010a0).unwrap(), pair);
+  assert_eq!(process.read::<[u8; 6]>(0x6010a2).unwrap(), [0xAA; 6]);
+}
+
+#[test]
+fn fails_to_read_past_memory() {
+  let process = process_with_data(0x20);
+
+  assert!(Player::read_from(&process, 0x601000).is_err());
+  assert!(Pair::read_from(&process, 0x601010).is_ok());
+}
@@ -0,0 +1,397 @@
+//! Integration tests running against target program of `TargetFixture`.
+#![cfg(feature = "testing")]
+
+extern crate libc;
+extern crate nix;
+extern crate trickster;
+
+use nix::sys::signal::Signal;
+use nix::sys::wait::WaitStatus;
+use std::env;
+use std::fs;
+use std::thread;
+use trickster::testing::TargetFixture;
+use trickster::{
+  DumpProcess, MemorySource, PatchSet, Process, RemoteArg, ScanRefinement, ScanSession, ScanValue,
+  Signature, TraceEvent,
+};
+
+/// Name of the target program, as it appears in `/proc/\[pid\]/comm` and maps.
+const TARGET_NAME: &str = "fixture_target";
+
+#[test]
+fn names_process_the_same_way_in_every_constructor() {
+  let fixture = TargetFixture::spawn().unwrap();
+
+  assert_eq!(fixture.process().get_name(), TARGET_NAME);
+  assert_eq!(
+    Process::from_pid(fixture.pid()).unwrap().get_name(),
+    TARGET_NAME
+  );
+  assert_eq!(Process::new(TARGET_NAME).unwrap().get_name(), TARGET_NAME);
+}
+
+#[test]
+fn reads_and_writes_variables() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let integer = fixture.address("integer").unwrap();
+  let float = fixture.address("float").unwrap();
+  let string = fixture.address("string").unwrap();
+
+  let process = fixture.process();
+  assert_eq!(
+    process.read::<u32>(integer).unwrap(),
+    TargetFixture::INTEGER
+  );
+  assert_eq!(process.read::<f32>(float).unwrap(), TargetFixture::FLOAT);
+  assert_eq!(
+    process.read_memory_bytes(string, TargetFixture::STRING.len()).unwrap(),
+    TargetFixture::STRING.as_bytes()
+  );
+
+  let pointer = fixture.process().read::<usize>(fixture.address("pointer").unwrap()).unwrap();
+  assert_eq!(pointer + 0x10, fixture.address("heap_value").unwrap());
+  assert_eq!(
+    fixture.process().read::<u32>(pointer + 0x10).unwrap(),
+    TargetFixture::HEAP_VALUE
+  );
+
+  fixture.process().write::<u32>(integer, &10).unwrap();
+  fixture.process().write::<f32>(float, &2.5).unwrap();
+  fixture.process().write_memory_bytes(string, b"cheat\0").unwrap();
+  assert_eq!(fixture.query("integer").unwrap(), "10");
+  assert_eq!(fixture.query("float").unwrap(), "2.5");
+  assert_eq!(fixture.query("string").unwrap(), "cheat");
+}
+
+#[test]
+fn scans_signature() {
+  let fixture = TargetFixture::spawn().unwrap();
+  let address = fixture.address("signature").unwrap();
+  let signature = Signature::new("DE AD BE EF ?? 37 C0 DE").unwrap();
+
+  let process = fixture.process();
+  assert!(process.scan(&signature).unwrap().contains(&address));
+  assert_eq!(
+    process.scan_module(TARGET_NAME, &signature).unwrap(),
+    vec![address]
+  );
+  assert_eq!(
+    process.scan_module_first(TARGET_NAME, &signature).unwrap(),
+    address
+  );
+  assert!(process
+    .find_bytes(
+      &TargetFixture::SIGNATURE,
+      process.regions().unwrap().name(TARGET_NAME)
+    )
+    .unwrap()
+    .contains(&address));
+}
+
+#[test]
+fn refines_scan_session() {
+  let fixture = TargetFixture::spawn().unwrap();
+  let integer = fixture.address("integer").unwrap();
+  let process = fixture.process();
+
+  let mut session =
+    ScanSession::new(process, &ScanValue::I32(TargetFixture::INTEGER as i32)).unwrap();
+  assert!(session.addresses().contains(&integer));
+
+  process.write::<u32>(integer, &(TargetFixture::INTEGER + 5)).unwrap();
+  session.refine(process, &ScanRefinement::Increased).unwrap();
+  assert!(session.addresses().contains(&integer));
+  session
+    .refine(
+      process,
+      &ScanRefinement::Exact(ScanValue::I32(TargetFixture::INTEGER as i32 + 5)),
+    )
+    .unwrap();
+  assert!(session.addresses().contains(&integer));
+
+  process.write::<u32>(integer, &1).unwrap();
+  session.refine(process, &ScanRefinement::Unchanged).unwrap();
+  assert!(!session.addresses().contains(&integer));
+}
+
+#[test]
+fn reverts_patches() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let integer = fixture.address("integer").unwrap();
+
+  {
+    let mut patches = PatchSet::new(fixture.process());
+    let first = patches.patch(integer, &1u32.to_ne_bytes()).unwrap();
+    let second = patches.patch(integer + 2, &[0xff, 0xff]).unwrap();
+    assert_eq!(patches.ids(), vec![first, second]);
+
+    // Bytes covered by the later patch stay patched until it is reverted too.
+    patches.revert(first).unwrap();
+    assert_eq!(
+      fixture.process().read::<u32>(integer).unwrap(),
+      (TargetFixture::INTEGER & 0xffff) | 0xffff_0000
+    );
+    patches.revert(second).unwrap();
+    assert_eq!(
+      fixture.process().read::<u32>(integer).unwrap(),
+      TargetFixture::INTEGER
+    );
+    assert!(patches.revert(second).is_err());
+
+    patches.patch(integer, &2u32.to_ne_bytes()).unwrap();
+    patches.patch(integer, &3u32.to_ne_bytes()).unwrap();
+  }
+  assert_eq!(
+    fixture.query("integer").unwrap(),
+    TargetFixture::INTEGER.to_string()
+  );
+}
+
+#[test]
+fn hooks_got() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let process = fixture.process();
+
+  // The target created its threads already, so the hooked function is not called anymore.
+  let hook = process.hook_got(TARGET_NAME, "pthread_create", 0xdead_0000).unwrap();
+  assert_eq!(hook.symbol(), "pthread_create");
+  assert!(!hook.slots().is_empty());
+  for slot in hook.slots() {
+    assert_eq!(process.read::<usize>(slot).unwrap(), 0xdead_0000);
+  }
+  let slots = hook.slots();
+  let original = hook.original();
+  hook.unhook(process).unwrap();
+  assert_eq!(process.read::<usize>(slots[0]).unwrap(), original);
+
+  assert!(process.hook_got(TARGET_NAME, "not_imported_symbol", 0).is_err());
+  assert_eq!(
+    fixture.query("integer").unwrap(),
+    TargetFixture::INTEGER.to_string()
+  );
+}
+
+#[test]
+fn resolves_symbols() {
+  let fixture = TargetFixture::spawn().unwrap();
+  let process = fixture.process();
+
+  assert_eq!(
+    process.resolve_symbol(TARGET_NAME, "TRICKSTER_INTEGER").unwrap(),
+    fixture.address("integer").unwrap()
+  );
+  assert_eq!(
+    process.resolve_symbol(TARGET_NAME, "TRICKSTER_SIGNATURE").unwrap(),
+    fixture.address("signature").unwrap()
+  );
+  assert!(process.resolve_symbol(TARGET_NAME, "not_defined_symbol").is_err());
+  assert!(process.resolve_symbol("not_loaded.so", "malloc").is_err());
+}
+
+#[test]
+fn computes_load_bias() {
+  let fixture = TargetFixture::spawn().unwrap();
+  let process = fixture.process();
+
+  // Both the target and libc are position independent, so their first
+  // loadable segment has zero address and they are biased by their base.
+  for name in &[TARGET_NAME, "libc.so.6"] {
+    let module = process.module(name).unwrap();
+    assert_eq!(module.load_bias().unwrap(), module.base());
+  }
+  assert!(process
+    .module(TARGET_NAME)
+    .unwrap()
+    .contains(fixture.address("integer").unwrap()));
+}
+
+#[test]
+fn symbolicates_addresses() {
+  let fixture = TargetFixture::spawn().unwrap();
+  let integer = fixture.address("integer").unwrap();
+  let process = fixture.process();
+
+  let info = process.symbolicate(integer + 2).unwrap();
+  assert_eq!(info.module, TARGET_NAME);
+  assert_eq!(info.symbol.as_deref(), Some("TRICKSTER_INTEGER"));
+  assert_eq!(info.offset, 2);
+  assert_eq!(info.to_string(), "fixture_target!TRICKSTER_INTEGER+0x2");
+
+  let heap_value = fixture.address("heap_value").unwrap();
+  assert!(process.symbolicate(heap_value).is_err());
+}
+
+#[test]
+fn attaches_steps_and_detaches() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let pid = fixture.pid();
+
+  let mut traced = fixture.process().attach().unwrap();
+  let threads = traced.traced_threads();
+  assert_eq!(threads.len(), 3);
+  assert!(threads.contains(&pid));
+  assert!(threads.iter().all(|tid| traced.is_stopped(*tid)));
+
+  thread::scope(|scope| {
+    // The main thread waits for input, so the step completes once the query is sent.
+    let query = scope.spawn(|| fixture.query("integer"));
+    traced.step(pid).unwrap();
+    assert_eq!(
+      traced.wait_for_stop().unwrap(),
+      WaitStatus::Stopped(pid, Signal::SIGTRAP)
+    );
+    assert!(traced.is_stopped(pid));
+
+    traced.detach().unwrap();
+    assert_eq!(
+      query.join().unwrap().unwrap(),
+      TargetFixture::INTEGER.to_string()
+    );
+  });
+}
+
+#[test]
+fn decodes_system_calls() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let mut traced = fixture.process().attach().unwrap();
+  traced.set_syscall_tracing(true);
+  traced.set_syscall_filter(Some(&[libc::SYS_write]));
+
+  thread::scope(|scope| {
+    let query = scope.spawn(|| fixture.query("integer"));
+
+    let mut events = traced.events();
+    let decoded = loop {
+      if let TraceEvent::SyscallEnter { number, args, .. } = events.next().unwrap().unwrap() {
+        break events.traced().decode_syscall(number, &args);
+      }
+    };
+    assert_eq!(decoded.name, Some("write"));
+    assert_eq!(decoded.to_string(), "write(1, \"1337\\n\", 5)");
+
+    traced.detach().unwrap();
+    assert_eq!(
+      query.join().unwrap().unwrap(),
+      TargetFixture::INTEGER.to_string()
+    );
+  });
+}
+
+#[test]
+fn calls_remote_functions() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let getpid = fixture.process().resolve_symbol("libc.so.6", "getpid").unwrap();
+  let atoi = fixture.process().resolve_symbol("libc.so.6", "atoi").unwrap();
+
+  let traced = fixture.process().attach().unwrap();
+  assert_eq!(
+    traced.call_function(getpid, &[]).unwrap(),
+    fixture.pid().as_raw() as u64
+  );
+  assert_eq!(
+    traced.call_function(atoi, &[RemoteArg::Str("4242")]).unwrap() as u32,
+    4242
+  );
+  traced.detach().unwrap();
+
+  // Registers of the main thread are restored, so it keeps answering queries.
+  assert_eq!(
+    fixture.query("integer").unwrap(),
+    TargetFixture::INTEGER.to_string()
+  );
+}
+
+#[cfg(all(feature = "inline-hook", target_arch = "x86_64"))]
+#[test]
+fn hooks_and_unhooks_inline() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let atoi = fixture.process().resolve_symbol("libc.so.6", "atoi").unwrap();
+  let getpid = fixture.process().resolve_symbol("libc.so.6", "getpid").unwrap();
+  let pid = fixture.pid().as_raw() as u64;
+
+  let traced = fixture.process().attach().unwrap();
+  let original = traced.read_memory_bytes(atoi, 16).unwrap();
+  let hook = traced.inline_hook(atoi, getpid).unwrap();
+  assert!(hook.is_enabled());
+  assert_eq!(hook.target(), atoi);
+  assert_ne!(traced.read_memory_bytes(atoi, 16).unwrap(), original);
+  assert_eq!(
+    traced
+      .read_memory_bytes(atoi + hook.patch_size(), 16 - hook.patch_size())
+      .unwrap(),
+    &original[hook.patch_size()..]
+  );
+
+  // Hooked function jumps to detour, while trampoline runs the displaced instructions.
+  let number = [RemoteArg::Str("4242")];
+  assert_eq!(traced.call_function(atoi, &number).unwrap(), pid);
+  assert_eq!(
+    traced.call_function(hook.trampoline(), &number).unwrap() as u32,
+    4242
+  );
+
+  let trampoline = hook.trampoline();
+  hook.unhook().unwrap();
+  assert_eq!(traced.read_memory_bytes(atoi, 16).unwrap(), original);
+  assert_eq!(traced.call_function(atoi, &number).unwrap() as u32, 4242);
+  assert!(traced.read_memory_bytes(trampoline, 1).is_err());
+  traced.detach().unwrap();
+
+  assert_eq!(
+    fixture.query("integer").unwrap(),
+    TargetFixture::INTEGER.to_string()
+  );
+}
+
+/// Returns value of heap object the target points to, read from any memory source.
+fn read_heap_value<M: MemorySource>(source: &M, pointer: usize) -> u32 {
+  let object = source.read::<usize>(pointer).unwrap();
+  assert!(source.region_containing(object).unwrap().is_some());
+  source.read::<u32>(object + 0x10).unwrap()
+}
+
+#[test]
+fn loads_written_core_dump() {
+  let mut fixture = TargetFixture::spawn().unwrap();
+  let integer = fixture.address("integer").unwrap();
+  let signature = fixture.address("signature").unwrap();
+  let heap_value = fixture.address("heap_value").unwrap();
+  fixture.process().write::<u32>(integer, &77).unwrap();
+
+  let path = env::temp_dir().join(format!("trickster-test-{}.core", fixture.pid()));
+  let traced = fixture.process().attach().unwrap();
+  let written = traced.write_core_dump(&path);
+  traced.detach().unwrap();
+  written.unwrap();
+
+  let dump = DumpProcess::from_core_file(&path);
+  fs::remove_file(&path).unwrap();
+  let dump = dump.unwrap();
+  assert_eq!(dump.get_name(), TARGET_NAME);
+  assert_eq!(dump.read::<u32>(integer).unwrap(), 77);
+  assert_eq!(
+    dump.read::<u32>(heap_value).unwrap(),
+    TargetFixture::HEAP_VALUE
+  );
+  assert_eq!(
+    dump
+      .scan_module(
+        TARGET_NAME,
+        &Signature::new("DE AD BE EF 13 37 C0 DE").unwrap()
+      )
+      .unwrap(),
+    vec![signature]
+  );
+  assert!(dump.region_find_first_by_name("[heap]", None).is_ok());
+  assert!(dump.write::<u32>(integer, &1).is_err());
+
+  // Memory of the live process changes, while the dump keeps captured values.
+  let pointer = fixture.address("pointer").unwrap();
+  fixture.process().write::<u32>(heap_value, &5).unwrap();
+  assert_eq!(read_heap_value(fixture.process(), pointer), 5);
+  assert_eq!(read_heap_value(&dump, pointer), TargetFixture::HEAP_VALUE);
+
+  // The target keeps running after it was detached.
+  assert_eq!(fixture.query("integer").unwrap(), "77");
+}
@@ -0,0 +1,144 @@
+//! Derive macros of [trickster](https://docs.rs/trickster) library,  
+//! enabled with its `derive` feature. Use them through `trickster` crate  
+//! re-exports instead of depending on this crate directly.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitInt};
+
+/// Derives `trickster::RemoteStruct` for structs which fields implement `Pod` trait.  
+/// Fields are laid out like in C structure (every field follows the previous one,  
+/// aligned to its type), unless offset of the field in remote structure is given  
+/// with `#[remote(offset = 0x10)]` attribute. Fields following such field continue  
+/// after it.
+///
+/// # Examples
+/// ```ignore
+/// #[derive(RemoteStruct)]
+/// struct Player {
+///   health: i32,
+///   #[remote(offset = 0x40)]
+///   position: [f32; 3],
+///   team: u8,
+/// }
+/// ```
+#[proc_macro_derive(RemoteStruct, attributes(remote))]
+pub fn derive_remote_struct(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  match expand_remote_struct(&input) {
+    Ok(expanded) => expanded.into(),
+    Err(error) => error.to_compile_error().into(),
+  }
+}
+
+fn expand_remote_struct(input: &DeriveInput) -> syn::Result<TokenStream2> {
+  let fields = match &input.data {
+    Data::Struct(data) => &data.fields,
+    _ => {
+      return Err(syn::Error::new(
+        input.span(),
+        "RemoteStruct can be derived only for structs",
+      ))
+    }
+  };
+  let fields: Vec<&syn::Field> = match fields {
+    Fields::Named(named) => named.named.iter().collect(),
+    Fields::Unnamed(unnamed) => unnamed.unnamed.iter().collect(),
+    Fields::Unit => Vec::new(),
+  };
+
+  let mut members = Vec::new();
+  let mut types = Vec::new();
+  let mut offsets = Vec::new();
+  for (index, field) in fields.iter().enumerate() {
+    let ty = &field.ty;
+    let offset = match field_offset(field)? {
+      Some(offset) => quote!(#offset),
+      None => quote!(::trickster::__private::align_up(
+        end,
+        ::std::mem::align_of::<#ty>()
+      )),
+    };
+    members.push(match &field.ident {
+      Some(ident) => syn::Member::Named(ident.clone()),
+      None => syn::Member::Unnamed(index.into()),
+    });
+    types.push(ty);
+    offsets.push(offset);
+  }
+  let indices: Vec<usize> = (0..fields.len()).collect();
+  let count = fields.len();
+
+  let name = &input.ident;
+  let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+  Ok(quote! {
+    impl #impl_generics ::trickster::RemoteStruct for #name #ty_generics #where_clause {
+      #[allow(unused_assignments, unused_mut, unused_variables)]
+      fn field_offsets() -> ::std::vec::Vec<usize> {
+        let mut offsets = ::std::vec::Vec::with_capacity(#count);
+        let mut end = 0usize;
+        #(
+          let offset: usize = #offsets;
+          offsets.push(offset);
+          end = offset + ::std::mem::size_of::<#types>();
+        )*
+        offsets
+      }
+
+      #[allow(unused_mut, unused_variables)]
+      fn remote_size() -> usize {
+        let offsets = Self::field_offsets();
+        let mut size = 0usize;
+        #(
+          size = size.max(offsets[#indices] + ::std::mem::size_of::<#types>());
+        )*
+        size
+      }
+
+      #[allow(unused_variables)]
+      fn from_remote_bytes(bytes: &[u8]) -> Self {
+        let offsets = Self::field_offsets();
+        Self {
+          #(
+            #members: ::trickster::__private::read_field::<#types>(bytes, offsets[#indices]),
+          )*
+        }
+      }
+
+      #[allow(unused_variables)]
+      fn remote_fields(&self) -> ::std::vec::Vec<(usize, &[u8])> {
+        let offsets = Self::field_offsets();
+        ::std::vec![
+          #(
+            (offsets[#indices], ::trickster::__private::field_bytes::<#types>(&self.#members)),
+          )*
+        ]
+      }
+    }
+  })
+}
+
+/// Parses offset of `#[remote(offset = ...)]` attribute of `field`, if there is one.
+fn field_offset(field: &syn::Field) -> syn::Result<Option<usize>> {
+  let mut offset = None;
+  for attribute in field.attrs.iter().filter(|attribute| attribute.path().is_ident("remote")) {
+    attribute.parse_nested_meta(|meta| {
+      if meta.path.is_ident("offset") {
+        let value: LitInt = meta.value()?.parse()?;
+        offset = Some(value.base10_parse::<usize>()?);
+        Ok(())
+      } else {
+        Err(meta.error("unsupported remote attribute, expected `offset`"))
+      }
+    })?;
+  }
+  Ok(offset)
+}